@@ -31,4 +31,160 @@ pub enum FluterByError {
     
     #[msg("Escrow has not expired yet")]
     EscrowNotExpired,
-}
\ No newline at end of file
+    
+    #[msg("Invalid escrow wallet count")]
+    InvalidWalletCount,
+    
+    #[msg("Escrow wallets still hold rewards")]
+    EscrowNotEmpty,
+    
+    #[msg("Invalid expiry")]
+    InvalidExpiry,
+    
+    #[msg("Reward is below the requested minimum")]
+    SlippageExceeded,
+    
+    #[msg("Token does not match the escrow's token")]
+    TokenMismatch,
+    
+    #[msg("Invalid new minter")]
+    InvalidNewMinter,
+    
+    #[msg("Redemption fee is too high")]
+    FeeTooHigh,
+    
+    #[msg("Invalid treasury account")]
+    InvalidTreasury,
+    
+    #[msg("A redemption is already in progress")]
+    RedemptionInProgress,
+    
+    #[msg("Token supply does not match the mint's supply")]
+    TokenSupplyMismatch,
+    
+    #[msg("Vesting schedule is invalid")]
+    InvalidVestingSchedule,
+    
+    #[msg("No rewards have vested yet")]
+    RewardsNotVested,
+    
+    #[msg("Program is paused")]
+    ProgramPaused,
+    
+    #[msg("Unauthorized: Only the config admin can perform this action")]
+    UnauthorizedAdmin,
+    
+    #[msg("Cannot cancel: redemptions have already taken place")]
+    RedemptionsInProgress,
+    
+    #[msg("Redeemer is not on the escrow's whitelist")]
+    RedeemerNotAuthorized,
+    
+    #[msg("Invalid reward mode")]
+    InvalidRewardMode,
+    
+    #[msg("Escrow wallet is not owned by the escrow lock account")]
+    InvalidEscrowWalletAuthority,
+    
+    #[msg("This escrow caps redemptions per user; the user redemption account is required")]
+    UserRedemptionRequired,
+    
+    #[msg("Redemption would exceed the per-user cap for this escrow")]
+    UserCapExceeded,
+    
+    #[msg("Emergency withdrawals are only allowed while the program is paused")]
+    ProgramNotPaused,
+    
+    #[msg("Funds can only be returned to the reward token account the lock was funded from")]
+    InvalidRefundAccount,
+    
+    #[msg("An escrow already exists for this token and minter; close it before locking again")]
+    EscrowAlreadyExists,
+    
+    #[msg("Invalid reward track")]
+    InvalidRewardTrack,
+    
+    #[msg("Every reward track's accounts must be passed after the escrow wallets")]
+    RewardTracksRequired,
+    
+    #[msg("Main tokens can only be sent to the escrow's incinerator account")]
+    InvalidIncinerator,
+    
+    #[msg("Redemption would pay more than the escrow's per-redemption reward cap")]
+    RewardCapExceeded,
+    
+    #[msg("Escrow wallet balances don't match the recorded remaining reward value")]
+    SolvencyMismatch,
+    
+    #[msg("Escrow wallet balances don't add up to the reward value being activated")]
+    FundingMismatch,
+    
+    #[msg("Wallet weights must give one weight per escrow wallet and sum to 10000 basis points")]
+    InvalidWeights,
+    
+    #[msg("The escrow cannot move to that status from its current one")]
+    InvalidStatusTransition,
+    
+    #[msg("Invalid receipt mint or receipt token account")]
+    InvalidReceiptMint,
+    
+    #[msg("The burn is too small to earn any reward")]
+    RewardRoundsToZero,
+    
+    #[msg("This user redeemed too recently; wait for the escrow's cooldown to pass")]
+    CooldownActive,
+    
+    #[msg("Redemption cooldown must not be negative")]
+    InvalidCooldown,
+    
+    #[msg("Dust can only be reclaimed once no rewards remain for holders")]
+    RewardsStillRemaining,
+    
+    #[msg("Escrow is no longer active: it was drained, withdrawn or cancelled")]
+    EscrowInactive,
+    
+    #[msg("An escrow wallet hasn't been initialized; call initialize_escrow_wallet for it before locking")]
+    EscrowWalletNotInitialized,
+    
+    #[msg("This escrow keeps a redemption log; redeem with redeem_rewards and pass the log")]
+    RedemptionLogRequired,
+    
+    #[msg("Clawback needs distinct admin and minter signatures and a delegation of the amount to the escrow")]
+    ClawbackNotAuthorized,
+    
+    #[msg("Invalid rounding mode")]
+    InvalidRoundingMode,
+    
+    #[msg("Invalid expiry action, or the escrow has already committed to burning its leftovers")]
+    InvalidExpiryAction,
+    
+    #[msg("This escrow burns its leftover rewards at expiry; settle it with settle_expired")]
+    LeftoversCommittedToBurn,
+    
+    #[msg("A batch must redeem from 1 to MAX_BATCH_REDEMPTIONS escrows, each with its full account set in order")]
+    InvalidBatch,
+    
+    #[msg("Redemptions from this escrow are paused by its minter")]
+    EscrowPaused,
+    
+    #[msg("Custom wallet amounts must each be greater than 0")]
+    InvalidWalletAmounts,
+    
+    #[msg("The lock expires sooner than the program's minimum lock duration allows")]
+    LockDurationTooShort,
+    
+    #[msg("An escrow wallet or reward account is frozen by the reward mint's freeze authority")]
+    AccountFrozen,
+    
+    #[msg("This escrow uses an older account layout; call migrate_escrow first")]
+    EscrowNeedsMigration,
+    
+    #[msg("This minter has created the most escrows the program allows")]
+    MinterEscrowLimitReached,
+    
+    #[msg("The rent recipient can't be the escrow being closed")]
+    InvalidRentRecipient,
+    
+    #[msg("This escrow mints claim receipts; redeem with redeem_rewards and pass the receipt accounts")]
+    ReceiptMintRequired,
+}
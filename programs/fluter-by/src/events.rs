@@ -7,6 +7,10 @@ pub struct FundsLocked {
     pub value: u64,
     pub expires_at: i64,
     pub timestamp: i64,
+    pub reward_per_wallet: u64,
+    pub reward_remainder: u64,
+    pub escrow_wallets: Vec<Pubkey>,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -14,7 +18,9 @@ pub struct EscrowExpired {
     pub mint: Pubkey,
     pub minter: Pubkey,
     pub total_value: u64,
+    pub remaining: u64,
     pub timestamp: i64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -24,6 +30,7 @@ pub struct FundsWithdrawn {
     pub amount: u64,
     pub wallet_index: u8,
     pub timestamp: i64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -32,8 +39,10 @@ pub struct RewardsRedeemed {
     pub user: Pubkey,
     pub tokens_burned: u64,
     pub rewards_received: u64,
+    pub fee: u64,
     pub remaining_rewards: u64,
     pub timestamp: i64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -41,5 +50,332 @@ pub struct ExpiredRewardsWithdrawn {
     pub token: Pubkey,
     pub minter: Pubkey,
     pub amount_withdrawn: u64,
+    pub remaining_reward_value: u64,
     pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EscrowWalletInitialized {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub escrow_wallet: Pubkey,
+    pub wallet_index: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowWalletFunded {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub escrow_wallet: Pubkey,
+    pub wallet_index: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RewardsToppedUp {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub amount: u64,
+    pub total_reward_value: u64,
+    pub remaining_rewards: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ExpiryExtended {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MinterAuthorityTransferred {
+    pub token: Pubkey,
+    pub old_minter: Pubkey,
+    pub new_minter: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MaxEscrowsPerMinterUpdated {
+    pub admin: Pubkey,
+    pub max_escrows_per_minter: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinLockDurationUpdated {
+    pub admin: Pubkey,
+    pub min_lock_duration: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockCancelled {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub amount_refunded: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DustReclaimed {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub amount_reclaimed: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EscrowWalletRotated {
+    pub token: Pubkey,
+    pub wallet_index: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RedeemerAuthorized {
+    pub escrow: Pubkey,
+    pub redeemer: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RedeemerRevoked {
+    pub escrow: Pubkey,
+    pub redeemer: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DecimalsMismatch {
+    pub token: Pubkey,
+    pub reward_token: Pubkey,
+    pub token_decimals: u8,
+    pub reward_token_decimals: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EmergencyWithdrawal {
+    pub token: Pubkey,
+    pub admin: Pubkey,
+    pub minter: Pubkey,
+    pub destination: Pubkey,
+    pub amount_withdrawn: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct Clawback {
+    pub token: Pubkey,
+    pub admin: Pubkey,
+    pub minter: Pubkey,
+    pub redeemer: Pubkey,
+    pub redeemer_reward_account: Pubkey,
+    pub escrow_wallet: Pubkey,
+    pub amount: u64,
+    pub remaining_rewards: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RewardTrackAdded {
+    pub token: Pubkey,
+    pub reward_token: Pubkey,
+    pub track_index: u8,
+    pub reward_value: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RewardTrackRedeemed {
+    pub token: Pubkey,
+    pub track_index: u8,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RewardTrackWithdrawn {
+    pub token: Pubkey,
+    pub reward_token: Pubkey,
+    pub track_index: u8,
+    pub amount_withdrawn: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MinterRegistered {
+    pub minter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowCreated {
+    pub minter: Pubkey,
+    pub token: Pubkey,
+    pub escrow: Pubkey,
+    pub escrow_id: u64,
+    pub escrow_number: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct IncineratorSet {
+    pub token: Pubkey,
+    pub incinerator: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FirstRedemption {
+    pub token: Pubkey,
+    pub created_at: i64,
+    pub seconds_since_lock: i64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ReceiptMintSet {
+    pub token: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RedemptionLogInitialized {
+    pub token: Pubkey,
+    pub redemption_log: Pubkey,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EscrowPauseUpdated {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ExpiryActionSet {
+    pub token: Pubkey,
+    pub expiry_action: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ExpiredRewardsBurned {
+    pub token: Pubkey,
+    pub minter: Pubkey,
+    pub caller: Pubkey,
+    pub amount_burned: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EscrowMigrated {
+    pub escrow: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RewardsSplit {
+    pub token: Pubkey,
+    pub user: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RoundingModeSet {
+    pub token: Pubkey,
+    pub rounding_mode: u8,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct FixedRateSet {
+    pub token: Pubkey,
+    pub mode: u8,
+    pub rate: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RedemptionCooldownSet {
+    pub token: Pubkey,
+    pub cooldown_secs: i64,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct EscrowWalletsResynced {
+    pub token: Pubkey,
+    pub old_escrow_wallets: Vec<Pubkey>,
+    pub new_escrow_wallets: Vec<Pubkey>,
+    pub timestamp: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct RemainingRewardReconciled {
+    pub token: Pubkey,
+    pub recorded_value: u64,
+    pub wallet_balance_total: u64,
+    pub timestamp: i64,
+    pub event_seq: u64,
 }
@@ -1,18 +1,66 @@
 use anchor_lang::prelude::*;
+use anchor_spl::memo;
 use anchor_spl::token;
+use anchor_spl::token_interface;
 use crate::state::*;
 use crate::error::FluterByError;
 use crate::events::*;
 
 pub fn initialize_escrow_wallet(
-    _ctx: Context<InitializeEscrowWallet>,
-    _token: Pubkey,
+    ctx: Context<InitializeEscrowWallet>,
+    token: Pubkey,
     wallet_index: u8,
 ) -> Result<()> {
+    // Validate the wallet index is one that a lock can reference
+    require!(
+        (1..=MAX_ESCROW_WALLET_COUNT).contains(&wallet_index),
+        FluterByError::InvalidEscrowWalletIndex
+    );
+    
     msg!("Initialized escrow wallet {}", wallet_index);
+    
+    emit!(EscrowWalletInitialized {
+        token,
+        minter: ctx.accounts.minter.key(),
+        escrow_wallet: ctx.accounts.escrow_wallet.key(),
+        wallet_index,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn batch_initialize_escrow_wallets(
+    ctx: Context<BatchInitializeEscrowWallets>,
+    token: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let minter = ctx.accounts.minter.key();
+    let escrow_wallets = [
+        ctx.accounts.escrow_wallet_1.key(),
+        ctx.accounts.escrow_wallet_2.key(),
+        ctx.accounts.escrow_wallet_3.key(),
+        ctx.accounts.escrow_wallet_4.key(),
+        ctx.accounts.escrow_wallet_5.key(),
+    ];
+    
+    for (i, escrow_wallet) in escrow_wallets.into_iter().enumerate() {
+        let wallet_index = i as u8 + 1;
+        msg!("Initialized escrow wallet {}", wallet_index);
+        
+        emit!(EscrowWalletInitialized {
+            token,
+            minter,
+            escrow_wallet,
+            wallet_index,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn lock_funds(
     ctx: Context<LockFunds>,
     token: Pubkey,
@@ -21,437 +69,4213 @@ pub fn lock_funds(
     reward_value: u64,
     token_supply: u64,
     expiry: i64,
+    params: LockParams,
 ) -> Result<()> {
-    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
-    let clock = Clock::get()?;
-    
-    // Validate reward value is greater than 0
+    process_lock_funds(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params, None)
+}
+
+/// Same as `lock_funds`, with the minter giving the exact amount for each of the 5
+/// escrow wallets. The amounts add up to the locked reward value and stay on the escrow
+/// as the wallets' weights.
+#[allow(clippy::too_many_arguments)]
+pub fn lock_funds_custom(
+    ctx: Context<LockFunds>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    amounts: [u64; 5],
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+) -> Result<()> {
+    // Validate every wallet gets something, so each one keeps a weight
     require!(
-        reward_value > 0,
-        FluterByError::InvalidDistributionAmount
+        amounts.iter().all(|amount| *amount > 0),
+        FluterByError::InvalidWalletAmounts
     );
     
-    // Validate token supply is greater than 0
+    // Validate no weights were given; the amounts already set each wallet's share
     require!(
-        token_supply > 0,
-        FluterByError::InvalidDistributionAmount
+        params.weights.is_none(),
+        FluterByError::InvalidWeights
     );
     
-    // Calculate reward per wallet (equal distribution across 5 wallets)
-    let reward_per_wallet = reward_value
-        .checked_div(5)
+    let reward_value = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
         .ok_or(FluterByError::DistributionCalculationOverflow)?;
     
-    // Validate that the division is clean (no remainder)
-    require!(
-        reward_per_wallet * 5 == reward_value,
-        FluterByError::InvalidDistributionAmount
-    );
+    process_lock_funds(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params, Some(amounts))
+}
+
+/// Locks `reward_value` across the 5 escrow wallets of `LockFunds`, split by the
+/// params' weights or, when given, by the exact `wallet_amounts` (which must add up to
+/// `reward_value`).
+#[allow(clippy::too_many_arguments)]
+fn process_lock_funds(
+    ctx: Context<LockFunds>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+    wallet_amounts: Option<[u64; 5]>,
+) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
     
-    // Validate minter matches the signer
+    // Validate the wallet count, if given, is the 5 named wallets this lock uses
     require!(
-        minter == ctx.accounts.minter.key(),
-        FluterByError::UnauthorizedMinter
+        params.wallet_count.unwrap_or(DEFAULT_ESCROW_WALLET_COUNT) == DEFAULT_ESCROW_WALLET_COUNT,
+        FluterByError::InvalidWalletCount
     );
     
-    // Transfer reward tokens from minter to each of the 5 escrow wallets
-    // Each wallet receives reward_per_wallet amount
-    msg!("Transferring {} tokens to each of 5 escrow wallets...", reward_per_wallet);
-    
-    // Transfer to wallet 1
-    let cpi_accounts_1 = token::Transfer {
-        from: ctx.accounts.minter_reward_account.to_account_info(),
-        to: ctx.accounts.escrow_wallet_1.to_account_info(),
-        authority: ctx.accounts.minter.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx_1 = CpiContext::new(cpi_program.clone(), cpi_accounts_1);
-    token::transfer(cpi_ctx_1, reward_per_wallet)?;
-    msg!("Transferred {} to wallet 1", reward_per_wallet);
-    
-    // Transfer to wallet 2
-    let cpi_accounts_2 = token::Transfer {
-        from: ctx.accounts.minter_reward_account.to_account_info(),
-        to: ctx.accounts.escrow_wallet_2.to_account_info(),
-        authority: ctx.accounts.minter.to_account_info(),
-    };
-    let cpi_ctx_2 = CpiContext::new(cpi_program.clone(), cpi_accounts_2);
-    token::transfer(cpi_ctx_2, reward_per_wallet)?;
-    msg!("Transferred {} to wallet 2", reward_per_wallet);
-    
-    // Transfer to wallet 3
-    let cpi_accounts_3 = token::Transfer {
-        from: ctx.accounts.minter_reward_account.to_account_info(),
-        to: ctx.accounts.escrow_wallet_3.to_account_info(),
-        authority: ctx.accounts.minter.to_account_info(),
-    };
-    let cpi_ctx_3 = CpiContext::new(cpi_program.clone(), cpi_accounts_3);
-    token::transfer(cpi_ctx_3, reward_per_wallet)?;
-    msg!("Transferred {} to wallet 3", reward_per_wallet);
-    
-    // Transfer to wallet 4
-    let cpi_accounts_4 = token::Transfer {
-        from: ctx.accounts.minter_reward_account.to_account_info(),
-        to: ctx.accounts.escrow_wallet_4.to_account_info(),
-        authority: ctx.accounts.minter.to_account_info(),
-    };
-    let cpi_ctx_4 = CpiContext::new(cpi_program.clone(), cpi_accounts_4);
-    token::transfer(cpi_ctx_4, reward_per_wallet)?;
-    msg!("Transferred {} to wallet 4", reward_per_wallet);
-    
-    // Transfer to wallet 5
-    let cpi_accounts_5 = token::Transfer {
-        from: ctx.accounts.minter_reward_account.to_account_info(),
-        to: ctx.accounts.escrow_wallet_5.to_account_info(),
-        authority: ctx.accounts.minter.to_account_info(),
-    };
-    let cpi_ctx_5 = CpiContext::new(cpi_program, cpi_accounts_5);
-    token::transfer(cpi_ctx_5, reward_per_wallet)?;
-    msg!("Transferred {} to wallet 5", reward_per_wallet);
+    validate_lock(escrow_lock_account, reward_value, ctx.accounts.minter_reward_account.amount, token_supply, ctx.accounts.token_mint.supply, minter, ctx.accounts.minter.key())?;
+    let terms = validate_lock_params(
+        params,
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.key()),
+        expiry,
+        &ctx.accounts.program_config,
+        clock.unix_timestamp,
+    )?;
+    let wallet_count = terms.wallet_count;
     
-    msg!("✅ All reward tokens transferred to escrow wallets!");
+    // Transfer reward tokens from minter to each of the 5 escrow wallets, split by weight
+    log_step!("Transferring {} tokens across 5 escrow wallets...", reward_value);
     
-    // Store the 5 escrow wallet addresses
-    let escrow_wallets = [
-        ctx.accounts.escrow_wallet_1.key(),
-        ctx.accounts.escrow_wallet_2.key(),
-        ctx.accounts.escrow_wallet_3.key(),
-        ctx.accounts.escrow_wallet_4.key(),
-        ctx.accounts.escrow_wallet_5.key(),
+    let wallets = [
+        ctx.accounts.escrow_wallet_1.to_account_info(),
+        ctx.accounts.escrow_wallet_2.to_account_info(),
+        ctx.accounts.escrow_wallet_3.to_account_info(),
+        ctx.accounts.escrow_wallet_4.to_account_info(),
+        ctx.accounts.escrow_wallet_5.to_account_info(),
     ];
     
-    // Initialize escrow lock account
-    escrow_lock_account.token = token;
-    escrow_lock_account.reward_token = reward_token;
-    escrow_lock_account.minter = minter;
-    escrow_lock_account.total_reward_value = reward_value;
-    escrow_lock_account.remaining_reward_value = reward_value;
-    escrow_lock_account.reward_per_wallet = reward_per_wallet;
-    escrow_lock_account.total_token_supply = token_supply;
-    escrow_lock_account.escrow_wallets = escrow_wallets;
-    escrow_lock_account.expires_at = expiry;
-    escrow_lock_account.created_at = clock.unix_timestamp;
-    escrow_lock_account.is_active = true;
-    
-    msg!("Token: {}", token);
-    msg!("Reward Token: {}", reward_token);
-    msg!("Total Reward Value: {}", reward_value);
-    msg!("Reward per wallet: {}", reward_per_wallet);
-    msg!("Token Supply: {}", token_supply);
-    msg!("Distribution across 5 wallets:");
-    msg!("  Wallet 1: {}", escrow_wallets[0]);
-    msg!("  Wallet 2: {}", escrow_wallets[1]);
-    msg!("  Wallet 3: {}", escrow_wallets[2]);
-    msg!("  Wallet 4: {}", escrow_wallets[3]);
-    msg!("  Wallet 5: {}", escrow_wallets[4]);
+    // Validate every wallet was set up before moving any tokens
+    for escrow_wallet in wallets.iter() {
+        validate_escrow_wallet_initialized(escrow_wallet, escrow_lock_account.key(), reward_token, &token::ID)?;
+    }
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let mut escrow_wallets = [Pubkey::default(); MAX_ESCROW_WALLET_COUNT as usize];
+    let mut wallet_bumps = [0u8; MAX_ESCROW_WALLET_COUNT as usize];
+    wallet_bumps[..5].copy_from_slice(&[
+        ctx.bumps.escrow_wallet_1,
+        ctx.bumps.escrow_wallet_2,
+        ctx.bumps.escrow_wallet_3,
+        ctx.bumps.escrow_wallet_4,
+        ctx.bumps.escrow_wallet_5,
+    ]);
     
-    emit!(FundsLocked {
-        mint: token,
-        minter,
-        value: reward_value,
-        expires_at: expiry,
-        timestamp: clock.unix_timestamp,
-    });
+    for (i, escrow_wallet) in wallets.into_iter().enumerate() {
+        let wallet_index = i as u8 + 1;
+        
+        // Validate slot i holds the PDA for seed index i + 1. The account seeds already
+        // tie each named wallet to its index; this keeps the stored order the draw logic
+        // relies on from depending on how the accounts struct is laid out.
+        let expected_wallet = Pubkey::create_program_address(
+            &[b"escrow_wallet", token.as_ref(), minter.as_ref(), &[wallet_index], &[wallet_bumps[i]]],
+            ctx.program_id,
+        )
+        .map_err(|_| FluterByError::InvalidEscrowWalletIndex)?;
+        require!(
+            escrow_wallet.key() == expected_wallet,
+            FluterByError::InvalidEscrowWalletIndex
+        );
+        
+        // Store the escrow wallet address
+        escrow_wallets[i] = escrow_wallet.key();
+        
+        let amount = match wallet_amounts {
+            Some(amounts) => amounts[i],
+            None => wallet_share(reward_value, &bps_weights(&terms.wallet_weights[..wallet_count as usize]), i as u64, 0)?,
+        };
+        transfer_to_escrow(
+            &cpi_program,
+            ctx.accounts.minter_reward_account.to_account_info(),
+            escrow_wallet,
+            ctx.accounts.minter.to_account_info(),
+            amount,
+        )?;
+        log_step!("Transferred {} to wallet {}", amount, wallet_index);
+    }
     
-    Ok(())
+    log_step!("✅ All reward tokens transferred to escrow wallets!");
+    log_step!("Distribution across 5 wallets:");
+    log_step!("  Wallet 1: {}", escrow_wallets[0]);
+    log_step!("  Wallet 2: {}", escrow_wallets[1]);
+    log_step!("  Wallet 3: {}", escrow_wallets[2]);
+    log_step!("  Wallet 4: {}", escrow_wallets[3]);
+    log_step!("  Wallet 5: {}", escrow_wallets[4]);
+    
+    let mut stored_amounts = [0u64; MAX_ESCROW_WALLET_COUNT as usize];
+    if let Some(amounts) = wallet_amounts {
+        stored_amounts[..amounts.len()].copy_from_slice(&amounts);
+    }
+    
+    init_escrow_lock(
+        &mut ctx.accounts.escrow_lock_account,
+        &mut ctx.accounts.escrow_counter,
+        &mut ctx.accounts.minter_account,
+        ctx.accounts.program_config.max_escrows_per_minter,
+        NewEscrow {
+            token,
+            reward_token,
+            minter,
+            reward_value,
+            token_supply,
+            expiry,
+            terms,
+            escrow_wallets,
+            wallet_bumps,
+            wallet_amounts: stored_amounts,
+            lock_bump: ctx.bumps.escrow_lock_account,
+            token_decimals: ctx.accounts.token_mint.decimals,
+            reward_token_decimals: ctx.accounts.reward_token_mint.decimals,
+            minter_reward_account: ctx.accounts.minter_reward_account.key(),
+        },
+        clock.unix_timestamp,
+    )
 }
 
-pub fn redeem_rewards(
-    ctx: Context<RedeemRewards>,
-    burn_amount: u64,
+/// Same as `lock_funds`, with the expiry given as seconds from now.
+#[allow(clippy::too_many_arguments)]
+pub fn lock_funds_duration(
+    ctx: Context<LockFunds>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    duration_secs: i64,
+    params: LockParams,
 ) -> Result<()> {
     let clock = Clock::get()?;
     
-    // Validate escrow is still active
-    require!(
-        ctx.accounts.escrow_lock_account.is_active,
-        FluterByError::EscrowNotFound
-    );
-    
-    // Validate escrow has not expired
-    require!(
-        clock.unix_timestamp < ctx.accounts.escrow_lock_account.expires_at,
-        FluterByError::EscrowExpired
-    );
+    // A non-positive duration lands at or before now and is rejected by `lock_funds`
+    let expiry = clock.unix_timestamp
+        .checked_add(duration_secs)
+        .ok_or(FluterByError::InvalidExpiry)?;
     
-    // Validate user has enough tokens to burn
+    lock_funds(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lock_funds_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockFundsV2<'info>>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+) -> Result<()> {
+    process_lock_v2(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params, false)
+}
+
+pub fn fund_escrow_wallet(
+    ctx: Context<FundEscrowWallet>,
+    token: Pubkey,
+    wallet_index: u8,
+    amount: u64,
+) -> Result<()> {
+    // Validate the wallet index is one that a lock can reference
     require!(
-        ctx.accounts.user_token_account.amount >= burn_amount,
-        FluterByError::InsufficientTokenBalance
+        (1..=MAX_ESCROW_WALLET_COUNT).contains(&wallet_index),
+        FluterByError::InvalidEscrowWalletIndex
     );
     
-    // Validate burn amount is greater than 0
+    // Validate amount is greater than 0
     require!(
-        burn_amount > 0,
+        amount > 0,
         FluterByError::InvalidDistributionAmount
     );
     
-    // Calculate proportional reward based on burned tokens
-    // reward = (burn_amount / total_token_supply) * remaining_reward_value
-    let reward_amount = (burn_amount as u128)
-        .checked_mul(ctx.accounts.escrow_lock_account.remaining_reward_value as u128)
-        .and_then(|x| x.checked_div(ctx.accounts.escrow_lock_account.total_token_supply as u128))
-        .ok_or(FluterByError::DistributionCalculationOverflow)? as u64;
+    transfer_to_escrow(
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.minter_reward_account.to_account_info(),
+        ctx.accounts.escrow_wallet.to_account_info(),
+        ctx.accounts.minter.to_account_info(),
+        amount,
+    )?;
     
-    // Validate there are enough rewards remaining
-    require!(
-        reward_amount <= ctx.accounts.escrow_lock_account.remaining_reward_value,
-        FluterByError::InsufficientFunds
-    );
+    msg!("Funded escrow wallet {} with {}", wallet_index, amount);
     
-    // Burn the user's FLBY tokens
-    msg!("Burning {} FLBY tokens...", burn_amount);
-    let cpi_accounts_burn = token::Burn {
-        mint: ctx.accounts.token_mint.to_account_info(),
-        from: ctx.accounts.user_token_account.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx_burn = CpiContext::new(cpi_program.clone(), cpi_accounts_burn);
-    token::burn(cpi_ctx_burn, burn_amount)?;
-    msg!("✅ Burned {} FLBY tokens", burn_amount);
-    
-    // Calculate how much to take from each of the 5 escrow wallets
-    // Distribute the withdrawal proportionally from each wallet
-    let reward_per_wallet = reward_amount
-        .checked_div(5)
-        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    emit!(EscrowWalletFunded {
+        token,
+        minter: ctx.accounts.minter.key(),
+        escrow_wallet: ctx.accounts.escrow_wallet.key(),
+        wallet_index,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     
-    let remainder = reward_amount % 5;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn activate_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockFundsV2<'info>>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+) -> Result<()> {
+    process_lock_v2(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params, true)
+}
+
+/// Creates the escrow lock across the escrow wallets in `remaining_accounts`.
+/// Shared by `lock_funds_v2` and `activate_lock`.
+/// 
+/// Unless `prefunded`, the minter transfers `reward_value` into the wallets here.
+/// With `prefunded` the wallets were already funded with `fund_escrow_wallet`, and
+/// their balances must add up to exactly `reward_value`.
+#[allow(clippy::too_many_arguments)]
+fn process_lock_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockFundsV2<'info>>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+    prefunded: bool,
+) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
     
-    msg!("Transferring {} reward tokens from 5 escrow wallets to user...", reward_amount);
-    msg!("Base amount per wallet: {}, Remainder: {}", reward_per_wallet, remainder);
+    // Prefunded wallets already hold the rewards; their total is checked below
+    let minter_balance = if prefunded {
+        reward_value
+    } else {
+        ctx.accounts.minter_reward_account.amount
+    };
+    validate_lock(escrow_lock_account, reward_value, minter_balance, token_supply, ctx.accounts.token_mint.supply, minter, ctx.accounts.minter.key())?;
+    let terms = validate_lock_params(
+        params,
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.key()),
+        expiry,
+        &ctx.accounts.program_config,
+        clock.unix_timestamp,
+    )?;
+    let wallet_count = terms.wallet_count;
     
-    // Get the PDA signer seeds for authority
-    let token_key = ctx.accounts.escrow_lock_account.token;
-    let minter_key = ctx.accounts.escrow_lock_account.minter;
-    let bump = ctx.bumps.escrow_lock_account;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"escrow_lock",
-        token_key.as_ref(),
-        minter_key.as_ref(),
-        &[bump],
-    ]];
+    // Validate one escrow wallet was passed per index
+    require!(
+        ctx.remaining_accounts.len() == wallet_count as usize,
+        FluterByError::InvalidWalletCount
+    );
     
-    // Transfer from escrow wallet 1 (gets extra from remainder if any)
-    let amount_1 = if remainder > 0 { reward_per_wallet + 1 } else { reward_per_wallet };
-    if amount_1 > 0 {
-        let cpi_accounts_1 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_1.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_1 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_1, signer_seeds);
-        token::transfer(cpi_ctx_1, amount_1)?;
-        msg!("Transferred {} from wallet 1", amount_1);
+    if !prefunded {
+        log_step!("Transferring {} tokens across {} escrow wallets...", reward_value, wallet_count);
     }
     
-    // Transfer from escrow wallet 2 (gets extra from remainder if any)
-    let amount_2 = if remainder > 1 { reward_per_wallet + 1 } else { reward_per_wallet };
-    if amount_2 > 0 {
-        let cpi_accounts_2 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_2.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_2 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_2, signer_seeds);
-        token::transfer(cpi_ctx_2, amount_2)?;
-        msg!("Transferred {} from wallet 2", amount_2);
-    }
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let mut escrow_wallets = [Pubkey::default(); MAX_ESCROW_WALLET_COUNT as usize];
+    let mut wallet_bumps = [0u8; MAX_ESCROW_WALLET_COUNT as usize];
+    let mut funded_value: u64 = 0;
     
-    // Transfer from escrow wallet 3 (gets extra from remainder if any)
-    let amount_3 = if remainder > 2 { reward_per_wallet + 1 } else { reward_per_wallet };
-    if amount_3 > 0 {
-        let cpi_accounts_3 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_3.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_3 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_3, signer_seeds);
-        token::transfer(cpi_ctx_3, amount_3)?;
-        msg!("Transferred {} from wallet 3", amount_3);
+    for (i, escrow_wallet) in ctx.remaining_accounts.iter().enumerate() {
+        let wallet_index = i as u8 + 1;
+        
+        // Validate the wallet is the PDA for this index
+        let (expected_wallet, wallet_bump) = Pubkey::find_program_address(
+            &[b"escrow_wallet", token.as_ref(), minter.as_ref(), &[wallet_index]],
+            ctx.program_id,
+        );
+        require!(
+            escrow_wallet.key() == expected_wallet,
+            FluterByError::InvalidEscrowWalletIndex
+        );
+        wallet_bumps[i] = wallet_bump;
+        validate_escrow_wallet_initialized(escrow_wallet, escrow_lock_account.key(), reward_token, &token::ID)?;
+        
+        if prefunded {
+            // Count what `fund_escrow_wallet` put in, as long as it is the reward token
+            let wallet = read_token_account(escrow_wallet)?;
+            require!(
+                wallet.mint == reward_token && wallet.mint == ctx.accounts.reward_token_mint.key(),
+                FluterByError::InvalidDistributionAmount
+            );
+            funded_value = funded_value
+                .checked_add(wallet.amount)
+                .ok_or(FluterByError::DistributionCalculationOverflow)?;
+            log_step!("Wallet {} holds {}", wallet_index, wallet.amount);
+        } else {
+            let amount = wallet_share(reward_value, &bps_weights(&terms.wallet_weights[..wallet_count as usize]), i as u64, 0)?;
+            transfer_to_escrow(
+                &cpi_program,
+                ctx.accounts.minter_reward_account.to_account_info(),
+                escrow_wallet.clone(),
+                ctx.accounts.minter.to_account_info(),
+                amount,
+            )?;
+            log_step!("Transferred {} to wallet {}", amount, wallet_index);
+        }
+        
+        escrow_wallets[i] = escrow_wallet.key();
     }
     
-    // Transfer from escrow wallet 4 (gets extra from remainder if any)
-    let amount_4 = if remainder > 3 { reward_per_wallet + 1 } else { reward_per_wallet };
-    if amount_4 > 0 {
-        let cpi_accounts_4 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_4.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_4 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_4, signer_seeds);
-        token::transfer(cpi_ctx_4, amount_4)?;
-        msg!("Transferred {} from wallet 4", amount_4);
+    if prefunded {
+        // Validate the staged funding adds up to exactly the value being locked
+        require!(
+            funded_value == reward_value,
+            FluterByError::FundingMismatch
+        );
+        log_step!("✅ Escrow wallets hold the full reward value!");
+    } else {
+        log_step!("✅ All reward tokens transferred to escrow wallets!");
     }
     
-    // Transfer from escrow wallet 5
-    if reward_per_wallet > 0 {
-        let cpi_accounts_5 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_5.to_account_info(),
-            to: ctx.accounts.user_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_5 = CpiContext::new_with_signer(cpi_program, cpi_accounts_5, signer_seeds);
-        token::transfer(cpi_ctx_5, reward_per_wallet)?;
-        msg!("Transferred {} from wallet 5", reward_per_wallet);
+    init_escrow_lock(
+        &mut ctx.accounts.escrow_lock_account,
+        &mut ctx.accounts.escrow_counter,
+        &mut ctx.accounts.minter_account,
+        ctx.accounts.program_config.max_escrows_per_minter,
+        NewEscrow {
+            token,
+            reward_token,
+            minter,
+            reward_value,
+            token_supply,
+            expiry,
+            terms,
+            escrow_wallets,
+            wallet_bumps,
+            wallet_amounts: [0; MAX_ESCROW_WALLET_COUNT as usize],
+            lock_bump: ctx.bumps.escrow_lock_account,
+            token_decimals: ctx.accounts.token_mint.decimals,
+            reward_token_decimals: ctx.accounts.reward_token_mint.decimals,
+            minter_reward_account: ctx.accounts.minter_reward_account.key(),
+        },
+        clock.unix_timestamp,
+    )
+}
+
+pub fn redeem_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewards<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    
+    // Tag the payout for exchanges and explorers before the reward transfers
+    if let Some(memo_program) = &ctx.accounts.memo_program {
+        let memo = redemption_memo(ctx.accounts.escrow_lock_account.escrow_id, ctx.accounts.user.key());
+        memo::build_memo(CpiContext::new(memo_program.to_account_info(), memo::BuildMemo {}), memo.as_bytes())?;
     }
     
-    // Update remaining reward value
-    ctx.accounts.escrow_lock_account.remaining_reward_value = ctx.accounts.escrow_lock_account.remaining_reward_value
-        .checked_sub(reward_amount)
-        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    let (burned, payout) = process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        None,
+        ctx.accounts.user_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.token_program.to_account_info(),
+        None,
+        ctx.remaining_accounts,
+        burn_amount,
+        min_reward_out,
+    )?;
     
-    msg!("✅ Redemption complete!");
-    msg!("FLBY tokens burned: {}", burn_amount);
-    msg!("Reward tokens received: {}", reward_amount);
-    msg!("Remaining rewards in escrow: {}", ctx.accounts.escrow_lock_account.remaining_reward_value);
+    mint_redemption_receipt(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.receipt_mint.as_deref(),
+        ctx.accounts.user_receipt_account.as_deref(),
+        &ctx.accounts.token_program,
+    )?;
     
-    emit!(RewardsRedeemed {
-        token: ctx.accounts.escrow_lock_account.token,
-        user: ctx.accounts.user.key(),
-        tokens_burned: burn_amount,
-        rewards_received: reward_amount,
-        remaining_rewards: ctx.accounts.escrow_lock_account.remaining_reward_value,
-        timestamp: clock.unix_timestamp,
-    });
+    record_redemption_log(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.redemption_log.as_ref(),
+        ctx.accounts.user.key(),
+        burned,
+        payout,
+    )?;
     
-    Ok(())
+    Ok(payout)
 }
 
-pub fn withdraw_expired_rewards(
-    ctx: Context<WithdrawExpiredRewards>,
-) -> Result<()> {
-    let clock = Clock::get()?;
-    
-    // Validate escrow is still active
-    require!(
-        ctx.accounts.escrow_lock_account.is_active,
-        FluterByError::EscrowNotFound
-    );
-    
-    // Validate escrow HAS expired (opposite of redeem_rewards)
-    require!(
-        clock.unix_timestamp >= ctx.accounts.escrow_lock_account.expires_at,
-        FluterByError::EscrowNotExpired
-    );
+pub fn redeem_rewards_to<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewardsTo<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    require_no_redemption_log(&ctx.accounts.escrow_lock_account)?;
+    require_no_receipt_mint(&ctx.accounts.escrow_lock_account)?;
     
-    // Validate caller is the minter
-    require!(
-        ctx.accounts.escrow_lock_account.minter == ctx.accounts.minter.key(),
-        FluterByError::UnauthorizedMinter
-    );
+    log_step!("Paying rewards to {}", ctx.accounts.recipient_reward_account.key());
     
-    let remaining_rewards = ctx.accounts.escrow_lock_account.remaining_reward_value;
+    process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        None,
+        ctx.accounts.recipient_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.token_program.to_account_info(),
+        None,
+        ctx.remaining_accounts,
+        burn_amount,
+        min_reward_out,
+    )
+    .map(|(_, payout)| payout)
+}
+
+pub fn redeem_rewards_and_close<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewardsAndClose<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    require_no_redemption_log(&ctx.accounts.escrow_lock_account)?;
+    require_no_receipt_mint(&ctx.accounts.escrow_lock_account)?;
     
-    // Check if there are any rewards left to withdraw
-    require!(
-        remaining_rewards > 0,
-        FluterByError::InsufficientFunds
-    );
+    log_step!("Paying rewards to {}", ctx.accounts.recipient_reward_account.key());
     
-    msg!("🔓 Escrow has expired. Minter withdrawing remaining rewards...");
-    msg!("Remaining rewards to withdraw: {}", remaining_rewards);
+    let (_, payout) = process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        None,
+        ctx.accounts.recipient_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.token_program.to_account_info(),
+        None,
+        ctx.remaining_accounts,
+        burn_amount,
+        min_reward_out,
+    )?;
     
-    // Get the PDA signer seeds for authority
-    let token_key = ctx.accounts.escrow_lock_account.token;
-    let minter_key = ctx.accounts.escrow_lock_account.minter;
-    let bump = ctx.bumps.escrow_lock_account;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"escrow_lock",
-        token_key.as_ref(),
-        minter_key.as_ref(),
-        &[bump],
-    ]];
+    // The redemption may have paid into it after all (e.g. as the fee treasury)
+    ctx.accounts.user_reward_account.reload()?;
     
-    // Get current balance from each escrow wallet and transfer all to minter
-    let wallet_1_balance = ctx.accounts.escrow_wallet_1.amount;
-    let wallet_2_balance = ctx.accounts.escrow_wallet_2.amount;
-    let wallet_3_balance = ctx.accounts.escrow_wallet_3.amount;
-    let wallet_4_balance = ctx.accounts.escrow_wallet_4.amount;
-    let wallet_5_balance = ctx.accounts.escrow_wallet_5.amount;
+    // An account still holding reward tokens can't be closed, so it is left open
+    let remaining_balance = ctx.accounts.user_reward_account.amount;
+    if remaining_balance > 0 {
+        msg!("Reward account {} still holds {}, leaving it open", ctx.accounts.user_reward_account.key(), remaining_balance);
+        return Ok(payout);
+    }
     
-    let total_to_withdraw = wallet_1_balance + wallet_2_balance + wallet_3_balance + 
-                            wallet_4_balance + wallet_5_balance;
+    // Close the emptied account, returning its rent to the user
+    let cpi_accounts = token::CloseAccount {
+        account: ctx.accounts.user_reward_account.to_account_info(),
+        destination: ctx.accounts.user.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    token::close_account(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts))?;
     
-    msg!("Total rewards in escrow wallets: {}", total_to_withdraw);
+    msg!("Closed reward account {}", ctx.accounts.user_reward_account.key());
     
-    let cpi_program = ctx.accounts.token_program.to_account_info();
+    Ok(payout)
+}
+
+pub fn redeem_rewards_split<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewardsSplit<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+    weights: Vec<u16>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    require_no_redemption_log(&ctx.accounts.escrow_lock_account)?;
+    require_no_receipt_mint(&ctx.accounts.escrow_lock_account)?;
     
-    // Transfer all funds from wallet 1
-    if wallet_1_balance > 0 {
-        let cpi_accounts_1 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_1.to_account_info(),
-            to: ctx.accounts.minter_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_1 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_1, signer_seeds);
-        token::transfer(cpi_ctx_1, wallet_1_balance)?;
-        msg!("Transferred {} from wallet 1", wallet_1_balance);
-    }
+    // Validate one non-zero weight per recipient, summing to 10000 basis points. A
+    // zero-weight recipient would still pick up a remainder token.
+    let recipient_count = weights.len();
+    require!(
+        (1..=MAX_SPLIT_RECIPIENTS).contains(&recipient_count)
+            && ctx.remaining_accounts.len() >= recipient_count
+            && weights.iter().all(|weight| *weight > 0)
+            && weights.iter().map(|weight| *weight as u64).sum::<u64>() == BPS_DENOMINATOR,
+        FluterByError::InvalidWeights
+    );
     
-    // Transfer all funds from wallet 2
-    if wallet_2_balance > 0 {
-        let cpi_accounts_2 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_2.to_account_info(),
-            to: ctx.accounts.minter_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_2 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_2, signer_seeds);
-        token::transfer(cpi_ctx_2, wallet_2_balance)?;
-        msg!("Transferred {} from wallet 2", wallet_2_balance);
-    }
+    // The recipients are the last remaining accounts
+    let (redemption_accounts, recipients) = ctx.remaining_accounts.split_at(ctx.remaining_accounts.len() - recipient_count);
     
-    // Transfer all funds from wallet 3
-    if wallet_3_balance > 0 {
-        let cpi_accounts_3 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_3.to_account_info(),
-            to: ctx.accounts.minter_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_3 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_3, signer_seeds);
-        token::transfer(cpi_ctx_3, wallet_3_balance)?;
-        msg!("Transferred {} from wallet 3", wallet_3_balance);
+    // Validate every recipient holds the reward token and isn't one of the escrow wallets
+    let escrow_wallets = &ctx.accounts.escrow_lock_account.escrow_wallets[..ctx.accounts.escrow_lock_account.wallet_count as usize];
+    for recipient in recipients {
+        let recipient_account = read_token_account(recipient)?;
+        require!(
+            recipient_account.mint == ctx.accounts.escrow_lock_account.reward_token
+                && !escrow_wallets.contains(&recipient.key()),
+            FluterByError::InvalidDistributionAmount
+        );
     }
+    require_unfrozen(recipients, &[])?;
     
-    // Transfer all funds from wallet 4
-    if wallet_4_balance > 0 {
-        let cpi_accounts_4 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_4.to_account_info(),
-            to: ctx.accounts.minter_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_4 = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_4, signer_seeds);
-        token::transfer(cpi_ctx_4, wallet_4_balance)?;
-        msg!("Transferred {} from wallet 4", wallet_4_balance);
-    }
+    let (_, payout) = process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        None,
+        ctx.accounts.user_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.token_program.to_account_info(),
+        None,
+        redemption_accounts,
+        burn_amount,
+        min_reward_out,
+    )?;
     
-    // Transfer all funds from wallet 5
-    if wallet_5_balance > 0 {
-        let cpi_accounts_5 = token::Transfer {
-            from: ctx.accounts.escrow_wallet_5.to_account_info(),
-            to: ctx.accounts.minter_reward_account.to_account_info(),
-            authority: ctx.accounts.escrow_lock_account.to_account_info(),
-        };
-        let cpi_ctx_5 = CpiContext::new_with_signer(cpi_program, cpi_accounts_5, signer_seeds);
-        token::transfer(cpi_ctx_5, wallet_5_balance)?;
-        msg!("Transferred {} from wallet 5", wallet_5_balance);
+    // Fan the payout out by weight, the remainder going one token each to the first recipients
+    let weights = bps_weights(&weights);
+    let mut amounts = Vec::with_capacity(recipient_count);
+    for (i, recipient) in recipients.iter().enumerate() {
+        let amount = wallet_share(payout, &weights, i as u64, 0)?;
+        if amount > 0 {
+            let cpi_accounts = token::Transfer {
+                from: ctx.accounts.user_reward_account.to_account_info(),
+                to: recipient.clone(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)?;
+        }
+        amounts.push(amount);
     }
     
-    // Mark escrow as inactive
-    ctx.accounts.escrow_lock_account.is_active = false;
-    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
-    
-    msg!("✅ Withdrawal complete! Escrow closed.");
-    msg!("Total withdrawn: {}", total_to_withdraw);
-    msg!("Minter received all remaining rewards.");
+    msg!("Split {} reward tokens across {} recipients", payout, recipient_count);
     
-    emit!(ExpiredRewardsWithdrawn {
+    emit!(RewardsSplit {
         token: ctx.accounts.escrow_lock_account.token,
-        minter: ctx.accounts.escrow_lock_account.minter,
-        amount_withdrawn: total_to_withdraw,
-        timestamp: clock.unix_timestamp,
+        user: ctx.accounts.user.key(),
+        recipients: recipients.iter().map(|recipient| recipient.key()).collect(),
+        amounts,
+        timestamp: Clock::get()?.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
     });
     
-    Ok(())
+    Ok(payout)
+}
+
+pub fn redeem_rewards_transfer<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewardsTransfer<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    require_no_redemption_log(&ctx.accounts.escrow_lock_account)?;
+    require_no_receipt_mint(&ctx.accounts.escrow_lock_account)?;
+    
+    process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        Some(&ctx.accounts.incinerator_account),
+        ctx.accounts.user_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.token_program.to_account_info(),
+        None,
+        ctx.remaining_accounts,
+        burn_amount,
+        min_reward_out,
+    )
+    .map(|(_, payout)| payout)
+}
+
+pub fn redeem_rewards_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RedeemRewardsBatch<'info>>,
+    redemptions: Vec<BatchRedemption>,
+) -> Result<Vec<u64>> {
+    // Validate the batch fits in one transaction
+    require!(
+        !redemptions.is_empty() && redemptions.len() <= MAX_BATCH_REDEMPTIONS,
+        FluterByError::InvalidBatch
+    );
+    
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let mut payouts = Vec::with_capacity(redemptions.len());
+    for redemption in redemptions {
+        require!(
+            remaining_accounts.len() >= BATCH_REDEMPTION_ACCOUNTS,
+            FluterByError::InvalidBatch
+        );
+        
+        // Load this escrow's accounts; they are read fresh for every escrow, so one
+        // listed twice sees the first redemption's effects
+        let mut escrow_lock_account = Account::<EscrowLockAccount>::try_from(&remaining_accounts[0])?;
+        let token_mint = Account::<token::Mint>::try_from(&remaining_accounts[1])?;
+        let user_token_account = Account::<token::TokenAccount>::try_from(&remaining_accounts[2])?;
+        let user_reward_account = Account::<token::TokenAccount>::try_from(&remaining_accounts[3])?;
+        let treasury_reward_account = if remaining_accounts[4].key() == crate::ID {
+            None
+        } else {
+            Some(Account::<token::TokenAccount>::try_from(&remaining_accounts[4])?)
+        };
+        
+        let accounts_used = BATCH_REDEMPTION_ACCOUNTS + escrow_lock_account.wallet_count as usize;
+        require!(
+            remaining_accounts.len() >= accounts_used,
+            FluterByError::InvalidBatch
+        );
+        let escrow_wallets = &remaining_accounts[BATCH_REDEMPTION_ACCOUNTS..accounts_used];
+        remaining_accounts = &remaining_accounts[accounts_used..];
+        
+        validate_batch_redemption(
+            &escrow_lock_account,
+            ctx.accounts.user.key(),
+            &token_mint,
+            &user_token_account,
+            &user_reward_account,
+            treasury_reward_account.as_ref(),
+        )?;
+        
+        // Escrows that need an allowlist entry or a log use the single-escrow instructions
+        validate_redeemer(&escrow_lock_account, false)?;
+        require_no_redemption_log(&escrow_lock_account)?;
+        require_no_receipt_mint(&escrow_lock_account)?;
+        
+        let (_, payout) = process_redemption(
+            &mut escrow_lock_account,
+            &ctx.accounts.user,
+            &token_mint,
+            &user_token_account,
+            None,
+            user_reward_account.to_account_info(),
+            treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+            None,
+            &ctx.accounts.token_program,
+            ctx.accounts.token_program.to_account_info(),
+            None,
+            escrow_wallets,
+            redemption.burn_amount,
+            redemption.min_reward_out,
+        )?;
+        
+        // Write the escrow back before the next escrow's accounts are loaded
+        escrow_lock_account.exit(&crate::ID)?;
+        payouts.push(payout);
+    }
+    
+    // Validate every account passed belonged to one of the escrows
+    require!(
+        remaining_accounts.is_empty(),
+        FluterByError::InvalidBatch
+    );
+    
+    msg!("✅ Batch redemption complete! Payouts: {:?}", payouts);
+    
+    Ok(payouts)
+}
+
+pub fn quote_redemption(
+    ctx: Context<QuoteRedemption>,
+    burn_amount: u64,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    let (burn_amount, reward_amount) = quote_reward(
+        &ctx.accounts.escrow_lock_account,
+        burn_amount,
+        clock.unix_timestamp,
+    )?;
+    let payout = reward_amount - redemption_fee(&ctx.accounts.escrow_lock_account, reward_amount)?;
+    
+    msg!("Burning {} tokens would pay {} reward tokens", burn_amount, payout);
+    
+    Ok(payout)
+}
+
+pub fn quote_max_redemption(ctx: Context<QuoteMaxRedemption>) -> Result<u64> {
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // The whole balance, within the supply the escrow shares its rewards against
+    let mut burn_amount = ctx.accounts.user_token_account.amount.min(escrow_lock_account.total_token_supply);
+    
+    // Proportional modes already cut a burn down to what the pool can pay for; a
+    // fixed-rate burn is cut down here instead of reverting
+    if escrow_lock_account.mode == REWARD_MODE_FIXED_RATE {
+        let vested_reward_value = vested_reward_value(escrow_lock_account, escrow_lock_account.remaining_reward_value, clock.unix_timestamp)?;
+        burn_amount = burn_amount.min(vested_reward_value / escrow_lock_account.rate);
+    }
+    
+    if burn_amount == 0 {
+        msg!("Nothing to redeem");
+        return Ok(0);
+    }
+    
+    let (burn_amount, reward_amount) = quote_reward(escrow_lock_account, burn_amount, clock.unix_timestamp)?;
+    let payout = reward_amount - redemption_fee(escrow_lock_account, reward_amount)?;
+    
+    msg!("Burning the whole balance ({} tokens) would pay {} reward tokens", burn_amount, payout);
+    
+    Ok(payout)
+}
+
+pub fn get_escrow_status<'info>(
+    ctx: Context<'_, '_, '_, 'info, GetEscrowStatus<'info>>,
+) -> Result<EscrowStatus> {
+    let clock = Clock::get()?;
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    
+    // Sum what the wallets actually hold, rather than trusting the recorded value
+    let wallet_balance_total = escrow_wallet_balance_total(escrow_lock_account, ctx.remaining_accounts)?;
+    
+    Ok(EscrowStatus {
+        remaining_reward_value: escrow_lock_account.remaining_reward_value,
+        total_reward_value: escrow_lock_account.total_reward_value,
+        status: escrow_lock_account.status_at(clock.unix_timestamp),
+        expires_at: escrow_lock_account.expires_at,
+        seconds_until_expiry: escrow_lock_account.expires_at.saturating_sub(clock.unix_timestamp).max(0),
+        wallet_balance_total,
+    })
+}
+
+pub fn verify_escrow_solvency<'info>(
+    ctx: Context<'_, '_, '_, 'info, GetEscrowStatus<'info>>,
+) -> Result<()> {
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    
+    let wallet_balance_total = escrow_wallet_balance_total(escrow_lock_account, ctx.remaining_accounts)?;
+    let remaining_reward_value = escrow_lock_account.remaining_reward_value;
+    let tolerance = SOLVENCY_TOLERANCE_PER_WALLET * escrow_lock_account.wallet_count as u64;
+    
+    msg!(
+        "Escrow wallets hold {}, remaining reward value is {} (tolerance {})",
+        wallet_balance_total,
+        remaining_reward_value,
+        tolerance
+    );
+    
+    // Validate the wallets and the recorded value agree, in either direction
+    require!(
+        wallet_balance_total.abs_diff(remaining_reward_value) <= tolerance,
+        FluterByError::SolvencyMismatch
+    );
+    
+    Ok(())
+}
+
+pub fn quote_expired_withdrawal<'info>(
+    ctx: Context<'_, '_, '_, 'info, QuoteExpiredWithdrawal<'info>>,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    validate_expired_withdrawal(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.minter.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // The sweep moves each wallet's full balance, so that is what the minter recovers
+    let total_to_withdraw = escrow_wallet_balance_total(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    
+    msg!("Withdrawing now would recover {} reward tokens", total_to_withdraw);
+    
+    Ok(total_to_withdraw)
+}
+
+pub fn withdraw_expired_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    validate_expired_withdrawal(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.minter.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // Record the expiry, and surface it for indexers if nobody has marked it yet
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    let remaining_rewards = ctx.accounts.escrow_lock_account.remaining_reward_value;
+    
+    msg!("🔓 Escrow has expired. Minter withdrawing remaining rewards...");
+    msg!("Remaining rewards to withdraw: {}", remaining_rewards);
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Transfer the full balance of each escrow wallet to the minter
+    let total_to_withdraw = sweep_escrow_wallets(
+        &mut ctx.accounts.escrow_lock_account,
+        ctx.remaining_accounts,
+        ctx.accounts.minter_reward_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        clock.unix_timestamp,
+    )?;
+    
+    report_sweep_drift(&mut ctx.accounts.escrow_lock_account, total_to_withdraw, clock.unix_timestamp);
+    
+    // Close the escrow
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    msg!("✅ Withdrawal complete! Escrow closed. Total withdrawn: {}", total_to_withdraw);
+    
+    emit!(ExpiredRewardsWithdrawn {
+        token: ctx.accounts.escrow_lock_account.token,
+        minter: ctx.accounts.escrow_lock_account.minter,
+        amount_withdrawn: total_to_withdraw,
+        remaining_reward_value: remaining_rewards,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(total_to_withdraw)
+}
+
+pub fn partial_withdraw_expired<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    validate_expired_withdrawal(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.minter.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // Validate the amount is positive and within what is left
+    require!(
+        amount > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    require!(
+        amount <= ctx.accounts.escrow_lock_account.remaining_reward_value,
+        FluterByError::InsufficientFunds
+    );
+    
+    // Record the expiry, and surface it for indexers if nobody has marked it yet
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    msg!("🔓 Escrow has expired. Minter withdrawing {} of the remaining rewards...", amount);
+    
+    // Draw each wallet's share, cascading any shortfall to wallets with a balance
+    let escrow_wallets = escrow_wallet_infos(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    let draws = plan_escrow_draws(
+        &wallet_balances,
+        &ctx.accounts.escrow_lock_account.weights(),
+        amount,
+        ctx.accounts.escrow_lock_account.next_remainder_wallet as u64,
+    )?;
+    rotate_remainder_wallet(&mut ctx.accounts.escrow_lock_account, amount, 0, clock.unix_timestamp);
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    for (i, (escrow_wallet, draw)) in escrow_wallets.iter().zip(draws).enumerate() {
+        if draw > 0 {
+            transfer_from_escrow(
+                &cpi_program,
+                escrow_wallet.clone(),
+                ctx.accounts.minter_reward_account.to_account_info(),
+                ctx.accounts.escrow_lock_account.to_account_info(),
+                draw,
+                signer_seeds,
+            )?;
+            
+            emit!(FundsWithdrawn {
+                mint: token_key,
+                minter: ctx.accounts.escrow_lock_account.minter,
+                amount: draw,
+                wallet_index: (i + 1) as u8,
+                timestamp: clock.unix_timestamp,
+                event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+            });
+        }
+    }
+    
+    // The escrow stays open for further withdrawals until nothing is left
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let remaining_rewards = escrow_lock_account.remaining_reward_value;
+    escrow_lock_account.remaining_reward_value -= amount;
+    if escrow_lock_account.remaining_reward_value == 0 {
+        escrow_lock_account.transition_to(EscrowState::Closed)?;
+    }
+    
+    msg!("✅ Partial withdrawal complete! Withdrawn: {}, remaining: {}", amount, escrow_lock_account.remaining_reward_value);
+    
+    emit!(ExpiredRewardsWithdrawn {
+        token: token_key,
+        minter: escrow_lock_account.minter,
+        amount_withdrawn: amount,
+        remaining_reward_value: remaining_rewards,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn rollover_expired<'info>(
+    ctx: Context<'_, '_, '_, 'info, RolloverExpired<'info>>,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    validate_expired_withdrawal(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.minter.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // Validate the new escrow can still take rewards
+    require_active(&ctx.accounts.new_escrow_lock_account, clock.unix_timestamp)?;
+    
+    // Record the expiry, and surface it for indexers if nobody has marked it yet
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    let old_wallet_count = ctx.accounts.escrow_lock_account.wallet_count as usize;
+    let old_wallets = escrow_wallet_infos(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    let new_wallets = escrow_wallet_infos(
+        &ctx.accounts.new_escrow_lock_account,
+        &ctx.remaining_accounts[old_wallet_count..],
+    )?;
+    
+    let old_balances = escrow_wallet_balances(old_wallets)?;
+    let total_rolled = old_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    // Validate the new escrow stays within the supported reward bound
+    require!(
+        ctx.accounts.new_escrow_lock_account.total_reward_value
+            .checked_add(total_rolled)
+            .is_some_and(|total| total <= MAX_REWARD_VALUE),
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Split the rollover across the new wallets like a top-up, continuing the remainder
+    // from where the new escrow's lock left off
+    let new_weights = ctx.accounts.new_escrow_lock_account.weights();
+    let remainder_offset = ctx.accounts.new_escrow_lock_account.reward_remainder;
+    let new_shares = (0..new_wallets.len() as u64)
+        .map(|i| wallet_share(total_rolled, &new_weights, i, remainder_offset))
+        .collect::<Result<Vec<u64>>>()?;
+    let transfers = plan_rollover_transfers(&old_balances, &new_shares)?;
+    
+    msg!("🔓 Escrow has expired. Rolling {} reward tokens into escrow {}...", total_rolled, ctx.accounts.new_escrow_lock_account.key());
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    for (from, to, amount) in transfers {
+        transfer_from_escrow(
+            &cpi_program,
+            old_wallets[from].clone(),
+            new_wallets[to].clone(),
+            ctx.accounts.escrow_lock_account.to_account_info(),
+            amount,
+            signer_seeds,
+        )?;
+    }
+    
+    for (i, balance) in old_balances.iter().copied().enumerate() {
+        if balance > 0 {
+            emit!(FundsWithdrawn {
+                mint: token_key,
+                minter: ctx.accounts.escrow_lock_account.minter,
+                amount: balance,
+                wallet_index: (i + 1) as u8,
+                timestamp: clock.unix_timestamp,
+                event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+            });
+        }
+    }
+    
+    report_sweep_drift(&mut ctx.accounts.escrow_lock_account, total_rolled, clock.unix_timestamp);
+    
+    // Close the old escrow
+    let remaining_rewards = ctx.accounts.escrow_lock_account.remaining_reward_value;
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    emit!(ExpiredRewardsWithdrawn {
+        token: token_key,
+        minter: ctx.accounts.escrow_lock_account.minter,
+        amount_withdrawn: total_rolled,
+        remaining_reward_value: remaining_rewards,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    // Update the new escrow's reward totals
+    let new_escrow_lock_account = &mut ctx.accounts.new_escrow_lock_account;
+    let new_wallet_count = new_escrow_lock_account.wallet_count as u64;
+    new_escrow_lock_account.total_reward_value = new_escrow_lock_account.total_reward_value
+        .checked_add(total_rolled)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    new_escrow_lock_account.remaining_reward_value = new_escrow_lock_account.remaining_reward_value
+        .checked_add(total_rolled)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    new_escrow_lock_account.reward_per_wallet = new_escrow_lock_account.total_reward_value
+        .checked_div(new_wallet_count)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    new_escrow_lock_account.reward_remainder = new_escrow_lock_account.total_reward_value % new_wallet_count;
+    
+    msg!("✅ Rollover complete! Old escrow closed. Total rolled over: {}", total_rolled);
+    
+    emit!(FundsLocked {
+        mint: new_escrow_lock_account.token,
+        minter: new_escrow_lock_account.minter,
+        value: total_rolled,
+        expires_at: new_escrow_lock_account.expires_at,
+        timestamp: clock.unix_timestamp,
+        reward_per_wallet: new_escrow_lock_account.reward_per_wallet,
+        reward_remainder: new_escrow_lock_account.reward_remainder,
+        escrow_wallets: new_escrow_lock_account.escrow_wallets[..new_wallet_count as usize].to_vec(),
+        event_seq: new_escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(total_rolled)
+}
+
+pub fn cancel_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelLock<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Validate escrow is still open
+    require!(
+        matches!(
+            ctx.accounts.escrow_lock_account.status_at(clock.unix_timestamp),
+            EscrowState::Active | EscrowState::Expired
+        ),
+        FluterByError::EscrowInactive
+    );
+    
+    // Validate nobody has redeemed yet, so holders are never cut off mid-stream
+    require!(
+        ctx.accounts.escrow_lock_account.remaining_reward_value
+            == ctx.accounts.escrow_lock_account.total_reward_value,
+        FluterByError::RedemptionsInProgress
+    );
+    
+    msg!("Cancelling lock and refunding the minter...");
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Transfer the full balance of each escrow wallet back to the minter
+    let total_refunded = sweep_escrow_wallets(
+        &mut ctx.accounts.escrow_lock_account,
+        ctx.remaining_accounts,
+        ctx.accounts.minter_reward_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        clock.unix_timestamp,
+    )?;
+    
+    // Close the escrow
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    msg!("✅ Lock cancelled. Refunded: {}", total_refunded);
+    
+    emit!(LockCancelled {
+        token: token_key,
+        minter: ctx.accounts.escrow_lock_account.minter,
+        amount_refunded: total_refunded,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn settle_expired<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleExpired<'info>>,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    validate_expired_settlement(&ctx.accounts.escrow_lock_account, clock.unix_timestamp)?;
+    
+    // Record the expiry, and surface it for indexers if nobody has marked it yet
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    let total_settled = if ctx.accounts.escrow_lock_account.expiry_action == EXPIRY_ACTION_BURN {
+        // Burning is trustless, so anyone can trigger it
+        msg!("🔥 Escrow has expired. Burning remaining rewards...");
+        
+        let total_burned = burn_escrow_wallets(
+            &ctx.accounts.escrow_lock_account,
+            ctx.remaining_accounts,
+            ctx.accounts.reward_token_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        
+        emit!(ExpiredRewardsBurned {
+            token: token_key,
+            minter: ctx.accounts.escrow_lock_account.minter,
+            caller: ctx.accounts.caller.key(),
+            amount_burned: total_burned,
+            timestamp: clock.unix_timestamp,
+            event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+        });
+        
+        total_burned
+    } else {
+        // Validate only the minter takes the leftovers back
+        require!(
+            ctx.accounts.caller.key() == ctx.accounts.escrow_lock_account.minter,
+            FluterByError::UnauthorizedMinter
+        );
+        let minter_reward_account = ctx.accounts.minter_reward_account
+            .as_ref()
+            .ok_or(FluterByError::InvalidRefundAccount)?;
+        
+        msg!("🔓 Escrow has expired. Returning remaining rewards to the minter...");
+        
+        let total_withdrawn = sweep_escrow_wallets(
+            &mut ctx.accounts.escrow_lock_account,
+            ctx.remaining_accounts,
+            minter_reward_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+            clock.unix_timestamp,
+        )?;
+        
+        emit!(ExpiredRewardsWithdrawn {
+            token: token_key,
+            minter: ctx.accounts.escrow_lock_account.minter,
+            amount_withdrawn: total_withdrawn,
+            remaining_reward_value: ctx.accounts.escrow_lock_account.remaining_reward_value,
+            timestamp: clock.unix_timestamp,
+            event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+        });
+        
+        total_withdrawn
+    };
+    
+    report_sweep_drift(&mut ctx.accounts.escrow_lock_account, total_settled, clock.unix_timestamp);
+    
+    // Close the escrow
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    msg!("✅ Settlement complete! Escrow closed. Total settled: {}", total_settled);
+    
+    Ok(total_settled)
+}
+
+pub fn reclaim_dust<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Validate nothing is still owed to holders; only the rounding tail is swept
+    require!(
+        ctx.accounts.escrow_lock_account.remaining_reward_value == 0,
+        FluterByError::RewardsStillRemaining
+    );
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Sweep whatever the wallets still hold; empty wallets make this a no-op
+    let total_reclaimed = sweep_escrow_wallets(
+        &mut ctx.accounts.escrow_lock_account,
+        ctx.remaining_accounts,
+        ctx.accounts.minter_reward_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        clock.unix_timestamp,
+    )?;
+    
+    msg!("✅ Dust reclaimed: {}", total_reclaimed);
+    
+    emit!(DustReclaimed {
+        token: token_key,
+        minter: ctx.accounts.escrow_lock_account.minter,
+        amount_reclaimed: total_reclaimed,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn emergency_withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmergencyWithdraw<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    msg!("🚨 Emergency withdrawal to {}", ctx.accounts.minter_reward_account.key());
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Sweep every escrow wallet regardless of expiry. `transfer_checked` is supported
+    // by both token programs, so this covers SPL Token and Token-2022 escrows alike.
+    let escrow_wallets = escrow_wallet_infos(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    
+    let total_withdrawn = wallet_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    let cpi_program = ctx.accounts.reward_token_program.to_account_info();
+    let decimals = ctx.accounts.reward_token_mint.decimals;
+    
+    for (escrow_wallet, balance) in escrow_wallets.iter().zip(wallet_balances.iter().copied()) {
+        if balance > 0 {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: escrow_wallet.clone(),
+                mint: ctx.accounts.reward_token_mint.to_account_info(),
+                to: ctx.accounts.minter_reward_account.to_account_info(),
+                authority: ctx.accounts.escrow_lock_account.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds);
+            token_interface::transfer_checked(cpi_ctx, balance, decimals)?;
+        }
+    }
+    
+    // Close the escrow
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    msg!("✅ Emergency withdrawal complete! Swept {} from escrow wallets: {:?}", total_withdrawn, wallet_balances);
+    
+    emit!(EmergencyWithdrawal {
+        token: token_key,
+        admin: ctx.accounts.admin.key(),
+        minter: ctx.accounts.escrow_lock_account.minter,
+        destination: ctx.accounts.minter_reward_account.key(),
+        amount_withdrawn: total_withdrawn,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn clawback(ctx: Context<ClawbackRewards>, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let redeemer_reward_account = &ctx.accounts.redeemer_reward_account;
+    
+    // Validate the admin and the minter are two different keys, so neither can act alone
+    require!(
+        ctx.accounts.admin.key() != ctx.accounts.minter.key(),
+        FluterByError::ClawbackNotAuthorized
+    );
+    
+    // Validate the escrow can still take the tokens back into its pool
+    require!(
+        matches!(
+            ctx.accounts.escrow_lock_account.status_at(clock.unix_timestamp),
+            EscrowState::Active | EscrowState::Expired
+        ),
+        FluterByError::EscrowInactive
+    );
+    
+    // Validate the amount is positive and the redeemer delegated at least that much
+    require!(
+        amount > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    require!(
+        redeemer_reward_account.delegate.contains(&ctx.accounts.escrow_lock_account.key())
+            && redeemer_reward_account.delegated_amount >= amount,
+        FluterByError::ClawbackNotAuthorized
+    );
+    
+    msg!("⚠️ Clawing back {} reward tokens from {}", amount, redeemer_reward_account.key());
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // The escrow lock account moves the tokens as the redeemer's delegate
+    transfer_from_escrow(
+        &ctx.accounts.token_program.to_account_info(),
+        redeemer_reward_account.to_account_info(),
+        ctx.accounts.escrow_wallet.to_account_info(),
+        ctx.accounts.escrow_lock_account.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+    
+    // The tokens are back in the pool, available to redeem again
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    escrow_lock_account.remaining_reward_value = escrow_lock_account.remaining_reward_value
+        .checked_add(amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    msg!("✅ Clawback complete! Remaining rewards: {}", escrow_lock_account.remaining_reward_value);
+    
+    emit!(Clawback {
+        token: token_key,
+        admin: ctx.accounts.admin.key(),
+        minter: ctx.accounts.minter.key(),
+        redeemer: redeemer_reward_account.owner,
+        redeemer_reward_account: redeemer_reward_account.key(),
+        escrow_wallet: ctx.accounts.escrow_wallet.key(),
+        amount,
+        remaining_rewards: escrow_lock_account.remaining_reward_value,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn top_up_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, TopUpRewards<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Validate escrow is still active and has not expired
+    require_active(&ctx.accounts.escrow_lock_account, clock.unix_timestamp)?;
+    
+    // Validate amount is greater than 0
+    require!(
+        amount > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate the escrow stays within the supported reward bound
+    require!(
+        ctx.accounts.escrow_lock_account.total_reward_value
+            .checked_add(amount)
+            .is_some_and(|total| total <= MAX_REWARD_VALUE),
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    let escrow_wallets = escrow_wallet_infos(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    let wallet_count = escrow_wallets.len() as u64;
+    let weights = ctx.accounts.escrow_lock_account.weights();
+    
+    // Split the top-up like the lock, continuing the remainder from where the lock left
+    // off so the wallets stay within one token of the stored per-wallet split
+    let remainder_offset = ctx.accounts.escrow_lock_account.reward_remainder;
+    
+    log_step!("Topping up {} tokens across {} escrow wallets...", amount, wallet_count);
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    for (i, escrow_wallet) in escrow_wallets.iter().enumerate() {
+        let amount_for_wallet = wallet_share(amount, &weights, i as u64, remainder_offset)?;
+        transfer_to_escrow(
+            &cpi_program,
+            ctx.accounts.minter_reward_account.to_account_info(),
+            escrow_wallet.clone(),
+            ctx.accounts.minter.to_account_info(),
+            amount_for_wallet,
+        )?;
+        log_step!("Transferred {} to wallet {}", amount_for_wallet, i + 1);
+    }
+    
+    // Update reward totals
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    escrow_lock_account.total_reward_value = escrow_lock_account.total_reward_value
+        .checked_add(amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.remaining_reward_value = escrow_lock_account.remaining_reward_value
+        .checked_add(amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.reward_per_wallet = escrow_lock_account.total_reward_value
+        .checked_div(wallet_count)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.reward_remainder = escrow_lock_account.total_reward_value % wallet_count;
+    
+    msg!(
+        "✅ Top-up complete! Total reward value {}, {} remaining",
+        escrow_lock_account.total_reward_value,
+        escrow_lock_account.remaining_reward_value
+    );
+    
+    emit!(RewardsToppedUp {
+        token: escrow_lock_account.token,
+        minter: escrow_lock_account.minter,
+        amount,
+        total_reward_value: escrow_lock_account.total_reward_value,
+        remaining_rewards: escrow_lock_account.remaining_reward_value,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn add_reward_track(
+    ctx: Context<AddRewardTrack>,
+    track_index: u8,
+    reward_value: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    
+    // Validate tracks are added in order, up to the supported number
+    require!(
+        track_index == escrow_lock_account.reward_track_count && track_index < MAX_REWARD_TRACKS,
+        FluterByError::InvalidRewardTrack
+    );
+    
+    // Validate escrow is still active and has not expired
+    require_active(escrow_lock_account, clock.unix_timestamp)?;
+    
+    // Validate nobody has redeemed yet, so every holder is paid from every track
+    require!(
+        escrow_lock_account.remaining_reward_value == escrow_lock_account.total_reward_value,
+        FluterByError::RedemptionsInProgress
+    );
+    
+    // Validate reward value is greater than 0
+    require!(
+        reward_value > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    msg!("Adding reward track {} with {} tokens...", track_index, reward_value);
+    
+    transfer_to_escrow(
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.minter_track_account.to_account_info(),
+        ctx.accounts.track_vault.to_account_info(),
+        ctx.accounts.minter.to_account_info(),
+        reward_value,
+    )?;
+    
+    let reward_track = &mut ctx.accounts.reward_track;
+    reward_track.escrow = ctx.accounts.escrow_lock_account.key();
+    reward_track.track_index = track_index;
+    reward_track.reward_token = ctx.accounts.track_reward_mint.key();
+    reward_track.vault = ctx.accounts.track_vault.key();
+    reward_track.total_reward_value = reward_value;
+    reward_track.remaining_reward_value = reward_value;
+    
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    escrow_lock_account.reward_track_count += 1;
+    escrow_lock_account.open_reward_tracks += 1;
+    
+    msg!("✅ Reward track {} added!", track_index);
+    
+    emit!(RewardTrackAdded {
+        token: escrow_lock_account.token,
+        reward_token: ctx.accounts.track_reward_mint.key(),
+        track_index,
+        reward_value,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn withdraw_reward_track(
+    ctx: Context<WithdrawRewardTrack>,
+    track_index: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    
+    // Validate the escrow can no longer be redeemed: withdrawn, cancelled or drained,
+    // or expired past its grace period
+    let withdrawable_at = escrow_lock_account.expires_at
+        .checked_add(escrow_lock_account.grace_period)
+        .ok_or(FluterByError::InvalidExpiry)?;
+    require!(
+        matches!(
+            escrow_lock_account.status_at(clock.unix_timestamp),
+            EscrowState::Drained | EscrowState::Closed
+        ) || clock.unix_timestamp >= withdrawable_at,
+        FluterByError::EscrowNotExpired
+    );
+    
+    // Get the PDA signer seeds for authority
+    let token_key = escrow_lock_account.token;
+    let creator_key = escrow_lock_account.creator;
+    let bump = escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let amount_withdrawn = ctx.accounts.track_vault.amount;
+    if amount_withdrawn > 0 {
+        transfer_from_escrow(
+            &cpi_program,
+            ctx.accounts.track_vault.to_account_info(),
+            ctx.accounts.minter_track_account.to_account_info(),
+            ctx.accounts.escrow_lock_account.to_account_info(),
+            amount_withdrawn,
+            signer_seeds,
+        )?;
+    }
+    
+    // Close the vault, returning its rent to the minter
+    let cpi_accounts = token::CloseAccount {
+        account: ctx.accounts.track_vault.to_account_info(),
+        destination: ctx.accounts.minter.to_account_info(),
+        authority: ctx.accounts.escrow_lock_account.to_account_info(),
+    };
+    token::close_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds))?;
+    
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    escrow_lock_account.open_reward_tracks = escrow_lock_account.open_reward_tracks.saturating_sub(1);
+    
+    msg!("✅ Reward track {} withdrawn: {}", track_index, amount_withdrawn);
+    
+    emit!(RewardTrackWithdrawn {
+        token: token_key,
+        reward_token: ctx.accounts.reward_track.reward_token,
+        track_index,
+        amount_withdrawn,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn extend_expiry(
+    ctx: Context<ExtendExpiry>,
+    new_expiry: i64,
+) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate escrow is still open
+    require!(
+        matches!(
+            escrow_lock_account.status_at(clock.unix_timestamp),
+            EscrowState::Active | EscrowState::Expired
+        ),
+        FluterByError::EscrowInactive
+    );
+    
+    // Validate the redemption window only ever grows
+    require!(
+        new_expiry > escrow_lock_account.expires_at,
+        FluterByError::InvalidExpiry
+    );
+    
+    let old_expiry = escrow_lock_account.expires_at;
+    escrow_lock_account.expires_at = new_expiry;
+    // An extension can reopen an expired escrow, so the next expiry gets its own event
+    escrow_lock_account.expired_emitted = false;
+    escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    
+    msg!("Expiry extended from {} to {}", old_expiry, new_expiry);
+    
+    emit!(ExpiryExtended {
+        token: escrow_lock_account.token,
+        minter: escrow_lock_account.minter,
+        old_expires_at: old_expiry,
+        new_expires_at: new_expiry,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_incinerator(ctx: Context<SetIncinerator>) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    let incinerator = ctx.accounts.incinerator_account.key();
+    escrow_lock_account.incinerator = incinerator;
+    
+    msg!("Incinerator set to {}", incinerator);
+    
+    emit!(IncineratorSet {
+        token: escrow_lock_account.token,
+        incinerator,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_receipt_mint(ctx: Context<SetReceiptMint>) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    let receipt_mint = ctx.accounts.receipt_mint.key();
+    escrow_lock_account.receipt_mint = receipt_mint;
+    
+    msg!("Receipt mint set to {}", receipt_mint);
+    
+    emit!(ReceiptMintSet {
+        token: escrow_lock_account.token,
+        receipt_mint,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn initialize_redemption_log(ctx: Context<InitializeRedemptionLog>) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    let mut log = ctx.accounts.redemption_log.load_init()?;
+    log.escrow = escrow_lock_account.key();
+    escrow_lock_account.has_redemption_log = true;
+    
+    msg!("Redemption log created at {}", ctx.accounts.redemption_log.key());
+    
+    emit!(RedemptionLogInitialized {
+        token: escrow_lock_account.token,
+        redemption_log: ctx.accounts.redemption_log.key(),
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_escrow_paused(ctx: Context<SetEscrowPaused>, paused: bool) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    
+    escrow_lock_account.paused = paused;
+    
+    msg!("Escrow paused: {}", paused);
+    
+    emit!(EscrowPauseUpdated {
+        token: escrow_lock_account.token,
+        minter: escrow_lock_account.minter,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_expiry_action(ctx: Context<SetExpiryAction>, expiry_action: u8) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate the escrow is still running, so the action is set before leftovers exist
+    require_active(escrow_lock_account, clock.unix_timestamp)?;
+    
+    // Validate the action is supported and a commitment to burn is never taken back
+    require!(
+        matches!(expiry_action, EXPIRY_ACTION_RETURN | EXPIRY_ACTION_BURN),
+        FluterByError::InvalidExpiryAction
+    );
+    require!(
+        escrow_lock_account.expiry_action != EXPIRY_ACTION_BURN || expiry_action == EXPIRY_ACTION_BURN,
+        FluterByError::InvalidExpiryAction
+    );
+    
+    // Validate `settle_expired` can burn the leftovers; it only handles SPL Token rewards
+    require!(
+        expiry_action != EXPIRY_ACTION_BURN || ctx.accounts.reward_token_mint.owner == &token::ID,
+        FluterByError::InvalidExpiryAction
+    );
+    
+    escrow_lock_account.expiry_action = expiry_action;
+    
+    msg!("Expiry action set to {}", expiry_action);
+    
+    emit!(ExpiryActionSet {
+        token: escrow_lock_account.token,
+        expiry_action,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_rounding_mode(ctx: Context<SetRoundingMode>, rounding_mode: u8) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate the rounding mode is one of the supported ones
+    require!(
+        matches!(rounding_mode, ROUNDING_MODE_FLOOR | ROUNDING_MODE_CEIL | ROUNDING_MODE_NEAREST),
+        FluterByError::InvalidRoundingMode
+    );
+    
+    // Validate nobody has redeemed yet, so every holder is paid under the same rule
+    require!(
+        escrow_lock_account.redemption_count == 0,
+        FluterByError::RedemptionsInProgress
+    );
+    
+    escrow_lock_account.rounding_mode = rounding_mode;
+    
+    msg!("Rounding mode set to {}", rounding_mode);
+    
+    emit!(RoundingModeSet {
+        token: escrow_lock_account.token,
+        rounding_mode,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_fixed_rate(ctx: Context<SetFixedRate>, rate: u64) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate nobody has redeemed yet, so every holder is paid under the same model
+    require!(
+        escrow_lock_account.redemption_count == 0,
+        FluterByError::RedemptionsInProgress
+    );
+    
+    // A rate of 0 goes back to the proportional model the escrow was locked with
+    escrow_lock_account.mode = if rate > 0 { REWARD_MODE_FIXED_RATE } else { escrow_lock_account.lock_mode };
+    escrow_lock_account.rate = rate;
+    
+    msg!("Reward mode set to {} (rate {})", escrow_lock_account.mode, rate);
+    
+    emit!(FixedRateSet {
+        token: escrow_lock_account.token,
+        mode: escrow_lock_account.mode,
+        rate,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn set_redemption_cooldown(ctx: Context<SetRedemptionCooldown>, cooldown_secs: i64) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate the cooldown is not negative
+    require!(cooldown_secs >= 0, FluterByError::InvalidCooldown);
+    
+    escrow_lock_account.cooldown_secs = cooldown_secs;
+    
+    msg!("Redemption cooldown set to {} seconds", cooldown_secs);
+    
+    emit!(RedemptionCooldownSet {
+        token: escrow_lock_account.token,
+        cooldown_secs,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn resync_escrow_wallets<'info>(
+    ctx: Context<'_, '_, '_, 'info, ResyncEscrowWallets<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let wallet_count = escrow_lock_account.wallet_count as usize;
+    
+    // Validate one escrow wallet was passed per index
+    require!(
+        ctx.remaining_accounts.len() == wallet_count,
+        FluterByError::InvalidWalletCount
+    );
+    
+    let escrow_key = escrow_lock_account.key();
+    let mut escrow_wallets = [Pubkey::default(); MAX_ESCROW_WALLET_COUNT as usize];
+    let mut wallet_bumps = [0u8; MAX_ESCROW_WALLET_COUNT as usize];
+    
+    for (i, escrow_wallet) in ctx.remaining_accounts.iter().enumerate() {
+        let wallet_index = i as u8 + 1;
+        
+        // Validate the wallet is the canonical PDA for this index
+        let (expected_wallet, wallet_bump) = Pubkey::find_program_address(
+            &[
+                b"escrow_wallet",
+                escrow_lock_account.token.as_ref(),
+                escrow_lock_account.creator.as_ref(),
+                &[wallet_index],
+            ],
+            ctx.program_id,
+        );
+        require!(
+            escrow_wallet.key() == expected_wallet,
+            FluterByError::InvalidEscrowWalletIndex
+        );
+        
+        // Validate the wallet exists, holds the reward token and is controlled by the escrow
+        let wallet = read_token_account(escrow_wallet)?;
+        require!(
+            wallet.mint == escrow_lock_account.reward_token,
+            FluterByError::InvalidDistributionAmount
+        );
+        require!(
+            wallet.owner == escrow_key,
+            FluterByError::InvalidEscrowWalletAuthority
+        );
+        
+        escrow_wallets[i] = expected_wallet;
+        wallet_bumps[i] = wallet_bump;
+    }
+    
+    let old_escrow_wallets = escrow_lock_account.escrow_wallets[..wallet_count].to_vec();
+    escrow_lock_account.escrow_wallets = escrow_wallets;
+    escrow_lock_account.wallet_bumps = wallet_bumps;
+    
+    msg!("Escrow wallets resynced: {:?}", &escrow_wallets[..wallet_count]);
+    
+    emit!(EscrowWalletsResynced {
+        token: escrow_lock_account.token,
+        old_escrow_wallets,
+        new_escrow_wallets: escrow_wallets[..wallet_count].to_vec(),
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn mark_escrow_expired(ctx: Context<MarkEscrowExpired>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Validate escrow HAS expired
+    require!(
+        clock.unix_timestamp >= ctx.accounts.escrow_lock_account.expires_at,
+        FluterByError::EscrowNotExpired
+    );
+    
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    Ok(())
+}
+
+pub fn transfer_minter_authority(
+    ctx: Context<TransferMinterAuthority>,
+    new_minter: Pubkey,
+) -> Result<()> {
+    let escrow_lock_account = &mut ctx.accounts.escrow_lock_account;
+    let clock = Clock::get()?;
+    
+    // Validate the new minter is a real, different key
+    require!(
+        new_minter != Pubkey::default() && new_minter != escrow_lock_account.minter,
+        FluterByError::InvalidNewMinter
+    );
+    
+    let old_minter = escrow_lock_account.minter;
+    escrow_lock_account.minter = new_minter;
+    
+    // The escrow PDAs stay derived from `creator`; only the authority moves
+    msg!("Minter authority transferred from {} to {}", old_minter, new_minter);
+    
+    emit!(MinterAuthorityTransferred {
+        token: escrow_lock_account.token,
+        old_minter,
+        new_minter,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn close_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseEscrow<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow_lock_account = &ctx.accounts.escrow_lock_account;
+    
+    // Validate the escrow is finished: withdrawn, cancelled or drained
+    require!(
+        matches!(
+            escrow_lock_account.status_at(clock.unix_timestamp),
+            EscrowState::Drained | EscrowState::Closed
+        ),
+        FluterByError::EscrowNotExpired
+    );
+    
+    // Get the PDA signer seeds for authority
+    let token_key = escrow_lock_account.token;
+    let minter_key = escrow_lock_account.minter;
+    let creator_key = escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Validate every reward track has been withdrawn
+    require!(
+        escrow_lock_account.open_reward_tracks == 0,
+        FluterByError::EscrowNotEmpty
+    );
+    
+    // The rent goes to the minter unless they named another recipient
+    let rent_recipient = ctx.accounts.rent_recipient
+        .as_ref()
+        .map(|rent_recipient| rent_recipient.to_account_info())
+        .unwrap_or_else(|| ctx.accounts.minter.to_account_info());
+    
+    let escrow_wallets = escrow_wallet_infos(escrow_lock_account, ctx.remaining_accounts)?;
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    
+    // Close each escrow wallet, returning its rent to the rent recipient
+    for (i, escrow_wallet) in escrow_wallets.iter().enumerate() {
+        require!(
+            escrow_wallet_balance(escrow_wallet)? == 0,
+            FluterByError::EscrowNotEmpty
+        );
+        
+        let cpi_accounts = token_interface::CloseAccount {
+            account: escrow_wallet.clone(),
+            destination: rent_recipient.clone(),
+            authority: ctx.accounts.escrow_lock_account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds);
+        token_interface::close_account(cpi_ctx)?;
+        log_step!("Closed escrow wallet {}", i + 1);
+    }
+    
+    let event_seq = ctx.accounts.escrow_lock_account.next_event_seq();
+    ctx.accounts.escrow_lock_account.close(rent_recipient.clone())?;
+    
+    msg!("✅ Escrow closed! Rent returned to {}.", rent_recipient.key());
+    
+    emit!(EscrowClosed {
+        token: token_key,
+        minter: minter_key,
+        rent_recipient: rent_recipient.key(),
+        timestamp: clock.unix_timestamp,
+        event_seq,
+    });
+    
+    Ok(())
+}
+
+pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+    let escrow_info = ctx.accounts.escrow_lock_account.to_account_info();
+    let clock = Clock::get()?;
+    
+    // Validate the account is an escrow lock account, in whatever layout
+    require!(
+        escrow_info.try_borrow_data()?.starts_with(EscrowLockAccount::DISCRIMINATOR),
+        FluterByError::EscrowNotFound
+    );
+    
+    // Grow the account to the current layout. The new bytes are zeroed, which is the
+    // default of every field added since.
+    let new_len = 8 + EscrowLockAccount::INIT_SPACE;
+    if escrow_info.data_len() < new_len {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(escrow_info.lamports());
+        if rent_due > 0 {
+            let cpi_accounts = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: escrow_info.clone(),
+            };
+            anchor_lang::system_program::transfer(
+                CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+                rent_due,
+            )?;
+        }
+        escrow_info.resize(new_len)?;
+    }
+    
+    let mut escrow_lock_account = EscrowLockAccount::try_deserialize(&mut &escrow_info.try_borrow_data()?[..])?;
+    let from_version = escrow_lock_account.version;
+    
+    // Validate the escrow isn't from a newer layout than this program knows
+    require!(
+        from_version <= ESCROW_VERSION,
+        FluterByError::EscrowNeedsMigration
+    );
+    
+    // Version 3 added lock_mode. An escrow in a proportional mode was locked with it;
+    // for one already on a fixed rate the lock-time mode is lost, so fall back to live.
+    if from_version < 3 && escrow_lock_account.mode != REWARD_MODE_FIXED_RATE {
+        escrow_lock_account.lock_mode = escrow_lock_account.mode;
+    }
+    
+    escrow_lock_account.version = ESCROW_VERSION;
+    let event_seq = escrow_lock_account.next_event_seq();
+    escrow_lock_account.try_serialize(&mut &mut escrow_info.try_borrow_mut_data()?[..])?;
+    
+    msg!("Escrow {} migrated from version {} to {}", escrow_info.key(), from_version, ESCROW_VERSION);
+    
+    emit!(EscrowMigrated {
+        escrow: escrow_info.key(),
+        from_version,
+        to_version: ESCROW_VERSION,
+        timestamp: clock.unix_timestamp,
+        event_seq,
+    });
+    
+    Ok(())
+}
+
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    
+    program_config.admin = ctx.accounts.admin.key();
+    program_config.paused = false;
+    program_config.min_lock_duration = 0;
+    program_config.max_escrows_per_minter = 0;
+    
+    msg!("Program config initialized with admin {}", program_config.admin);
+    
+    Ok(())
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    
+    program_config.paused = paused;
+    
+    msg!("Program paused: {}", paused);
+    
+    emit!(PauseUpdated {
+        admin: program_config.admin,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn set_max_escrows_per_minter(ctx: Context<SetMaxEscrowsPerMinter>, max_escrows_per_minter: u64) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    
+    program_config.max_escrows_per_minter = max_escrows_per_minter;
+    
+    msg!("Maximum escrows per minter: {}", max_escrows_per_minter);
+    
+    emit!(MaxEscrowsPerMinterUpdated {
+        admin: program_config.admin,
+        max_escrows_per_minter,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn set_min_lock_duration(ctx: Context<SetMinLockDuration>, min_lock_duration: i64) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    
+    // Validate the minimum is not negative
+    require!(
+        min_lock_duration >= 0,
+        FluterByError::InvalidExpiry
+    );
+    
+    program_config.min_lock_duration = min_lock_duration;
+    
+    msg!("Minimum lock duration: {}s", min_lock_duration);
+    
+    emit!(MinLockDurationUpdated {
+        admin: program_config.admin,
+        min_lock_duration,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn authorize_redeemer(ctx: Context<AuthorizeRedeemer>, redeemer: Pubkey) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow = ctx.accounts.escrow_lock_account.key();
+    let redeemer_entry = &mut ctx.accounts.redeemer_entry;
+    
+    redeemer_entry.escrow = escrow;
+    redeemer_entry.redeemer = redeemer;
+    redeemer_entry.authorized_at = clock.unix_timestamp;
+    
+    msg!("Authorized redeemer {}", redeemer);
+    
+    emit!(RedeemerAuthorized {
+        escrow,
+        redeemer,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn revoke_redeemer(ctx: Context<RevokeRedeemer>, redeemer: Pubkey) -> Result<()> {
+    // The entry itself is closed to the minter by the account constraint
+    msg!("Revoked redeemer {}", redeemer);
+    
+    emit!(RedeemerRevoked {
+        escrow: ctx.accounts.escrow_lock_account.key(),
+        redeemer,
+        timestamp: Clock::get()?.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+pub fn initialize_escrow_wallet_2022(
+    ctx: Context<InitializeEscrowWallet2022>,
+    token: Pubkey,
+    wallet_index: u8,
+) -> Result<()> {
+    // Validate the wallet index is one that a lock can reference
+    require!(
+        (1..=MAX_ESCROW_WALLET_COUNT).contains(&wallet_index),
+        FluterByError::InvalidEscrowWalletIndex
+    );
+    
+    msg!("Initialized escrow wallet {}", wallet_index);
+    
+    emit!(EscrowWalletInitialized {
+        token,
+        minter: ctx.accounts.minter.key(),
+        escrow_wallet: ctx.accounts.escrow_wallet.key(),
+        wallet_index,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lock_funds_2022<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockFunds2022<'info>>,
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    params: LockParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    validate_lock(&ctx.accounts.escrow_lock_account, reward_value, ctx.accounts.minter_reward_account.amount, token_supply, ctx.accounts.token_mint.supply, minter, ctx.accounts.minter.key())?;
+    let terms = validate_lock_params(
+        params,
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.key()),
+        expiry,
+        &ctx.accounts.program_config,
+        clock.unix_timestamp,
+    )?;
+    let wallet_count = terms.wallet_count;
+    
+    // Validate the reward token argument matches the mint account
+    require!(
+        reward_token == ctx.accounts.reward_token_mint.key(),
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate one escrow wallet was passed per index
+    require!(
+        ctx.remaining_accounts.len() == wallet_count as usize,
+        FluterByError::InvalidWalletCount
+    );
+    
+    // Validate every wallet was set up before moving any tokens
+    for escrow_wallet in ctx.remaining_accounts.iter() {
+        validate_escrow_wallet_initialized(
+            escrow_wallet,
+            ctx.accounts.escrow_lock_account.key(),
+            reward_token,
+            &ctx.accounts.reward_token_program.key(),
+        )?;
+    }
+    
+    log_step!("Transferring {} tokens across {} escrow wallets...", reward_value, wallet_count);
+    
+    let cpi_program = ctx.accounts.reward_token_program.to_account_info();
+    let decimals = ctx.accounts.reward_token_mint.decimals;
+    let mut escrow_wallets = [Pubkey::default(); MAX_ESCROW_WALLET_COUNT as usize];
+    let mut wallet_bumps = [0u8; MAX_ESCROW_WALLET_COUNT as usize];
+    let mut received_value = 0u64;
+    
+    for (i, escrow_wallet) in ctx.remaining_accounts.iter().enumerate() {
+        let wallet_index = i as u8 + 1;
+        
+        // Validate the wallet is the PDA for this index
+        let (expected_wallet, wallet_bump) = Pubkey::find_program_address(
+            &[b"escrow_wallet", token.as_ref(), minter.as_ref(), &[wallet_index]],
+            ctx.program_id,
+        );
+        require!(
+            escrow_wallet.key() == expected_wallet,
+            FluterByError::InvalidEscrowWalletIndex
+        );
+        wallet_bumps[i] = wallet_bump;
+        
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.minter_reward_account.to_account_info(),
+            mint: ctx.accounts.reward_token_mint.to_account_info(),
+            to: escrow_wallet.clone(),
+            authority: ctx.accounts.minter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program.clone(), cpi_accounts);
+        let amount = wallet_share(reward_value, &bps_weights(&terms.wallet_weights[..wallet_count as usize]), i as u64, 0)?;
+        
+        // Measure what actually arrived, since transfer-fee mints withhold part of it
+        let balance_before = escrow_wallet_balance(escrow_wallet)?;
+        token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+        let received = escrow_wallet_balance(escrow_wallet)?
+            .checked_sub(balance_before)
+            .ok_or(FluterByError::DistributionCalculationOverflow)?;
+        received_value = received_value
+            .checked_add(received)
+            .ok_or(FluterByError::DistributionCalculationOverflow)?;
+        log_step!("Transferred {} to wallet {} ({} received)", amount, wallet_index, received);
+        
+        escrow_wallets[i] = escrow_wallet.key();
+    }
+    
+    require!(
+        received_value > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    log_step!("✅ All reward tokens transferred to escrow wallets!");
+    
+    // Record what the escrow wallets actually hold, not what was sent
+    init_escrow_lock(
+        &mut ctx.accounts.escrow_lock_account,
+        &mut ctx.accounts.escrow_counter,
+        &mut ctx.accounts.minter_account,
+        ctx.accounts.program_config.max_escrows_per_minter,
+        NewEscrow {
+            token,
+            reward_token,
+            minter,
+            reward_value: received_value,
+            token_supply,
+            expiry,
+            terms,
+            escrow_wallets,
+            wallet_bumps,
+            wallet_amounts: [0; MAX_ESCROW_WALLET_COUNT as usize],
+            lock_bump: ctx.bumps.escrow_lock_account,
+            token_decimals: ctx.accounts.token_mint.decimals,
+            reward_token_decimals: decimals,
+            minter_reward_account: ctx.accounts.minter_reward_account.key(),
+        },
+        clock.unix_timestamp,
+    )
+}
+
+pub fn redeem_rewards_2022<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewards2022<'info>>,
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<u64> {
+    validate_redeemer(&ctx.accounts.escrow_lock_account, ctx.accounts.redeemer_entry.is_some())?;
+    require_no_redemption_log(&ctx.accounts.escrow_lock_account)?;
+    require_no_receipt_mint(&ctx.accounts.escrow_lock_account)?;
+    
+    process_redemption(
+        &mut ctx.accounts.escrow_lock_account,
+        &ctx.accounts.user,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account,
+        None,
+        ctx.accounts.user_reward_account.to_account_info(),
+        ctx.accounts.treasury_reward_account.as_ref().map(|treasury| treasury.to_account_info()),
+        ctx.accounts.user_redemption.as_mut(),
+        &ctx.accounts.token_program,
+        ctx.accounts.reward_token_program.to_account_info(),
+        Some(&ctx.accounts.reward_token_mint),
+        ctx.remaining_accounts,
+        burn_amount,
+        min_reward_out,
+    )
+    .map(|(_, payout)| payout)
+}
+
+pub fn withdraw_expired_rewards_2022<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards2022<'info>>,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    
+    validate_expired_withdrawal(
+        &ctx.accounts.escrow_lock_account,
+        ctx.accounts.minter.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // Record the expiry, and surface it for indexers if nobody has marked it yet
+    ctx.accounts.escrow_lock_account.refresh_status(clock.unix_timestamp)?;
+    emit_escrow_expired(&mut ctx.accounts.escrow_lock_account, clock.unix_timestamp);
+    
+    msg!("🔓 Escrow has expired. Minter withdrawing remaining rewards...");
+    
+    // Get the PDA signer seeds for authority
+    let token_key = ctx.accounts.escrow_lock_account.token;
+    let creator_key = ctx.accounts.escrow_lock_account.creator;
+    let bump = ctx.accounts.escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Get current balance from each escrow wallet and transfer all to minter
+    let escrow_wallets = escrow_wallet_infos(&ctx.accounts.escrow_lock_account, ctx.remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    
+    let total_to_withdraw = wallet_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    let cpi_program = ctx.accounts.reward_token_program.to_account_info();
+    let decimals = ctx.accounts.reward_token_mint.decimals;
+    
+    // Transfer all funds from each non-empty wallet, logging the sweep once below
+    for (i, (escrow_wallet, balance)) in escrow_wallets.iter().zip(wallet_balances.iter().copied()).enumerate() {
+        if balance > 0 {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: escrow_wallet.clone(),
+                mint: ctx.accounts.reward_token_mint.to_account_info(),
+                to: ctx.accounts.minter_reward_account.to_account_info(),
+                authority: ctx.accounts.escrow_lock_account.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds);
+            token_interface::transfer_checked(cpi_ctx, balance, decimals)?;
+            
+            emit!(FundsWithdrawn {
+                mint: token_key,
+                minter: ctx.accounts.escrow_lock_account.minter,
+                amount: balance,
+                wallet_index: (i + 1) as u8,
+                timestamp: clock.unix_timestamp,
+                event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+            });
+        }
+    }
+    
+    report_sweep_drift(&mut ctx.accounts.escrow_lock_account, total_to_withdraw, clock.unix_timestamp);
+    
+    // Close the escrow
+    let remaining_rewards = ctx.accounts.escrow_lock_account.remaining_reward_value;
+    ctx.accounts.escrow_lock_account.transition_to(EscrowState::Closed)?;
+    ctx.accounts.escrow_lock_account.remaining_reward_value = 0;
+    
+    msg!("✅ Withdrawal complete! Swept {} from escrow wallets: {:?}", total_to_withdraw, wallet_balances);
+    
+    emit!(ExpiredRewardsWithdrawn {
+        token: ctx.accounts.escrow_lock_account.token,
+        minter: ctx.accounts.escrow_lock_account.minter,
+        amount_withdrawn: total_to_withdraw,
+        remaining_reward_value: remaining_rewards,
+        timestamp: clock.unix_timestamp,
+        event_seq: ctx.accounts.escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(total_to_withdraw)
+}
+
+/// Burns `burn_amount` of the user's main tokens and pays the reward to
+/// `recipient_reward_account`. Shared by every redemption instruction.
+/// 
+/// The main token always goes through the SPL Token `token_program`. The reward is
+/// paid through `reward_token_program`, which is SPL Token or Token-2022; see
+/// `transfer_reward_from_escrow` for how `reward_token_mint` picks the transfer.
+/// 
+/// With an `incinerator_account` the main tokens are transferred there instead of
+/// burned; the escrow accounts for them the same way. Returns the main tokens taken
+/// from the user (less than asked for if the burn was reduced) and the reward paid to
+/// the recipient, after fees.
+#[allow(clippy::too_many_arguments)]
+fn process_redemption<'info>(
+    escrow_lock_account: &mut Account<'info, EscrowLockAccount>,
+    user: &Signer<'info>,
+    token_mint: &Account<'info, token::Mint>,
+    user_token_account: &Account<'info, token::TokenAccount>,
+    incinerator_account: Option<&Account<'info, token::TokenAccount>>,
+    recipient_reward_account: AccountInfo<'info>,
+    treasury_reward_account: Option<AccountInfo<'info>>,
+    user_redemption: Option<&mut Account<'info, UserRedemption>>,
+    token_program: &Program<'info, token::Token>,
+    reward_token_program: AccountInfo<'info>,
+    reward_token_mint: Option<&InterfaceAccount<'info, token_interface::Mint>>,
+    remaining_accounts: &[AccountInfo<'info>],
+    burn_amount: u64,
+    min_reward_out: Option<u64>,
+) -> Result<(u64, u64)> {
+    let clock = Clock::get()?;
+    
+    reconcile_remaining_reward(escrow_lock_account, remaining_accounts, clock.unix_timestamp)?;
+    
+    let (burn_amount, reward_amount) = redemption_reward(
+        escrow_lock_account,
+        burn_amount,
+        user_token_account.amount,
+        clock.unix_timestamp,
+    )?;
+    
+    // Split off the protocol fee; the user receives the rest
+    let fee = redemption_fee(escrow_lock_account, reward_amount)?;
+    let payout = reward_amount - fee;
+    require!(
+        fee == 0 || treasury_reward_account.is_some(),
+        FluterByError::InvalidTreasury
+    );
+    
+    // Validate the payout meets the user's minimum before anything is burned
+    require!(
+        payout >= min_reward_out.unwrap_or(0),
+        FluterByError::SlippageExceeded
+    );
+    
+    require_unfrozen(
+        escrow_wallet_infos(escrow_lock_account, remaining_accounts)?,
+        &[Some(recipient_reward_account.clone()), treasury_reward_account.clone()],
+    )?;
+    
+    // Update remaining reward value and circulating supply before any CPI, and hold
+    // the processing guard until every transfer has gone through. If any CPI fails
+    // the whole instruction reverts, including these writes.
+    begin_redemption(escrow_lock_account)?;
+    
+    // Extra reward tracks are shared against the same pre-burn supply as the primary
+    // reward, so they are recorded before the burn leaves the supply
+    let track_accounts = remaining_accounts
+        .get(escrow_lock_account.wallet_count as usize..)
+        .unwrap_or(&[]);
+    let track_amounts = record_reward_tracks(
+        escrow_lock_account,
+        track_accounts,
+        read_token_account(&recipient_reward_account)?.owner,
+        burn_amount,
+        clock.unix_timestamp,
+    )?;
+    
+    record_redemption(escrow_lock_account, burn_amount, reward_amount, clock.unix_timestamp)?;
+    record_user_redemption(escrow_lock_account, user_redemption, user.key(), burn_amount, payout, clock.unix_timestamp)?;
+    
+    let cpi_program = token_program.to_account_info();
+    if let Some(incinerator_account) = incinerator_account {
+        // Send the user's FLBY tokens to the incinerator
+        log_step!("Sending {} FLBY tokens to the incinerator...", burn_amount);
+        let cpi_accounts_transfer = token::Transfer {
+            from: user_token_account.to_account_info(),
+            to: incinerator_account.to_account_info(),
+            authority: user.to_account_info(),
+        };
+        let cpi_ctx_transfer = CpiContext::new(cpi_program.clone(), cpi_accounts_transfer);
+        token::transfer(cpi_ctx_transfer, burn_amount)?;
+        log_step!("✅ Sent {} FLBY tokens to {}", burn_amount, incinerator_account.key());
+    } else {
+        // Burn the user's FLBY tokens
+        log_step!("Burning {} FLBY tokens...", burn_amount);
+        let cpi_accounts_burn = token::Burn {
+            mint: token_mint.to_account_info(),
+            from: user_token_account.to_account_info(),
+            authority: user.to_account_info(),
+        };
+        let cpi_ctx_burn = CpiContext::new(cpi_program.clone(), cpi_accounts_burn);
+        token::burn(cpi_ctx_burn, burn_amount)?;
+        log_step!("✅ Burned {} FLBY tokens", burn_amount);
+    }
+    
+    // Calculate how much to take from each of the escrow wallets
+    // Draw each wallet's share, cascading any shortfall to wallets with a balance
+    let escrow_wallets = escrow_wallet_infos(escrow_lock_account, remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    let (draws, fee_draws) = plan_redemption_draws(
+        &wallet_balances,
+        &escrow_lock_account.weights(),
+        payout,
+        fee,
+        escrow_lock_account.next_remainder_wallet as u64,
+    )?;
+    rotate_remainder_wallet(escrow_lock_account, payout, fee, clock.unix_timestamp);
+    
+    log_step!("Transferring {} reward tokens from {} escrow wallets to user (fee {})...", payout, escrow_wallets.len(), fee);
+    
+    // Get the PDA signer seeds for authority
+    let token_key = escrow_lock_account.token;
+    let creator_key = escrow_lock_account.creator;
+    let bump = escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    // Transfer the planned draw from each escrow wallet
+    for (i, (escrow_wallet, amount)) in escrow_wallets.iter().zip(draws).enumerate() {
+        if amount > 0 {
+            transfer_reward_from_escrow(
+                &reward_token_program,
+                reward_token_mint,
+                escrow_wallet.clone(),
+                recipient_reward_account.clone(),
+                escrow_lock_account.to_account_info(),
+                amount,
+                signer_seeds,
+            )?;
+            log_step!("Transferred {} from wallet {}", amount, i + 1);
+        }
+    }
+    
+    // Transfer the fee to the treasury
+    if let Some(treasury_reward_account) = treasury_reward_account {
+        for (i, (escrow_wallet, amount)) in escrow_wallets.iter().zip(fee_draws).enumerate() {
+            if amount > 0 {
+                transfer_reward_from_escrow(
+                    &reward_token_program,
+                    reward_token_mint,
+                    escrow_wallet.clone(),
+                    treasury_reward_account.clone(),
+                    escrow_lock_account.to_account_info(),
+                    amount,
+                    signer_seeds,
+                )?;
+                log_step!("Transferred fee {} from wallet {}", amount, i + 1);
+            }
+        }
+    }
+    
+    // Pay each reward track from its vault. Tracks are SPL Token only, and only
+    // attach to SPL Token escrows, whose reward program is the track program too.
+    pay_reward_tracks(
+        escrow_lock_account,
+        track_accounts,
+        &track_amounts,
+        &reward_token_program,
+        signer_seeds,
+        user.key(),
+        clock.unix_timestamp,
+    )?;
+    
+    // Every transfer went through; release the processing guard
+    escrow_lock_account.is_redeeming = false;
+    
+    msg!(
+        "✅ Redemption complete! Burned {}, paid {} (fee {}), {} remaining",
+        burn_amount,
+        payout,
+        fee,
+        escrow_lock_account.remaining_reward_value
+    );
+    
+    emit!(RewardsRedeemed {
+        token: escrow_lock_account.token,
+        user: user.key(),
+        tokens_burned: burn_amount,
+        rewards_received: payout,
+        fee,
+        remaining_rewards: escrow_lock_account.remaining_reward_value,
+        timestamp: clock.unix_timestamp,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok((burn_amount, payout))
+}
+
+/// Validates the arguments shared by every `lock_funds` variant.
+fn validate_lock(
+    escrow_lock_account: &EscrowLockAccount,
+    reward_value: u64,
+    minter_balance: u64,
+    token_supply: u64,
+    mint_supply: u64,
+    minter: Pubkey,
+    signer: Pubkey,
+) -> Result<()> {
+    // The lock account is `init_if_needed`, so an escrow that still exists for this
+    // token and minter loads here instead of failing at creation. Only a freshly
+    // created account has never been stamped with `created_at`.
+    require!(
+        escrow_lock_account.created_at == 0,
+        FluterByError::EscrowAlreadyExists
+    );
+    
+    // Validate reward value is greater than 0 and within the supported bound
+    require!(
+        reward_value > 0 && reward_value <= MAX_REWARD_VALUE,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate the minter can fund the whole lock before any wallet transfer starts
+    require!(
+        minter_balance >= reward_value,
+        FluterByError::InsufficientFunds
+    );
+    
+    // Validate token supply is greater than 0 and within the supported bound
+    require!(
+        token_supply > 0 && token_supply <= MAX_TOKEN_SUPPLY,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate the token supply matches the main token mint's actual supply
+    require!(
+        token_supply == mint_supply,
+        FluterByError::TokenSupplyMismatch
+    );
+    
+    // Validate minter matches the signer
+    require!(
+        minter == signer,
+        FluterByError::UnauthorizedMinter
+    );
+    
+    Ok(())
+}
+
+/// The validated settings of a new lock, resolved from its `LockParams`.
+struct LockTerms {
+    wallet_count: u8,
+    fee_bps: u16,
+    treasury: Pubkey,
+    grace_period: i64,
+    vesting_start: i64,
+    vesting_duration: i64,
+    requires_whitelist: bool,
+    mode: u8,
+    max_per_user: u64,
+    max_reward_per_redeem: u64,
+    wallet_weights: [u16; MAX_ESCROW_WALLET_COUNT as usize],
+}
+
+/// Validates the optional settings shared by every lock entrypoint and fills in
+/// their defaults.
+fn validate_lock_params(
+    params: LockParams,
+    treasury: Option<Pubkey>,
+    expiry: i64,
+    program_config: &ProgramConfig,
+    now: i64,
+) -> Result<LockTerms> {
+    let wallet_count = params.wallet_count.unwrap_or(DEFAULT_ESCROW_WALLET_COUNT);
+    
+    // Validate the wallet count is within the supported range
+    require!(
+        (MIN_ESCROW_WALLET_COUNT..=MAX_ESCROW_WALLET_COUNT).contains(&wallet_count),
+        FluterByError::InvalidWalletCount
+    );
+    
+    let fee_bps = params.fee_bps.unwrap_or(0);
+    let treasury = validate_fee(fee_bps, treasury)?;
+    
+    // Validate the escrow doesn't expire before it can be redeemed
+    require!(
+        expiry > now,
+        FluterByError::InvalidExpiry
+    );
+    validate_lock_duration(expiry, now, program_config)?;
+    
+    // Validate the grace period is not negative
+    let grace_period = params.grace_period.unwrap_or(0);
+    require!(
+        grace_period >= 0,
+        FluterByError::InvalidExpiry
+    );
+    
+    let (vesting_start, vesting_duration) = validate_vesting(params.vesting_start, params.vesting_duration, now)?;
+    
+    // Validate the reward mode is one of the supported formulas
+    let mode = params.mode.unwrap_or(REWARD_MODE_LIVE);
+    require!(
+        mode == REWARD_MODE_LIVE || mode == REWARD_MODE_SNAPSHOT,
+        FluterByError::InvalidRewardMode
+    );
+    
+    let wallet_weights = validate_weights(params.weights, wallet_count)?;
+    
+    Ok(LockTerms {
+        wallet_count,
+        fee_bps,
+        treasury,
+        grace_period,
+        vesting_start,
+        vesting_duration,
+        requires_whitelist: params.requires_whitelist.unwrap_or(false),
+        mode,
+        max_per_user: params.max_per_user.unwrap_or(0),
+        max_reward_per_redeem: params.max_reward_per_redeem.unwrap_or(0),
+        wallet_weights,
+    })
+}
+
+/// Everything a funded lock records on its escrow lock account.
+struct NewEscrow {
+    token: Pubkey,
+    reward_token: Pubkey,
+    minter: Pubkey,
+    /// What the escrow wallets now hold for this lock
+    reward_value: u64,
+    token_supply: u64,
+    expiry: i64,
+    terms: LockTerms,
+    escrow_wallets: [Pubkey; MAX_ESCROW_WALLET_COUNT as usize],
+    wallet_bumps: [u8; MAX_ESCROW_WALLET_COUNT as usize],
+    wallet_amounts: [u64; MAX_ESCROW_WALLET_COUNT as usize],
+    lock_bump: u8,
+    token_decimals: u8,
+    reward_token_decimals: u8,
+    minter_reward_account: Pubkey,
+}
+
+/// Initializes the escrow lock account once its wallets are funded, then registers
+/// the escrow and emits `FundsLocked`. Shared by every lock entrypoint.
+fn init_escrow_lock(
+    escrow_lock_account: &mut Account<EscrowLockAccount>,
+    escrow_counter: &mut EscrowCounter,
+    minter_account: &mut MinterAccount,
+    max_escrows_per_minter: u64,
+    new_escrow: NewEscrow,
+    now: i64,
+) -> Result<()> {
+    let NewEscrow { token, reward_token, minter, reward_value, token_supply, expiry, terms, .. } = new_escrow;
+    let wallet_count = terms.wallet_count;
+    
+    // Calculate reward per wallet (equal distribution across all wallets)
+    let reward_per_wallet = reward_value
+        .checked_div(wallet_count as u64)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    // Any remainder goes one token each to the first wallets
+    let reward_remainder = reward_value % wallet_count as u64;
+    
+    escrow_lock_account.token = token;
+    escrow_lock_account.reward_token = reward_token;
+    escrow_lock_account.minter = minter;
+    escrow_lock_account.creator = minter;
+    escrow_lock_account.total_reward_value = reward_value;
+    escrow_lock_account.remaining_reward_value = reward_value;
+    escrow_lock_account.reward_per_wallet = reward_per_wallet;
+    escrow_lock_account.reward_remainder = reward_remainder;
+    escrow_lock_account.total_token_supply = token_supply;
+    escrow_lock_account.escrow_wallets = new_escrow.escrow_wallets;
+    escrow_lock_account.wallet_bumps = new_escrow.wallet_bumps;
+    escrow_lock_account.lock_bump = new_escrow.lock_bump;
+    escrow_lock_account.wallet_count = wallet_count;
+    escrow_lock_account.expires_at = expiry;
+    escrow_lock_account.created_at = now;
+    escrow_lock_account.status = EscrowState::Active;
+    escrow_lock_account.version = ESCROW_VERSION;
+    escrow_lock_account.fee_bps = terms.fee_bps;
+    escrow_lock_account.treasury = terms.treasury;
+    escrow_lock_account.grace_period = terms.grace_period;
+    escrow_lock_account.vesting_start = terms.vesting_start;
+    escrow_lock_account.vesting_duration = terms.vesting_duration;
+    escrow_lock_account.requires_whitelist = terms.requires_whitelist;
+    escrow_lock_account.mode = terms.mode;
+    escrow_lock_account.lock_mode = terms.mode;
+    escrow_lock_account.snapshot_supply = token_supply;
+    escrow_lock_account.token_decimals = new_escrow.token_decimals;
+    escrow_lock_account.reward_token_decimals = new_escrow.reward_token_decimals;
+    escrow_lock_account.max_per_user = terms.max_per_user;
+    escrow_lock_account.max_reward_per_redeem = terms.max_reward_per_redeem;
+    escrow_lock_account.wallet_weights = terms.wallet_weights;
+    escrow_lock_account.wallet_amounts = new_escrow.wallet_amounts;
+    escrow_lock_account.minter_reward_account = new_escrow.minter_reward_account;
+    warn_on_decimals_mismatch(escrow_lock_account, now);
+    
+    msg!("✅ Locked {} {} for {} (supply {}) across {} wallets", reward_value, reward_token, token, token_supply, wallet_count);
+    log_step!("Reward per wallet: {}", reward_per_wallet);
+    
+    register_escrow(
+        escrow_counter,
+        minter_account,
+        escrow_lock_account,
+        max_escrows_per_minter,
+        now,
+    )?;
+    
+    emit!(FundsLocked {
+        mint: token,
+        minter,
+        value: reward_value,
+        expires_at: expiry,
+        timestamp: now,
+        reward_per_wallet,
+        reward_remainder,
+        escrow_wallets: new_escrow.escrow_wallets[..wallet_count as usize].to_vec(),
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+/// Validates the redemption fee of a new lock and returns the treasury to record.
+fn validate_fee(fee_bps: u16, treasury: Option<Pubkey>) -> Result<Pubkey> {
+    // Validate the fee is within the cap
+    require!(
+        fee_bps <= MAX_FEE_BPS,
+        FluterByError::FeeTooHigh
+    );
+    
+    // A fee needs somewhere to go
+    require!(
+        fee_bps == 0 || treasury.is_some(),
+        FluterByError::InvalidTreasury
+    );
+    
+    Ok(treasury.unwrap_or_default())
+}
+
+/// Emits `DecimalsMismatch` when the main token and reward token use different
+/// decimals. Rewards are a share of the pool (`burn_amount / supply`), so the
+/// formula itself needs no scaling, but the raw amounts may not read the way a
+/// minter expects.
+fn warn_on_decimals_mismatch(escrow_lock_account: &mut EscrowLockAccount, now: i64) {
+    if escrow_lock_account.token_decimals == escrow_lock_account.reward_token_decimals {
+        return;
+    }
+    
+    msg!(
+        "⚠️ Token has {} decimals but the reward token has {}",
+        escrow_lock_account.token_decimals,
+        escrow_lock_account.reward_token_decimals
+    );
+    
+    emit!(DecimalsMismatch {
+        token: escrow_lock_account.token,
+        reward_token: escrow_lock_account.reward_token,
+        token_decimals: escrow_lock_account.token_decimals,
+        reward_token_decimals: escrow_lock_account.reward_token_decimals,
+        timestamp: now,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+}
+
+/// Resolves an optional linear vesting schedule, returning `(0, 0)` when none is
+/// set. The start defaults to the lock time when only a duration is given.
+fn validate_vesting(
+    vesting_start: Option<i64>,
+    vesting_duration: Option<i64>,
+    now: i64,
+) -> Result<(i64, i64)> {
+    let Some(vesting_duration) = vesting_duration else {
+        // A start without a duration does not describe a schedule
+        require!(
+            vesting_start.is_none(),
+            FluterByError::InvalidVestingSchedule
+        );
+        return Ok((0, 0));
+    };
+    
+    require!(
+        vesting_duration > 0,
+        FluterByError::InvalidVestingSchedule
+    );
+    
+    Ok((vesting_start.unwrap_or(now), vesting_duration))
+}
+
+/// Counts a new escrow program-wide and in the minter's registry, registering the
+/// minter on their first lock, and assigns the escrow its `escrow_id`.
+fn register_escrow(
+    escrow_counter: &mut EscrowCounter,
+    minter_account: &mut MinterAccount,
+    escrow_lock_account: &mut Account<EscrowLockAccount>,
+    max_escrows_per_minter: u64,
+    now: i64,
+) -> Result<()> {
+    let minter = escrow_lock_account.minter;
+    
+    // Validate the minter is still under the program's escrow limit
+    require!(
+        max_escrows_per_minter == 0 || minter_account.total_escrows < max_escrows_per_minter,
+        FluterByError::MinterEscrowLimitReached
+    );
+    
+    // A freshly created registry is zeroed
+    if minter_account.minter == Pubkey::default() {
+        minter_account.minter = minter;
+        minter_account.registered_at = now;
+        
+        emit!(MinterRegistered {
+            minter,
+            timestamp: now,
+        });
+    }
+    
+    escrow_counter.total_escrows = escrow_counter.total_escrows
+        .checked_add(1)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    minter_account.total_escrows = minter_account.total_escrows
+        .checked_add(1)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    escrow_lock_account.escrow_id = escrow_counter.total_escrows;
+    
+    emit!(EscrowCreated {
+        minter,
+        token: escrow_lock_account.token,
+        escrow: escrow_lock_account.key(),
+        escrow_id: escrow_counter.total_escrows,
+        escrow_number: minter_account.total_escrows,
+        timestamp: now,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+/// Mints one claim receipt to the redeemer when the escrow has a receipt mint, signed
+/// by the escrow lock account as the mint authority. Escrows without one skip this.
+fn mint_redemption_receipt<'info>(
+    escrow_lock_account: &Account<'info, EscrowLockAccount>,
+    receipt_mint: Option<&Account<'info, token::Mint>>,
+    user_receipt_account: Option<&Account<'info, token::TokenAccount>>,
+    token_program: &Program<'info, token::Token>,
+) -> Result<()> {
+    if escrow_lock_account.receipt_mint == Pubkey::default() {
+        return Ok(());
+    }
+    
+    // Validate the receipt accounts were passed
+    let (Some(receipt_mint), Some(user_receipt_account)) = (receipt_mint, user_receipt_account) else {
+        return err!(FluterByError::InvalidReceiptMint);
+    };
+    
+    let token_key = escrow_lock_account.token;
+    let creator_key = escrow_lock_account.creator;
+    let bump = escrow_lock_account.lock_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow_lock",
+        token_key.as_ref(),
+        creator_key.as_ref(),
+        &[bump],
+    ]];
+    
+    let cpi_accounts = token::MintTo {
+        mint: receipt_mint.to_account_info(),
+        to: user_receipt_account.to_account_info(),
+        authority: escrow_lock_account.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, 1)?;
+    
+    msg!("✅ Minted a claim receipt to {}", user_receipt_account.key());
+    
+    Ok(())
+}
+
+/// Writes a redemption into the escrow's `RedemptionLog`, overwriting the oldest
+/// entry once the log is full. Escrows without a log skip this; once the minter has
+/// created one, every `redeem_rewards` call must pass it.
+fn record_redemption_log(
+    escrow_lock_account: &EscrowLockAccount,
+    redemption_log: Option<&AccountLoader<RedemptionLog>>,
+    user: Pubkey,
+    burn_amount: u64,
+    reward_amount: u64,
+) -> Result<()> {
+    if !escrow_lock_account.has_redemption_log {
+        return Ok(());
+    }
+    
+    // Validate the log was passed
+    let Some(redemption_log) = redemption_log else {
+        return err!(FluterByError::RedemptionLogRequired);
+    };
+    
+    let mut log = redemption_log.load_mut()?;
+    let index = (log.total_entries % REDEMPTION_LOG_CAPACITY as u64) as usize;
+    log.entries[index] = RedemptionLogEntry {
+        user,
+        burn_amount,
+        reward_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    log.total_entries = log.total_entries
+        .checked_add(1)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    Ok(())
+}
+
+/// Fails if the escrow keeps a `RedemptionLog`. Only `redeem_rewards` writes to the
+/// log, so the other redemption instructions are closed to logged escrows rather than
+/// leave gaps in the record.
+fn require_no_redemption_log(escrow_lock_account: &EscrowLockAccount) -> Result<()> {
+    require!(
+        !escrow_lock_account.has_redemption_log,
+        FluterByError::RedemptionLogRequired
+    );
+    
+    Ok(())
+}
+
+/// Fails if the escrow has a receipt mint. Only `redeem_rewards` mints receipts, so
+/// the other redemption instructions are closed to such escrows rather than let a
+/// redeemer go without one.
+fn require_no_receipt_mint(escrow_lock_account: &EscrowLockAccount) -> Result<()> {
+    require!(
+        escrow_lock_account.receipt_mint == Pubkey::default(),
+        FluterByError::ReceiptMintRequired
+    );
+    
+    Ok(())
+}
+
+/// Validates one escrow's accounts in a batch redemption, the checks `RedeemRewards`
+/// makes through its account constraints.
+fn validate_batch_redemption(
+    escrow_lock_account: &Account<'_, EscrowLockAccount>,
+    user: Pubkey,
+    token_mint: &Account<'_, token::Mint>,
+    user_token_account: &Account<'_, token::TokenAccount>,
+    user_reward_account: &Account<'_, token::TokenAccount>,
+    treasury_reward_account: Option<&Account<'_, token::TokenAccount>>,
+) -> Result<()> {
+    // Validate the main token accounts belong to this escrow and user
+    require!(
+        token_mint.key() == escrow_lock_account.token
+            && user_token_account.mint == escrow_lock_account.token
+            && user_token_account.owner == user,
+        FluterByError::TokenMismatch
+    );
+    
+    // Validate the reward goes to the user, and never back into an escrow wallet
+    require!(
+        user_reward_account.mint == escrow_lock_account.reward_token
+            && user_reward_account.owner == user
+            && !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+                .contains(&user_reward_account.key()),
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate the treasury, when passed, is the escrow's
+    require!(
+        !matches!(treasury_reward_account, Some(treasury) if treasury.key() != escrow_lock_account.treasury),
+        FluterByError::InvalidTreasury
+    );
+    
+    Ok(())
+}
+
+/// Fails unless the user may redeem: either the escrow is open to every holder, or
+/// the user's `RedeemerEntry` was passed.
+fn validate_redeemer(escrow_lock_account: &EscrowLockAccount, has_redeemer_entry: bool) -> Result<()> {
+    require!(
+        !escrow_lock_account.requires_whitelist || has_redeemer_entry,
+        FluterByError::RedeemerNotAuthorized
+    );
+    
+    Ok(())
+}
+
+/// Validates a redemption of `burn_amount` main tokens against the escrow and
+/// returns the amount to burn and the reward it pays out (see `quote_reward`).
+fn redemption_reward(
+    escrow_lock_account: &EscrowLockAccount,
+    burn_amount: u64,
+    token_balance: u64,
+    now: i64,
+) -> Result<(u64, u64)> {
+    // Validate the minter hasn't paused this escrow
+    require!(
+        !escrow_lock_account.paused,
+        FluterByError::EscrowPaused
+    );
+    
+    // Validate user has enough tokens to burn
+    require!(
+        token_balance >= burn_amount,
+        FluterByError::InsufficientTokenBalance
+    );
+    
+    quote_reward(escrow_lock_account, burn_amount, now)
+}
+
+/// Returns the amount to burn and the reward it pays out right now for a request to
+/// burn `burn_amount` main tokens, as long as the escrow is still redeemable.
+/// 
+/// When the remaining pool can't cover the whole burn, the burn is reduced to the
+/// most the pool can pay for, so the last redeemer drains it instead of failing.
+fn quote_reward(
+    escrow_lock_account: &EscrowLockAccount,
+    burn_amount: u64,
+    now: i64,
+) -> Result<(u64, u64)> {
+    // Validate the escrow has a supply to share rewards against. Locking requires
+    // a non-zero supply; this keeps an account that breaks that invariant from
+    // surfacing as a division overflow below.
+    require!(
+        escrow_lock_account.total_token_supply > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate there are rewards left to pay out
+    require!(
+        escrow_lock_account.remaining_reward_value > 0,
+        FluterByError::InsufficientFunds
+    );
+    
+    // Validate escrow is still active and has not expired
+    require_active(escrow_lock_account, now)?;
+    
+    // Validate burn amount is greater than 0
+    require!(
+        burn_amount > 0,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Validate the burn fits within the recorded supply, so a supply set too low
+    // at lock time cannot pay out more than the whole pool
+    require!(
+        burn_amount <= escrow_lock_account.total_token_supply,
+        FluterByError::InvalidDistributionAmount
+    );
+    
+    // Fixed-rate mode pays `rate` per burned token, however many others redeem, as
+    // long as the vested part of the pool covers it
+    if escrow_lock_account.mode == REWARD_MODE_FIXED_RATE {
+        let reward_amount = burn_amount
+            .checked_mul(escrow_lock_account.rate)
+            .ok_or(FluterByError::DistributionCalculationOverflow)?;
+        let vested_reward_value = vested_reward_value(escrow_lock_account, escrow_lock_account.remaining_reward_value, now)?;
+        require!(
+            reward_amount <= vested_reward_value,
+            FluterByError::InsufficientFunds
+        );
+        require_within_reward_cap(escrow_lock_account, reward_amount)?;
+        
+        return Ok((burn_amount, reward_amount));
+    }
+    
+    // Live mode shares what is left among the supply still outstanding. Snapshot mode
+    // shares the whole pool among the supply recorded at lock time, so a holder's
+    // reward does not depend on when others redeem.
+    let (reward_pool, supply) = if escrow_lock_account.mode == REWARD_MODE_SNAPSHOT {
+        (escrow_lock_account.total_reward_value, escrow_lock_account.snapshot_supply)
+    } else {
+        (escrow_lock_account.remaining_reward_value, escrow_lock_account.total_token_supply)
+    };
+    
+    // Only the vested part of the pool can be redeemed so far
+    let vested_reward_value = vested_reward_value(escrow_lock_account, reward_pool, now)?;
+    require!(
+        vested_reward_value > 0,
+        FluterByError::RewardsNotVested
+    );
+    
+    // Calculate proportional reward based on burned tokens
+    // reward = (burn_amount / supply) * vested_reward_value
+    let mut burn_amount = burn_amount;
+    let rounding_mode = escrow_lock_account.rounding_mode;
+    let mut reward_amount = proportional_reward(burn_amount, vested_reward_value, supply, rounding_mode)?;
+    
+    // When the pool is short, burn only what it can pay for and leave the rest of
+    // the user's tokens unburned:
+    // burn = (remaining_reward_value / vested_reward_value) * supply
+    if reward_amount > escrow_lock_account.remaining_reward_value {
+        let requested_burn = burn_amount;
+        burn_amount = (escrow_lock_account.remaining_reward_value as u128)
+            .checked_mul(supply as u128)
+            .and_then(|x| x.checked_div(vested_reward_value as u128))
+            .ok_or(FluterByError::DistributionCalculationOverflow)? as u64;
+        reward_amount = proportional_reward(burn_amount, vested_reward_value, supply, rounding_mode)?;
+        
+        msg!("Remaining rewards only cover {} of the {} tokens; burning that instead", burn_amount, requested_burn);
+    }
+    
+    // Validate the pool can pay for at least one token
+    require!(
+        burn_amount > 0,
+        FluterByError::InsufficientFunds
+    );
+    
+    // Validate the burn earns something, so tokens are never burned for nothing
+    require!(
+        reward_amount > 0,
+        FluterByError::RewardRoundsToZero
+    );
+    
+    // Validate rounding up never pays more than the escrow holds
+    require!(
+        reward_amount <= escrow_lock_account.remaining_reward_value,
+        FluterByError::InsufficientFunds
+    );
+    
+    require_within_reward_cap(escrow_lock_account, reward_amount)?;
+    
+    Ok((burn_amount, reward_amount))
+}
+
+/// Validates the reward stays within the escrow's per-redemption cap. A larger burn
+/// reverts rather than being scaled down, so nothing is burned without full payment.
+fn require_within_reward_cap(escrow_lock_account: &EscrowLockAccount, reward_amount: u64) -> Result<()> {
+    require!(
+        escrow_lock_account.max_reward_per_redeem == 0
+            || reward_amount <= escrow_lock_account.max_reward_per_redeem,
+        FluterByError::RewardCapExceeded
+    );
+    
+    Ok(())
+}
+
+/// Returns `burn_amount`'s share of `reward_pool` when it is shared among `supply`,
+/// rounded by `rounding_mode`.
+/// 
+/// Only a share worth at least one whole token is rounded up, so burns too small to
+/// earn anything still floor to 0 instead of each collecting a token. Since
+/// `burn_amount <= supply`, rounding up never takes the share past `reward_pool`.
+fn proportional_reward(burn_amount: u64, reward_pool: u64, supply: u64, rounding_mode: u8) -> Result<u64> {
+    let product = (burn_amount as u128)
+        .checked_mul(reward_pool as u128)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    let supply = supply as u128;
+    let reward_amount = product
+        .checked_div(supply)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    let remainder = product % supply;
+    
+    let round_up = reward_amount > 0
+        && match rounding_mode {
+            ROUNDING_MODE_CEIL => remainder > 0,
+            ROUNDING_MODE_NEAREST => remainder * 2 >= supply,
+            _ => false,
+        };
+    
+    Ok((reward_amount + round_up as u128) as u64)
+}
+
+/// Returns the part of `reward_pool` unlocked by the escrow's linear vesting
+/// schedule at `now`, or all of it when no schedule is set.
+fn vested_reward_value(escrow_lock_account: &EscrowLockAccount, reward_pool: u64, now: i64) -> Result<u64> {
+    if escrow_lock_account.vesting_duration == 0 {
+        return Ok(reward_pool);
+    }
+    
+    // elapsed is clamped to [0, vesting_duration], so the result never exceeds the pool
+    let elapsed = now
+        .saturating_sub(escrow_lock_account.vesting_start)
+        .clamp(0, escrow_lock_account.vesting_duration);
+    let vested = (reward_pool as u128)
+        .checked_mul(elapsed as u128)
+        .and_then(|x| x.checked_div(escrow_lock_account.vesting_duration as u128))
+        .ok_or(FluterByError::DistributionCalculationOverflow)? as u64;
+    
+    Ok(vested)
+}
+
+/// Returns the protocol fee taken out of a `reward_amount` redemption.
+fn redemption_fee(escrow_lock_account: &EscrowLockAccount, reward_amount: u64) -> Result<u64> {
+    let fee = (reward_amount as u128)
+        .checked_mul(escrow_lock_account.fee_bps as u128)
+        .and_then(|x| x.checked_div(BPS_DENOMINATOR as u128))
+        .ok_or(FluterByError::DistributionCalculationOverflow)? as u64;
+    
+    Ok(fee)
+}
+
+/// Brings `remaining_reward_value` in line with what the escrow wallets actually hold.
+/// 
+/// Redemptions are then sized from the live balances rather than a recorded value
+/// that may have drifted (tokens sent to a wallet directly, or a bug). Since the
+/// redemption draws exactly its reward from the wallets, the recorded value matches
+/// their live sum again once it completes.
+fn reconcile_remaining_reward(
+    escrow_lock_account: &mut Account<EscrowLockAccount>,
+    remaining_accounts: &[AccountInfo],
+    now: i64,
+) -> Result<()> {
+    let wallet_balance_total = escrow_wallet_balance_total(escrow_lock_account, remaining_accounts)?;
+    let recorded = escrow_lock_account.remaining_reward_value;
+    if wallet_balance_total == recorded {
+        return Ok(());
+    }
+    
+    msg!("Reconciling remaining rewards from {} to the wallets' {}", recorded, wallet_balance_total);
+    escrow_lock_account.remaining_reward_value = wallet_balance_total;
+    
+    emit!(RemainingRewardReconciled {
+        token: escrow_lock_account.token,
+        recorded_value: recorded,
+        wallet_balance_total,
+        timestamp: now,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+    
+    Ok(())
+}
+
+/// Emits `RemainingRewardReconciled` when a sweep that empties the escrow moved a
+/// different amount than its recorded `remaining_reward_value`, so accounting drift
+/// is surfaced before the escrow is closed instead of vanishing with it.
+fn report_sweep_drift(escrow_lock_account: &mut EscrowLockAccount, swept: u64, now: i64) {
+    let recorded = escrow_lock_account.remaining_reward_value;
+    if swept == recorded {
+        return;
+    }
+    
+    msg!("⚠️ Swept {} but {} was recorded as remaining", swept, recorded);
+    
+    emit!(RemainingRewardReconciled {
+        token: escrow_lock_account.token,
+        recorded_value: recorded,
+        wallet_balance_total: swept,
+        timestamp: now,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+}
+
+/// Sets the escrow's processing guard, failing if a redemption is already underway.
+fn begin_redemption(escrow_lock_account: &mut EscrowLockAccount) -> Result<()> {
+    require!(
+        !escrow_lock_account.is_redeeming,
+        FluterByError::RedemptionInProgress
+    );
+    escrow_lock_account.is_redeeming = true;
+    
+    Ok(())
+}
+
+/// Records a paid-out redemption on the escrow lock account.
+/// 
+/// The burned tokens leave circulation, so they are taken out of
+/// `total_token_supply` to keep later redemptions proportional to the live supply.
+/// The lifetime redemption statistics are updated alongside.
+fn record_redemption(
+    escrow_lock_account: &mut EscrowLockAccount,
+    burn_amount: u64,
+    reward_amount: u64,
+    now: i64,
+) -> Result<()> {
+    escrow_lock_account.remaining_reward_value = escrow_lock_account.remaining_reward_value
+        .checked_sub(reward_amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.total_token_supply = escrow_lock_account.total_token_supply
+        .checked_sub(burn_amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    escrow_lock_account.total_burned = escrow_lock_account.total_burned
+        .checked_add(burn_amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.redemption_count = escrow_lock_account.redemption_count
+        .checked_add(1)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    escrow_lock_account.last_redeemed_at = now;
+    
+    // Report how long holders took to start redeeming, once per escrow
+    if escrow_lock_account.first_redemption_at == 0 {
+        escrow_lock_account.first_redemption_at = now;
+        
+        emit!(FirstRedemption {
+            token: escrow_lock_account.token,
+            created_at: escrow_lock_account.created_at,
+            seconds_since_lock: now.saturating_sub(escrow_lock_account.created_at),
+            timestamp: now,
+            event_seq: escrow_lock_account.next_event_seq(),
+        });
+    }
+    
+    // A fully drained pool can no longer pay out
+    escrow_lock_account.refresh_status(now)?;
+    
+    Ok(())
+}
+
+/// Adds a redemption to the user's cumulative record and enforces the escrow's
+/// per-user cap and redemption cooldown.
+/// 
+/// The record is optional while the escrow has neither (`max_per_user == 0` and
+/// `cooldown_secs == 0`); once either is set every redemption must pass it, so the
+/// running total and the last redemption time can't be skipped.
+fn record_user_redemption(
+    escrow_lock_account: &Account<EscrowLockAccount>,
+    user_redemption: Option<&mut Account<UserRedemption>>,
+    user: Pubkey,
+    burn_amount: u64,
+    payout: u64,
+    now: i64,
+) -> Result<()> {
+    let max_per_user = escrow_lock_account.max_per_user;
+    let cooldown_secs = escrow_lock_account.cooldown_secs;
+    let Some(user_redemption) = user_redemption else {
+        require!(
+            max_per_user == 0 && cooldown_secs == 0,
+            FluterByError::UserRedemptionRequired
+        );
+        return Ok(());
+    };
+    
+    // Validate the user's previous redemption is at least the cooldown ago
+    require!(
+        user_redemption.last_redeemed_at == 0
+            || now >= user_redemption.last_redeemed_at.saturating_add(cooldown_secs),
+        FluterByError::CooldownActive
+    );
+    
+    // A freshly created record is zeroed; stamp it with its escrow and user
+    user_redemption.escrow = escrow_lock_account.key();
+    user_redemption.user = user;
+    user_redemption.total_burned = user_redemption.total_burned
+        .checked_add(burn_amount)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    user_redemption.total_claimed = user_redemption.total_claimed
+        .checked_add(payout)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    user_redemption.last_redeemed_at = now;
+    
+    require!(
+        max_per_user == 0 || user_redemption.total_burned <= max_per_user,
+        FluterByError::UserCapExceeded
+    );
+    
+    Ok(())
+}
+
+/// Validates the reward track accounts passed after the escrow wallets and records
+/// what each track pays for this burn.
+/// 
+/// Each track is passed as a (reward track, track vault, destination) triple, in
+/// track order. The destination must belong to the same owner as the primary reward
+/// account. Tracks follow the escrow's reward mode and vesting, but charge no fee.
+fn record_reward_tracks(
+    escrow_lock_account: &Account<EscrowLockAccount>,
+    track_accounts: &[AccountInfo],
+    recipient: Pubkey,
+    burn_amount: u64,
+    now: i64,
+) -> Result<Vec<u64>> {
+    let track_count = escrow_lock_account.reward_track_count as usize;
+    require!(
+        track_accounts.len() >= track_count * 3,
+        FluterByError::RewardTracksRequired
+    );
+    
+    let mut track_amounts = Vec::with_capacity(track_count);
+    for (i, accounts) in track_accounts[..track_count * 3].chunks(3).enumerate() {
+        let (track_info, vault, destination) = (&accounts[0], &accounts[1], &accounts[2]);
+        
+        require!(
+            *track_info.owner == crate::ID,
+            FluterByError::InvalidRewardTrack
+        );
+        let mut track = RewardTrack::try_deserialize(&mut &track_info.try_borrow_data()?[..])?;
+        require!(
+            track.escrow == escrow_lock_account.key()
+                && track.track_index as usize == i
+                && vault.key() == track.vault,
+            FluterByError::InvalidRewardTrack
+        );
+        
+        let destination_account = read_token_account(destination)?;
+        require!(
+            destination_account.mint == track.reward_token && destination_account.owner == recipient,
+            FluterByError::InvalidRewardTrack
+        );
+        
+        // Same formula as the primary reward, against this track's pool
+        let (reward_pool, supply) = if escrow_lock_account.mode == REWARD_MODE_SNAPSHOT {
+            (track.total_reward_value, escrow_lock_account.snapshot_supply)
+        } else {
+            (track.remaining_reward_value, escrow_lock_account.total_token_supply)
+        };
+        let vested_reward_value = vested_reward_value(escrow_lock_account, reward_pool, now)?;
+        let amount = ((burn_amount as u128)
+            .checked_mul(vested_reward_value as u128)
+            .and_then(|x| x.checked_div(supply as u128))
+            .ok_or(FluterByError::DistributionCalculationOverflow)? as u64)
+            .min(track.remaining_reward_value);
+        
+        track.remaining_reward_value -= amount;
+        track.try_serialize(&mut &mut track_info.try_borrow_mut_data()?[..])?;
+        track_amounts.push(amount);
+    }
+    
+    Ok(track_amounts)
+}
+
+/// Transfers the amounts recorded by `record_reward_tracks` from each track vault
+/// to its destination.
+fn pay_reward_tracks<'info>(
+    escrow_lock_account: &mut Account<'info, EscrowLockAccount>,
+    track_accounts: &[AccountInfo<'info>],
+    track_amounts: &[u64],
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    user: Pubkey,
+    now: i64,
+) -> Result<()> {
+    for (i, (accounts, amount)) in track_accounts.chunks(3).zip(track_amounts.iter().copied()).enumerate() {
+        if amount > 0 {
+            transfer_from_escrow(
+                token_program,
+                accounts[1].clone(),
+                accounts[2].clone(),
+                escrow_lock_account.to_account_info(),
+                amount,
+                signer_seeds,
+            )?;
+        }
+        
+        emit!(RewardTrackRedeemed {
+            token: escrow_lock_account.token,
+            track_index: i as u8,
+            user,
+            amount,
+            timestamp: now,
+            event_seq: escrow_lock_account.next_event_seq(),
+        });
+    }
+    
+    Ok(())
+}
+
+/// Validates that the escrow is active at `now`, i.e. open, unexpired and not drained.
+fn require_active(escrow_lock_account: &EscrowLockAccount, now: i64) -> Result<()> {
+    require_current_version(escrow_lock_account)?;
+    
+    match escrow_lock_account.status_at(now) {
+        EscrowState::Active => Ok(()),
+        EscrowState::Expired => err!(FluterByError::EscrowExpired),
+        EscrowState::Drained | EscrowState::Closed => err!(FluterByError::EscrowInactive),
+    }
+}
+
+/// Returns the memo `redeem_rewards` attaches to a payout when the memo program is passed.
+fn redemption_memo(escrow_id: u64, redeemer: Pubkey) -> String {
+    format!("fluter-by redemption: escrow {} redeemer {}", escrow_id, redeemer)
+}
+
+/// Validates the escrow has been migrated to the current account layout.
+fn require_current_version(escrow_lock_account: &EscrowLockAccount) -> Result<()> {
+    require!(
+        escrow_lock_account.version == ESCROW_VERSION,
+        FluterByError::EscrowNeedsMigration
+    );
+    
+    Ok(())
+}
+
+/// Validates that `minter` may sweep the remaining rewards of an expired escrow.
+fn validate_expired_withdrawal(
+    escrow_lock_account: &EscrowLockAccount,
+    minter: Pubkey,
+    now: i64,
+) -> Result<()> {
+    validate_expired_settlement(escrow_lock_account, now)?;
+    
+    // Validate caller is the minter
+    require!(
+        escrow_lock_account.minter == minter,
+        FluterByError::UnauthorizedMinter
+    );
+    
+    // Validate the escrow hasn't committed its leftovers to being burned
+    require!(
+        escrow_lock_account.expiry_action == EXPIRY_ACTION_RETURN,
+        FluterByError::LeftoversCommittedToBurn
+    );
+    
+    Ok(())
+}
+
+/// Validates that an escrow is past its expiry and grace period with rewards left to
+/// settle, whoever settles them.
+fn validate_expired_settlement(escrow_lock_account: &EscrowLockAccount, now: i64) -> Result<()> {
+    // Validate escrow is still open
+    require!(
+        matches!(
+            escrow_lock_account.status_at(now),
+            EscrowState::Active | EscrowState::Expired
+        ),
+        FluterByError::EscrowInactive
+    );
+    
+    // Validate escrow HAS expired and the grace period for in-flight redemptions is over
+    let withdrawable_at = escrow_lock_account.expires_at
+        .checked_add(escrow_lock_account.grace_period)
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    require!(
+        now >= withdrawable_at,
+        FluterByError::EscrowNotExpired
+    );
+    
+    // Check if there are any rewards left to withdraw
+    require!(
+        escrow_lock_account.remaining_reward_value > 0,
+        FluterByError::InsufficientFunds
+    );
+    
+    Ok(())
+}
+
+/// Emits `EscrowExpired` the first time it is called for an escrow past its expiry.
+fn emit_escrow_expired(escrow_lock_account: &mut EscrowLockAccount, now: i64) {
+    if now < escrow_lock_account.expires_at || escrow_lock_account.expired_emitted {
+        return;
+    }
+    
+    escrow_lock_account.expired_emitted = true;
+    
+    emit!(EscrowExpired {
+        mint: escrow_lock_account.token,
+        minter: escrow_lock_account.minter,
+        total_value: escrow_lock_account.total_reward_value,
+        remaining: escrow_lock_account.remaining_reward_value,
+        timestamp: now,
+        event_seq: escrow_lock_account.next_event_seq(),
+    });
+}
+
+/// Returns the escrow wallets passed in `remaining_accounts`, checked against
+/// the addresses recorded on the escrow lock account at lock time, the escrow's
+/// reward token mint, and the escrow lock account as their authority.
+fn escrow_wallet_infos<'a, 'info>(
+    escrow_lock_account: &Account<'_, EscrowLockAccount>,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    let wallet_count = escrow_lock_account.wallet_count as usize;
+    
+    require!(
+        remaining_accounts.len() >= wallet_count,
+        FluterByError::InvalidWalletCount
+    );
+    
+    let escrow_wallets = &remaining_accounts[..wallet_count];
+    for (escrow_wallet, expected_wallet) in escrow_wallets.iter().zip(escrow_lock_account.escrow_wallets.iter()) {
+        require!(
+            escrow_wallet.key() == *expected_wallet,
+            FluterByError::InvalidEscrowWalletIndex
+        );
+        
+        let wallet = read_token_account(escrow_wallet)?;
+        require!(
+            wallet.mint == escrow_lock_account.reward_token,
+            FluterByError::InvalidDistributionAmount
+        );
+        require!(
+            wallet.owner == escrow_lock_account.key(),
+            FluterByError::InvalidEscrowWalletAuthority
+        );
+    }
+    
+    Ok(escrow_wallets)
+}
+
+/// Validates that an escrow wallet was created with `initialize_escrow_wallet` (or its
+/// Token-2022 variant): a token account of the reward token under `token_program`,
+/// controlled by the escrow lock account.
+/// 
+/// Locking checks this before transferring, so a skipped wallet fails with
+/// `EscrowWalletNotInitialized` instead of a token program error partway through.
+fn validate_escrow_wallet_initialized(
+    escrow_wallet: &AccountInfo,
+    escrow_lock_account: Pubkey,
+    reward_token: Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require!(
+        escrow_wallet.owner == token_program && !escrow_wallet.data_is_empty(),
+        FluterByError::EscrowWalletNotInitialized
+    );
+    
+    let wallet = read_token_account(escrow_wallet)
+        .map_err(|_| FluterByError::EscrowWalletNotInitialized)?;
+    require!(
+        wallet.mint == reward_token,
+        FluterByError::InvalidDistributionAmount
+    );
+    require!(
+        wallet.owner == escrow_lock_account,
+        FluterByError::InvalidEscrowWalletAuthority
+    );
+    
+    Ok(())
+}
+
+/// Moves `amount` reward tokens from the minter's account into an escrow wallet.
+fn transfer_to_escrow<'info>(
+    token_program: &AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    escrow_wallet: AccountInfo<'info>,
+    minter: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = token::Transfer {
+        from,
+        to: escrow_wallet,
+        authority: minter,
+    };
+    token::transfer(CpiContext::new(token_program.clone(), cpi_accounts), amount)
+}
+
+/// Moves `amount` reward tokens out of an escrow wallet, signed by the escrow
+/// lock account PDA.
+fn transfer_from_escrow<'info>(
+    token_program: &AccountInfo<'info>,
+    escrow_wallet: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    escrow_lock_account: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = token::Transfer {
+        from: escrow_wallet,
+        to,
+        authority: escrow_lock_account,
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds),
+        amount,
+    )
+}
+
+/// Moves `amount` reward tokens out of an escrow wallet, signed by the escrow lock
+/// account. With the reward mint this is a `transfer_checked` through
+/// `reward_token_program`, which works for both token programs and is required for
+/// Token-2022 mints with extensions; without it, a plain SPL Token `transfer`.
+#[allow(clippy::too_many_arguments)]
+fn transfer_reward_from_escrow<'info>(
+    reward_token_program: &AccountInfo<'info>,
+    reward_token_mint: Option<&InterfaceAccount<'info, token_interface::Mint>>,
+    escrow_wallet: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    escrow_lock_account: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    match reward_token_mint {
+        Some(reward_token_mint) => {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: escrow_wallet,
+                mint: reward_token_mint.to_account_info(),
+                to,
+                authority: escrow_lock_account,
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(reward_token_program.clone(), cpi_accounts, signer_seeds),
+                amount,
+                reward_token_mint.decimals,
+            )
+        }
+        None => transfer_from_escrow(reward_token_program, escrow_wallet, to, escrow_lock_account, amount, signer_seeds),
+    }
+}
+
+/// Transfers the full balance of each of the escrow's wallets to `destination`,
+/// emitting `FundsWithdrawn` per wallet, and returns the total moved.
+fn sweep_escrow_wallets<'info>(
+    escrow_lock_account: &mut Account<'info, EscrowLockAccount>,
+    remaining_accounts: &[AccountInfo<'info>],
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    now: i64,
+) -> Result<u64> {
+    let escrow_wallets = escrow_wallet_infos(escrow_lock_account, remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    
+    let total = wallet_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    // Empty wallets are skipped before any CPI is built, and the sweep is logged once
+    // below rather than per wallet, to keep compute down as wallet counts grow
+    for (i, (escrow_wallet, balance)) in escrow_wallets.iter().zip(wallet_balances.iter().copied()).enumerate() {
+        if balance > 0 {
+            transfer_from_escrow(
+                &token_program,
+                escrow_wallet.clone(),
+                destination.clone(),
+                escrow_lock_account.to_account_info(),
+                balance,
+                signer_seeds,
+            )?;
+            
+            emit!(FundsWithdrawn {
+                mint: escrow_lock_account.token,
+                minter: escrow_lock_account.minter,
+                amount: balance,
+                wallet_index: (i + 1) as u8,
+                timestamp: now,
+                event_seq: escrow_lock_account.next_event_seq(),
+            });
+        }
+    }
+    
+    msg!("Swept {} from escrow wallets: {:?}", total, wallet_balances);
+    
+    Ok(total)
+}
+
+/// Burns the full balance of each of the escrow's wallets, signed by the escrow lock
+/// account PDA, and returns the total burned.
+fn burn_escrow_wallets<'info>(
+    escrow_lock_account: &Account<'info, EscrowLockAccount>,
+    remaining_accounts: &[AccountInfo<'info>],
+    reward_token_mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let escrow_wallets = escrow_wallet_infos(escrow_lock_account, remaining_accounts)?;
+    let wallet_balances = escrow_wallet_balances(escrow_wallets)?;
+    
+    let total = wallet_balances
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    for (escrow_wallet, balance) in escrow_wallets.iter().zip(wallet_balances.iter().copied()) {
+        if balance > 0 {
+            let cpi_accounts = token::Burn {
+                mint: reward_token_mint.clone(),
+                from: escrow_wallet.clone(),
+                authority: escrow_lock_account.to_account_info(),
+            };
+            token::burn(
+                CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds),
+                balance,
+            )?;
+        }
+    }
+    
+    msg!("Burned {} from escrow wallets: {:?}", total, wallet_balances);
+    
+    Ok(total)
+}
+
+/// Parses a token account under either token program. Token-2022 accounts are
+/// longer than the 165-byte SPL layout whenever they carry extensions (transfer fee
+/// amounts, the immutable owner of associated token accounts, ...), so this goes
+/// through `token_interface::TokenAccount`, which reads the base state and skips them.
+fn read_token_account(account: &AccountInfo) -> Result<token_interface::TokenAccount> {
+    token_interface::TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])
+}
+
+/// Reads the current token balance of an escrow wallet (SPL Token or Token-2022).
+fn escrow_wallet_balance(escrow_wallet: &AccountInfo) -> Result<u64> {
+    let wallet = read_token_account(escrow_wallet)?;
+    Ok(wallet.amount)
+}
+
+/// Validates that none of the escrow wallets or reward accounts a redemption moves
+/// tokens between is frozen by the reward mint's freeze authority, so a freeze fails
+/// with `AccountFrozen` instead of an opaque token program error. Works under both
+/// token programs, including Token-2022 associated token accounts, which always carry
+/// the ImmutableOwner extension.
+fn require_unfrozen<'info>(escrow_wallets: &[AccountInfo<'info>], reward_accounts: &[Option<AccountInfo<'info>>]) -> Result<()> {
+    for account in escrow_wallets.iter().chain(reward_accounts.iter().flatten()) {
+        let token_account = read_token_account(account)?;
+        require!(
+            !token_account.is_frozen(),
+            FluterByError::AccountFrozen
+        );
+    }
+    
+    Ok(())
+}
+
+/// Reads the current token balance of each escrow wallet returned by
+/// `escrow_wallet_infos`. Those wallets were already deserialized and checked, so
+/// the amount is read straight from the account data instead of parsing it again.
+fn escrow_wallet_balances(escrow_wallets: &[AccountInfo]) -> Result<Vec<u64>> {
+    escrow_wallets.iter().map(token::accessor::amount).collect()
+}
+
+/// Returns the sum of the escrow wallets' live balances.
+fn escrow_wallet_balance_total(
+    escrow_lock_account: &Account<'_, EscrowLockAccount>,
+    remaining_accounts: &[AccountInfo],
+) -> Result<u64> {
+    let escrow_wallets = escrow_wallet_infos(escrow_lock_account, remaining_accounts)?;
+    let total = escrow_wallet_balances(escrow_wallets)?
+        .iter()
+        .try_fold(0u64, |total, balance| total.checked_add(*balance))
+        .ok_or(FluterByError::DistributionCalculationOverflow)?;
+    
+    Ok(total)
+}
+
+/// Splits `amount` into a draw per escrow wallet.
+/// 
+/// Each wallet gives its share by `weights` (an even share when they are all 0), with
+/// the remainder covered one token each by the wallets starting at index
+/// `remainder_offset`. When a wallet cannot cover its share, the shortfall is taken
+/// from the wallets that still hold a balance, in index order.
+fn plan_escrow_draws(wallet_balances: &[u64], weights: &[u64], amount: u64, remainder_offset: u64) -> Result<Vec<u64>> {
+    let mut draws = Vec::with_capacity(wallet_balances.len());
+    let mut shortfall = 0u64;
+    for (i, balance) in wallet_balances.iter().enumerate() {
+        let share = wallet_share(amount, weights, i as u64, remainder_offset)?;
+        let draw = share.min(*balance);
+        shortfall += share - draw;
+        draws.push(draw);
+    }
+    
+    // Cascade the shortfall to wallets with balance left over
+    for (draw, balance) in draws.iter_mut().zip(wallet_balances) {
+        if shortfall == 0 {
+            break;
+        }
+        let extra = (balance - *draw).min(shortfall);
+        *draw += extra;
+        shortfall -= extra;
+    }
+    
+    require!(
+        shortfall == 0,
+        FluterByError::InsufficientFunds
+    );
+    
+    Ok(draws)
+}
+
+/// Splits a redemption into the user's draws and the treasury's fee draws.
+/// 
+/// The payout is planned first; the fee is then drawn from what the wallets have left.
+/// The payout's remainder starts at wallet `remainder_offset` and the fee's remainder
+/// picks up where the payout's left off.
+fn plan_redemption_draws(
+    wallet_balances: &[u64],
+    weights: &[u64],
+    payout: u64,
+    fee: u64,
+    remainder_offset: u64,
+) -> Result<(Vec<u64>, Vec<u64>)> {
+    let wallet_count = wallet_balances.len() as u64;
+    let draws = plan_escrow_draws(wallet_balances, weights, payout, remainder_offset)?;
+    
+    let balances_left: Vec<u64> = wallet_balances
+        .iter()
+        .zip(&draws)
+        .map(|(balance, draw)| balance - draw)
+        .collect();
+    let fee_offset = (remainder_offset + share_remainder(payout, weights)) % wallet_count;
+    let fee_draws = plan_escrow_draws(&balances_left, weights, fee, fee_offset)?;
+    
+    Ok((draws, fee_draws))
+}
+
+/// Moves the escrow's remainder cursor past the wallets that just covered a
+/// redemption's remainder, so the extra tokens rotate across all wallets.
+fn rotate_remainder_wallet(escrow_lock_account: &mut EscrowLockAccount, payout: u64, fee: u64, now: i64) {
+    let wallet_count = escrow_lock_account.wallet_count as u64;
+    let weights = escrow_lock_account.weights();
+    let current = escrow_lock_account.next_remainder_wallet as u64;
+    let next = (current + share_remainder(payout, &weights) + share_remainder(fee, &weights)) % wallet_count;
+    
+    if next != current {
+        escrow_lock_account.next_remainder_wallet = next as u8;
+        
+        emit!(EscrowWalletRotated {
+            token: escrow_lock_account.token,
+            wallet_index: next as u8 + 1,
+            timestamp: now,
+            event_seq: escrow_lock_account.next_event_seq(),
+        });
+    }
+}
+
+/// Pairs up the old escrow wallets' balances with the new escrow wallets' shares, in
+/// index order, as `(old wallet, new wallet, amount)` transfers. Both sides must add up
+/// to the same total.
+fn plan_rollover_transfers(old_balances: &[u64], new_shares: &[u64]) -> Result<Vec<(usize, usize, u64)>> {
+    let mut transfers = Vec::with_capacity(old_balances.len() + new_shares.len());
+    let mut to = 0;
+    let mut share_left = new_shares.first().copied().unwrap_or(0);
+    for (from, balance) in old_balances.iter().copied().enumerate() {
+        let mut balance_left = balance;
+        while balance_left > 0 {
+            // Move on to the next new wallet once this one has its share
+            while share_left == 0 {
+                to += 1;
+                share_left = *new_shares.get(to).ok_or(FluterByError::DistributionCalculationOverflow)?;
+            }
+            
+            let amount = balance_left.min(share_left);
+            transfers.push((from, to, amount));
+            balance_left -= amount;
+            share_left -= amount;
+        }
+    }
+    
+    Ok(transfers)
+}
+
+/// Returns wallet `i`'s share of `amount` split across the wallets in proportion to
+/// `weights` (one per wallet), or evenly when every weight is 0.
+/// 
+/// The tokens left over after each wallet takes its whole share go one each to the
+/// wallets starting at `remainder_offset` (wrapping around), so consecutive splits
+/// keep every wallet within one token of its target.
+fn wallet_share(amount: u64, weights: &[u64], i: u64, remainder_offset: u64) -> Result<u64> {
+    let wallet_count = weights.len() as u64;
+    let base_share = whole_share(amount, weights, i as usize);
+    let remainder = share_remainder(amount, weights);
+    
+    if (i + wallet_count - remainder_offset % wallet_count) % wallet_count < remainder {
+        Ok(base_share
+            .checked_add(1)
+            .ok_or(FluterByError::DistributionCalculationOverflow)?)
+    } else {
+        Ok(base_share)
+    }
+}
+
+/// Returns wallet `i`'s share of `amount`, rounded down.
+/// 
+/// A weighted share is at most `amount` and the product fits in a u128, so this
+/// can't overflow.
+fn whole_share(amount: u64, weights: &[u64], i: usize) -> u64 {
+    let total_weight: u128 = weights.iter().map(|weight| *weight as u128).sum();
+    if total_weight == 0 {
+        return amount / weights.len() as u64;
+    }
+    
+    (amount as u128 * weights[i] as u128 / total_weight) as u64
+}
+
+/// Returns how many tokens of `amount` are left over once every wallet has taken its
+/// whole share. It is always less than the number of wallets.
+fn share_remainder(amount: u64, weights: &[u64]) -> u64 {
+    let assigned: u64 = (0..weights.len())
+        .map(|i| whole_share(amount, weights, i))
+        .sum();
+    
+    amount - assigned
+}
+
+/// Validates a lock gives holders at least the program's minimum lock duration to
+/// redeem before it expires.
+fn validate_lock_duration(expiry: i64, now: i64, program_config: &ProgramConfig) -> Result<()> {
+    require!(
+        expiry >= now.saturating_add(program_config.min_lock_duration),
+        FluterByError::LockDurationTooShort
+    );
+    
+    Ok(())
+}
+
+/// Widens basis-point wallet weights for `wallet_share`.
+fn bps_weights(wallet_weights: &[u16]) -> Vec<u64> {
+    wallet_weights.iter().map(|weight| *weight as u64).collect()
+}
+
+/// Validates the optional per-wallet weights of a lock and returns them as stored on
+/// the escrow. No weights, all zeros, or equal weights keep the even split and are
+/// stored as zeros.
+fn validate_weights(weights: Option<Vec<u16>>, wallet_count: u8) -> Result<[u16; MAX_ESCROW_WALLET_COUNT as usize]> {
+    let mut wallet_weights = [0u16; MAX_ESCROW_WALLET_COUNT as usize];
+    let Some(weights) = weights else {
+        return Ok(wallet_weights);
+    };
+    
+    require!(
+        weights.len() == wallet_count as usize,
+        FluterByError::InvalidWeights
+    );
+    if weights.iter().all(|weight| *weight == 0) {
+        return Ok(wallet_weights);
+    }
+    require!(
+        weights.iter().map(|weight| *weight as u64).sum::<u64>() == BPS_DENOMINATOR,
+        FluterByError::InvalidWeights
+    );
+    
+    // Equal weights describe the even split
+    if weights.iter().all(|weight| *weight == weights[0]) {
+        return Ok(wallet_weights);
+    }
+    
+    wallet_weights[..weights.len()].copy_from_slice(&weights);
+    Ok(wallet_weights)
 }
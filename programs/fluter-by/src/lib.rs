@@ -1,4 +1,17 @@
 use anchor_lang::prelude::*;
+
+/// Logs a per-wallet or per-step progress message, only in builds with the
+/// `verbose-logs` feature. Every `msg!` formats a string and makes a syscall, which adds
+/// up on the multi-CPI lock and redemption paths, and the events already carry the data.
+/// Instructions still log one summary line each.
+macro_rules! log_step {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "verbose-logs") {
+            msg!($($arg)*);
+        }
+    };
+}
+
 pub mod error;
 pub mod instructions;
 pub mod state;
@@ -14,14 +27,15 @@ declare_id!("8zsKxbVSrBUUYWDdSxkNAjS1SL4a4yR7yy7TZBH6qS1d");
 pub mod fluter_by {
     use super::*;
 
-    /// Initialize a single escrow wallet (call 5 times for wallets 1-5)
+    /// Initialize a single escrow wallet (call once per index 1..=wallet_count)
     /// 
-    /// This creates one of the 5 PDA-owned token accounts used to hold reward tokens.
-    /// Must be called before lock_funds.
+    /// This creates one of the `wallet_count` PDA-owned token accounts used to hold
+    /// reward tokens, between `MIN_ESCROW_WALLET_COUNT` and `MAX_ESCROW_WALLET_COUNT` of
+    /// them (5 for `lock_funds`). Must be called before the lock.
     /// 
     /// # Arguments
     /// * `token` - Main token pubkey (for PDA derivation)
-    /// * `wallet_index` - Index 1-5 for which wallet to create
+    /// * `wallet_index` - Index 1-10 for which wallet to create (1-5 for `lock_funds`)
     pub fn initialize_escrow_wallet(
         ctx: Context<InitializeEscrowWallet>,
         token: Pubkey,
@@ -30,15 +44,62 @@ pub mod fluter_by {
         instructions::initialize_escrow_wallet(ctx, token, wallet_index)
     }
 
+    /// Initialize escrow wallets 1-5 in a single instruction
+    /// 
+    /// Same as calling `initialize_escrow_wallet` for indices 1 through 5, so a
+    /// `lock_funds` setup takes two transactions instead of six. The minter pays rent
+    /// for five SPL token accounts (165 bytes each, about 0.00204 SOL apiece, so about
+    /// 0.0102 SOL in total), which is returned by `close_escrow`.
+    /// 
+    /// # Arguments
+    /// * `token` - Main token pubkey (for PDA derivation)
+    pub fn batch_initialize_escrow_wallets(
+        ctx: Context<BatchInitializeEscrowWallets>,
+        token: Pubkey,
+    ) -> Result<()> {
+        instructions::batch_initialize_escrow_wallets(ctx, token)
+    }
+
     /// Lock reward tokens in escrow for a main token
     /// 
     /// # Arguments
     /// * `token` - Main token that users hold
     /// * `reward_token` - Reward token locked in escrow (e.g., USDC)
     /// * `minter` - The minter who is locking the rewards
-    /// * `reward_value` - Total reward value to lock (distributed equally across 5 wallets)
-    /// * `token_supply` - Total supply of the main token
-    /// * `expiry` - Unix timestamp when the lock expires
+    /// * `reward_value` - Total reward value to lock (distributed equally across 5 wallets,
+    ///   with any remainder going one token each to the first wallets)
+    /// * `token_supply` - Total supply of the main token; must equal the supply of the
+    ///   `token_mint` account at lock time
+    /// * `expiry` - Unix timestamp when the lock expires; must be in the future and at
+    ///   least the program's `min_lock_duration` away
+    /// * `params` - Optional lock settings (see `LockParams`); each field left `None`
+    ///   takes its default:
+    ///   - `wallet_count` - Must be omitted or 5, the number of named escrow wallets
+    ///   - `fee_bps` - Optional redemption fee in basis points (at most 1000), paid to the
+    ///     treasury reward account passed with the lock; no fee when omitted
+    ///   - `grace_period` - Optional seconds after expiry before the minter can withdraw,
+    ///     so redemptions in flight at expiry are not swept; defaults to 0
+    ///   - `vesting_start` - Optional start of a linear reward unlock; defaults to the lock
+    ///     time when a `vesting_duration` is given
+    ///   - `vesting_duration` - Optional length of the linear unlock in seconds; redemptions
+    ///     only draw on `remaining_reward_value * elapsed / vesting_duration`. Rewards are
+    ///     fully available at once when omitted
+    ///   - `requires_whitelist` - When true, only users the minter has authorized with
+    ///     `authorize_redeemer` can redeem; open to every holder when omitted
+    ///   - `mode` - Optional reward formula: 0 (live, the default) shares the remaining pool
+    ///     among the supply still outstanding; 1 (snapshot) pays
+    ///     `burn_amount * total_reward_value / token_supply`, independent of redemption order
+    ///   - `max_per_user` - Optional cap on the main tokens one user may burn across all
+    ///     their redemptions; redeemers must then pass their `user_redemption` account.
+    ///     Uncapped when omitted or 0
+    ///   - `max_reward_per_redeem` - Optional cap on the reward one redemption can pay
+    ///     (before fees); a burn that would pay more reverts with `RewardCapExceeded`.
+    ///     Uncapped when omitted or 0
+    ///   - `weights` - Optional share of each escrow wallet in basis points, one per wallet
+    ///     and summing to 10000 (e.g. `[5000, 1250, 1250, 1250, 1250]`). Locks, top-ups,
+    ///     redemptions and partial withdrawals split their amounts by these weights.
+    ///     Even split when omitted, all 0 or all equal
+    #[allow(clippy::too_many_arguments)]
     pub fn lock_funds(
         ctx: Context<LockFunds>,
         token: Pubkey,
@@ -47,33 +108,787 @@ pub mod fluter_by {
         reward_value: u64,
         token_supply: u64,
         expiry: i64,
+        params: LockParams,
+    ) -> Result<()> {
+        instructions::lock_funds(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params)
+    }
+
+    /// Lock reward tokens in escrow for a fixed duration
+    /// 
+    /// Same as `lock_funds`, but the expiry is given relative to the lock time, so
+    /// clients don't depend on their own clock to compute it.
+    /// 
+    /// # Arguments
+    /// * `duration_secs` - Seconds from now until the lock expires; must be positive
+    /// * The remaining arguments are as for `lock_funds`
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_funds_duration(
+        ctx: Context<LockFunds>,
+        token: Pubkey,
+        reward_token: Pubkey,
+        minter: Pubkey,
+        reward_value: u64,
+        token_supply: u64,
+        duration_secs: i64,
+        params: LockParams,
+    ) -> Result<()> {
+        instructions::lock_funds_duration(ctx, token, reward_token, minter, reward_value, token_supply, duration_secs, params)
+    }
+
+    /// Lock reward tokens with an exact amount per escrow wallet
+    /// 
+    /// Same as `lock_funds`, but the minter gives each of the 5 wallets its amount instead
+    /// of a total. Top-ups, redemptions and partial withdrawals then split their amounts in
+    /// proportion to these amounts.
+    /// 
+    /// # Arguments
+    /// * `amounts` - Amount for each escrow wallet, wallet 1 first; each must be greater
+    ///   than 0 and together they are the locked reward value
+    /// * `params` - As for `lock_funds`, except `weights` must be omitted since the
+    ///   amounts already set each wallet's share
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_funds_custom(
+        ctx: Context<LockFunds>,
+        token: Pubkey,
+        reward_token: Pubkey,
+        minter: Pubkey,
+        amounts: [u64; 5],
+        token_supply: u64,
+        expiry: i64,
+        params: LockParams,
     ) -> Result<()> {
-        instructions::lock_funds(ctx, token, reward_token, minter, reward_value, token_supply, expiry)
+        instructions::lock_funds_custom(ctx, token, reward_token, minter, amounts, token_supply, expiry, params)
+    }
+
+    /// Lock reward tokens across a configurable number of escrow wallets
+    /// 
+    /// Same as `lock_funds`, but the escrow wallets are passed via remaining accounts
+    /// (wallet 1 first) and must be pre-created with `initialize_escrow_wallet`.
+    /// 
+    /// # Arguments
+    /// * `params` - As for `lock_funds`, plus `wallet_count`: the number of escrow
+    ///   wallets (2-10), defaulting to 5 when omitted. `weights` then takes one weight
+    ///   per escrow wallet
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_funds_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockFundsV2<'info>>,
+        token: Pubkey,
+        reward_token: Pubkey,
+        minter: Pubkey,
+        reward_value: u64,
+        token_supply: u64,
+        expiry: i64,
+        params: LockParams,
+    ) -> Result<()> {
+        instructions::lock_funds_v2(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params)
+    }
+
+    /// Transfer reward tokens into one escrow wallet before the lock is activated
+    /// 
+    /// Lets a treasury fund the escrow wallets over several transactions instead of
+    /// holding the whole reward value at lock time. The wallet must exist (see
+    /// `initialize_escrow_wallet`) and the escrow must not be locked yet. Funded
+    /// tokens stay in the PDA-owned wallet until `activate_lock` turns them into an
+    /// escrow; after that they are handled like any locked rewards.
+    /// 
+    /// # Arguments
+    /// * `token` - Main token pubkey (for PDA derivation)
+    /// * `wallet_index` - Index 1-10 of the wallet to fund
+    /// * `amount` - Reward tokens to transfer into the wallet
+    pub fn fund_escrow_wallet(
+        ctx: Context<FundEscrowWallet>,
+        token: Pubkey,
+        wallet_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_escrow_wallet(ctx, token, wallet_index, amount)
+    }
+
+    /// Activate a lock over escrow wallets funded with `fund_escrow_wallet`
+    /// 
+    /// Same accounts and arguments as `lock_funds_v2`, but nothing is transferred:
+    /// the escrow wallets' balances must add up to exactly `reward_value`, or the
+    /// activation fails with `FundingMismatch`. The wallets may hold uneven amounts;
+    /// redemptions cascade any shortfall across the wallets that still have a balance.
+    #[allow(clippy::too_many_arguments)]
+    pub fn activate_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockFundsV2<'info>>,
+        token: Pubkey,
+        reward_token: Pubkey,
+        minter: Pubkey,
+        reward_value: u64,
+        token_supply: u64,
+        expiry: i64,
+        params: LockParams,
+    ) -> Result<()> {
+        instructions::activate_lock(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params)
     }
 
     /// Redeem rewards by burning main tokens
     /// 
     /// Users burn their main tokens to receive proportional rewards
     /// Rewards are calculated based on: (burn_amount / total_supply) * remaining_rewards
+    /// If the escrow charges a fee, it is taken out of the reward and sent to the treasury
+    /// The burned amount is then removed from total_supply, so the proportion always
+    /// tracks the circulating supply
     /// The escrow must not be expired for redemption to work
+    /// The escrow wallets are passed via remaining accounts, in index order
+    /// If the escrow has reward tracks (see `add_reward_track`), each one is passed
+    /// after the escrow wallets as (reward track, track vault, user's token account)
+    /// and paid out in the same call
+    /// Rewards go to the user's associated token account for the reward token. If the
+    /// user doesn't have one yet, it is created first and the user pays its rent
+    /// (about 0.002 SOL for an SPL token account, recoverable by closing it later)
+    /// Returns the reward paid to the user after fees, also as return data, so a
+    /// program redeeming through CPI can read it with `get_return_data`
+    /// When the SPL Memo program is passed as `memo_program`, the transaction carries a
+    /// memo with the escrow id and the redeemer, for deposit flows that key off memos
+    /// 
+    /// # Arguments
+    /// * `burn_amount` - Amount of main tokens to burn
+    /// * `min_reward_out` - Optional minimum reward after fees; the redemption reverts
+    ///   (and nothing is burned) if the user would receive less
+    pub fn redeem_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewards<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards(ctx, burn_amount, min_reward_out)
+    }
+
+    /// Redeem from several escrows in one transaction
+    /// 
+    /// For holders of several tokens each backed by an escrow. Each entry of `redemptions`
+    /// is redeemed like `redeem_rewards`, in order, from the escrow whose accounts come next
+    /// in remaining accounts (see `RedeemRewardsBatch`), paying the signer. A batch covers
+    /// 1 to `MAX_BATCH_REDEMPTIONS` escrows (`InvalidBatch` otherwise); with five wallets
+    /// each, more than two escrows need an address lookup table to fit in a transaction.
+    /// Escrows with a whitelist, per-user cap, cooldown, redemption log, receipt mint or
+    /// reward tracks need the single-escrow instructions. If any
+    /// redemption fails, the whole batch reverts. Returns each escrow's payout after fees,
+    /// in order.
+    /// 
+    /// # Arguments
+    /// * `redemptions` - Burn amount and optional minimum reward per escrow
+    pub fn redeem_rewards_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemRewardsBatch<'info>>,
+        redemptions: Vec<BatchRedemption>,
+    ) -> Result<Vec<u64>> {
+        instructions::redeem_rewards_batch(ctx, redemptions)
+    }
+
+    /// Redeem rewards by burning main tokens, splitting the payout across recipients
+    /// 
+    /// Same as `redeem_rewards_to`, but the payout (after fees) is shared by weight
+    /// among several recipient reward accounts, e.g. the members of a pool position. The
+    /// tokens are burned and the reward computed once; the payout lands in the user's
+    /// reward account and is then sent on to each recipient, with any remainder going
+    /// one token each to the first recipients. The recipient reward accounts are passed
+    /// via `remaining_accounts` after the escrow wallets (and any reward track accounts),
+    /// in the same order as `weights`. Returns the total payout.
     /// 
     /// # Arguments
     /// * `burn_amount` - Amount of main tokens to burn
-    pub fn redeem_rewards(
-        ctx: Context<RedeemRewards>,
+    /// * `min_reward_out` - Optional minimum total reward after fees, as for `redeem_rewards`
+    /// * `weights` - Share of each recipient in basis points, one non-zero weight per
+    ///   recipient (at most `MAX_SPLIT_RECIPIENTS`) and summing to 10000
+    pub fn redeem_rewards_split<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewardsSplit<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+        weights: Vec<u16>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards_split(ctx, burn_amount, min_reward_out, weights)
+    }
+
+    /// Redeem rewards by burning main tokens, paying a chosen recipient
+    /// 
+    /// Same as `redeem_rewards`, but the reward lands in `recipient_reward_account`,
+    /// which can be owned by anyone (e.g. a custodian or a relayer's user). The signer
+    /// must still own the main tokens being burned.
+    /// 
+    /// # Arguments
+    /// * `burn_amount` - Amount of main tokens to burn
+    /// * `min_reward_out` - Optional minimum reward after fees, as for `redeem_rewards`
+    pub fn redeem_rewards_to<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewardsTo<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards_to(ctx, burn_amount, min_reward_out)
+    }
+
+    /// Redeem rewards to a chosen recipient and close the user's emptied reward account
+    /// 
+    /// Same as `redeem_rewards_to`, for one-shot claimers who don't mean to hold the
+    /// reward token. After the payout, the user's own reward token account (the
+    /// associated token account) is closed and its rent returned to the user, provided
+    /// it holds no reward tokens; an account with a balance is left open. Returns the
+    /// payout.
+    /// 
+    /// # Arguments
+    /// * `burn_amount` - Amount of main tokens to burn
+    /// * `min_reward_out` - Optional minimum reward after fees, as for `redeem_rewards`
+    pub fn redeem_rewards_and_close<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewardsAndClose<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards_and_close(ctx, burn_amount, min_reward_out)
+    }
+
+    /// Redeem rewards by sending main tokens to the escrow's incinerator account
+    /// 
+    /// Same as `redeem_rewards`, but the main tokens are transferred to the incinerator
+    /// token account the minter configured with `set_incinerator` instead of being
+    /// burned. Use it for mints whose holders can't burn, or to keep the redeemed
+    /// balance visible at a dead address. The reward is calculated exactly as for a burn.
+    /// 
+    /// # Arguments
+    /// * `burn_amount` - Amount of main tokens to send to the incinerator
+    /// * `min_reward_out` - Optional minimum reward after fees, as for `redeem_rewards`
+    pub fn redeem_rewards_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewardsTransfer<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards_transfer(ctx, burn_amount, min_reward_out)
+    }
+
+    /// Quote the reward for burning main tokens, without burning anything
+    /// 
+    /// Runs the same calculation and checks as `redeem_rewards` (the escrow must be
+    /// active and not expired) and returns the reward amount after fees. No tokens move and no
+    /// state changes, so this can be simulated to preview a redemption.
+    /// 
+    /// # Arguments
+    /// * `burn_amount` - Amount of main tokens that would be burned
+    pub fn quote_redemption(
+        ctx: Context<QuoteRedemption>,
         burn_amount: u64,
+    ) -> Result<u64> {
+        instructions::quote_redemption(ctx, burn_amount)
+    }
+
+    /// Quote the reward for burning a user's whole main token balance, without burning
+    /// anything
+    /// 
+    /// Reads the balance of `user_token_account` and runs the same calculation and
+    /// checks as `quote_redemption` for it, returning the reward after fees at the
+    /// escrow's current state. The burn is cut down to what the remaining (vested)
+    /// rewards can pay for, so the quote never exceeds `remaining_reward_value`. An empty
+    /// balance quotes 0.
+    pub fn quote_max_redemption(ctx: Context<QuoteMaxRedemption>) -> Result<u64> {
+        instructions::quote_max_redemption(ctx)
+    }
+
+    /// Return an escrow's status in one call, without changing anything
+    /// 
+    /// Returns the recorded remaining and total reward values, whether the escrow is
+    /// active, its expiry and the seconds left until it, and the live sum of the escrow
+    /// wallets' balances. The escrow wallets are passed via remaining accounts, in
+    /// index order. Simulate it with `.view()`.
+    pub fn get_escrow_status<'info>(
+        ctx: Context<'_, '_, '_, 'info, GetEscrowStatus<'info>>,
+    ) -> Result<EscrowStatus> {
+        instructions::get_escrow_status(ctx)
+    }
+
+    /// Assert that an escrow's wallets hold what its accounting says (anyone can call)
+    /// 
+    /// Sums the live balances of the escrow wallets and compares the total with
+    /// `remaining_reward_value`. Fails with `SolvencyMismatch` when they differ by
+    /// more than `SOLVENCY_TOLERANCE_PER_WALLET` base units per wallet, in either
+    /// direction. Changes nothing, so monitoring can simulate it as a cheap probe.
+    /// The escrow wallets are passed via remaining accounts, in index order.
+    pub fn verify_escrow_solvency<'info>(
+        ctx: Context<'_, '_, '_, 'info, GetEscrowStatus<'info>>,
     ) -> Result<()> {
-        instructions::redeem_rewards(ctx, burn_amount)
+        instructions::verify_escrow_solvency(ctx)
+    }
+
+    /// Preview what `withdraw_expired_rewards` would recover, without changing anything
+    /// 
+    /// Applies the same checks as the withdrawal (minter signer, expiry plus grace period
+    /// passed, escrow still active with rewards left) and returns the live sum of the
+    /// escrow wallets' balances, which is what the sweep would transfer. No tokens move
+    /// and the escrow stays active. The escrow wallets are passed via remaining accounts,
+    /// in index order. Simulate it with `.view()`.
+    pub fn quote_expired_withdrawal<'info>(
+        ctx: Context<'_, '_, '_, 'info, QuoteExpiredWithdrawal<'info>>,
+    ) -> Result<u64> {
+        instructions::quote_expired_withdrawal(ctx)
     }
 
     /// Withdraw all remaining rewards after escrow expiry
     /// 
-    /// Only the minter can call this instruction, and only after the expiry time has passed.
-    /// This withdraws all remaining reward tokens from the 5 escrow wallets back to the minter.
+    /// Only the minter can call this instruction, and only after the expiry time plus the
+    /// escrow's grace period has passed. Redemption is still blocked from the expiry time.
+    /// This withdraws all remaining reward tokens from the escrow wallets back to the minter.
+    /// The escrow wallets are passed via remaining accounts, in index order.
     /// The escrow account is marked as inactive after withdrawal.
-    pub fn withdraw_expired_rewards(
-        ctx: Context<WithdrawExpiredRewards>,
-    ) -> Result<()> {
+    /// Returns the total withdrawn, also as return data for programs calling it through CPI.
+    pub fn withdraw_expired_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+    ) -> Result<u64> {
         instructions::withdraw_expired_rewards(ctx)
     }
+
+    /// Withdraw part of the remaining rewards after escrow expiry
+    /// 
+    /// Same checks as `withdraw_expired_rewards`, but only `amount` is pulled from the
+    /// escrow wallets (an even share from each, with any shortfall taken from wallets
+    /// that still hold more). The escrow stays active until `remaining_reward_value`
+    /// reaches zero, so the minter can reclaim leftovers in several steps.
+    /// 
+    /// # Arguments
+    /// * `amount` - Reward tokens to withdraw, at most `remaining_reward_value`
+    pub fn partial_withdraw_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::partial_withdraw_expired(ctx, amount)
+    }
+
+    /// Cancel a lock before anyone has redeemed and refund the minter
+    /// 
+    /// Only the minter can call this, and only while `remaining_reward_value` still
+    /// equals `total_reward_value`; once any redemption has happened it fails with
+    /// `RedemptionsInProgress`. Sweeps every escrow wallet (passed via remaining
+    /// accounts, in index order) back to the minter and deactivates the escrow.
+    pub fn cancel_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelLock<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_lock(ctx)
+    }
+
+    /// Move an expired escrow's remaining rewards straight into a newer escrow
+    /// 
+    /// For minters running back-to-back campaigns. It has the same conditions as
+    /// `withdraw_expired_rewards` for the old escrow, and the new escrow must belong to the
+    /// same minter, pay the same reward token and still be active. The old escrow wallets'
+    /// full balances are split across the new escrow wallets like `top_up_rewards`, the old
+    /// escrow is closed, and `ExpiredRewardsWithdrawn` (old escrow) and `FundsLocked` (new
+    /// escrow, `value` = the amount rolled over) are emitted. The old escrow's wallets are
+    /// passed via remaining accounts, in index order, followed by the new escrow's.
+    /// Returns the total rolled over, also as return data for programs calling it through CPI.
+    pub fn rollover_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, RolloverExpired<'info>>,
+    ) -> Result<u64> {
+        instructions::rollover_expired(ctx)
+    }
+
+    /// Settle an expired escrow by its expiry action
+    /// 
+    /// Callable once the expiry time plus the grace period has passed and rewards remain.
+    /// With `EXPIRY_ACTION_BURN` anyone can call it, the escrow wallets' full balances are
+    /// burned and `ExpiredRewardsBurned` is emitted. With `EXPIRY_ACTION_RETURN` only the
+    /// minter can call it, `minter_reward_account` must be the account the lock was funded
+    /// from, and it works like `withdraw_expired_rewards`. Either way the escrow is closed.
+    /// The escrow wallets are passed via remaining accounts, in index order.
+    /// Returns the total burned or returned, also as return data.
+    pub fn settle_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleExpired<'info>>,
+    ) -> Result<u64> {
+        instructions::settle_expired(ctx)
+    }
+
+    /// Sweep leftover reward tokens once nothing is owed to holders
+    /// 
+    /// Only the minter can call this instruction, and only while `remaining_reward_value`
+    /// is 0; otherwise it fails with `RewardsStillRemaining` and moves nothing. Whatever
+    /// the escrow wallets still hold (rounding leftovers or tokens sent to them directly)
+    /// goes to the minter, and `DustReclaimed` is emitted. It takes the same accounts as
+    /// `withdraw_expired_rewards` but doesn't wait for expiry, and calling it again with
+    /// empty wallets does nothing. The escrow wallets are passed via remaining accounts,
+    /// in index order.
+    pub fn reclaim_dust<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards<'info>>,
+    ) -> Result<()> {
+        instructions::reclaim_dust(ctx)
+    }
+
+    /// Emergency withdrawal by the program admin
+    /// 
+    /// Break-glass tool for rescuing funds after a bug is found. Sweeps every escrow
+    /// wallet back to the reward token account the lock was funded from, regardless
+    /// of expiry, and deactivates the escrow. Only the `ProgramConfig` admin can call
+    /// it, and only while the program is paused; funds can't be sent anywhere else.
+    /// Works for SPL Token and Token-2022 reward tokens. The escrow wallets are passed
+    /// via remaining accounts, in index order.
+    pub fn emergency_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::emergency_withdraw(ctx)
+    }
+
+    /// Claw back reward tokens from a redeemer into one of the escrow's wallets
+    /// 
+    /// For fraud cases. Both the `ProgramConfig` admin and the escrow's minter must sign,
+    /// and they must be different keys, so neither can act alone. The program can't take
+    /// tokens from an account on its own: the redeemer's reward account must have
+    /// delegated at least `amount` to the escrow lock account (e.g. as a term of a
+    /// compliance agreement), otherwise it fails with `ClawbackNotAuthorized`.
+    /// 
+    /// Redemption never sets up that delegation, and the redeemer can revoke it at any
+    /// time, so clawback only reaches redeemers who approved the escrow off-chain and
+    /// kept the approval in place. It is not a guarantee that paid rewards can be
+    /// recovered.
+    /// 
+    /// The escrow must still be open (checked against the current time, like
+    /// `withdraw_expired_rewards`); the tokens are added back to `remaining_reward_value`
+    /// and a `Clawback` event is emitted.
+    /// 
+    /// # Arguments
+    /// * `amount` - Reward tokens to pull back
+    pub fn clawback(ctx: Context<ClawbackRewards>, amount: u64) -> Result<()> {
+        instructions::clawback(ctx, amount)
+    }
+
+    /// Add more reward tokens to an active escrow
+    /// 
+    /// Only the minter can call this instruction, and only before expiry.
+    /// The amount is split equally across the escrow wallets, which are passed
+    /// via remaining accounts.
+    /// 
+    /// # Arguments
+    /// * `amount` - Reward value to add (distributed equally across the wallets)
+    pub fn top_up_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, TopUpRewards<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::top_up_rewards(ctx, amount)
+    }
+
+    /// Attach an extra reward token to an escrow
+    /// 
+    /// Holders are then paid in this token too, proportional to the same burn, on
+    /// top of the primary reward. Up to 2 tracks can be added (3 reward tokens in
+    /// total), in order, by the minter, before expiry and before any redemption.
+    /// The track's tokens are held in a single vault owned by the escrow lock PDA.
+    /// SPL Token escrows only.
+    /// 
+    /// # Arguments
+    /// * `track_index` - Index of the new track; must equal the number of tracks so far
+    /// * `reward_value` - Amount of the extra reward token to lock
+    pub fn add_reward_track(
+        ctx: Context<AddRewardTrack>,
+        track_index: u8,
+        reward_value: u64,
+    ) -> Result<()> {
+        instructions::add_reward_track(ctx, track_index, reward_value)
+    }
+
+    /// Withdraw what is left of a reward track
+    /// 
+    /// Once the escrow can no longer be redeemed (inactive, or expired past its grace
+    /// period), the minter sweeps the track's vault back to themselves and closes the
+    /// vault and the track. `close_escrow` requires every track to be withdrawn first.
+    /// 
+    /// # Arguments
+    /// * `track_index` - Index of the track to withdraw
+    pub fn withdraw_reward_track(
+        ctx: Context<WithdrawRewardTrack>,
+        track_index: u8,
+    ) -> Result<()> {
+        instructions::withdraw_reward_track(ctx, track_index)
+    }
+
+    /// Push back the expiry of an active escrow
+    /// 
+    /// Only the minter can call this instruction. The new expiry must be later than
+    /// the current one; the redemption window can never be shortened.
+    /// 
+    /// # Arguments
+    /// * `new_expiry` - New unix timestamp when the lock expires
+    pub fn extend_expiry(
+        ctx: Context<ExtendExpiry>,
+        new_expiry: i64,
+    ) -> Result<()> {
+        instructions::extend_expiry(ctx, new_expiry)
+    }
+
+    /// Hand the escrow's minter authority to a new key
+    /// 
+    /// Only the current minter can call this instruction. The new minter takes over
+    /// every minter-only action (withdrawal, top-up, expiry extension, closing).
+    /// 
+    /// Only the authority changes: the escrow lock account and escrow wallet PDAs
+    /// remain derived from the original minter, recorded as `creator` on the escrow.
+    /// Clients must keep deriving the PDAs from `creator`, not from `minter`.
+    /// 
+    /// # Arguments
+    /// * `new_minter` - Key that becomes the escrow's minter
+    pub fn transfer_minter_authority(
+        ctx: Context<TransferMinterAuthority>,
+        new_minter: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_minter_authority(ctx, new_minter)
+    }
+
+    /// Set the main token account that `redeem_rewards_transfer` sends tokens to
+    /// 
+    /// Only the minter can call this instruction. The account must hold the escrow's
+    /// main token; it should be owned by a key nobody controls (e.g. the incinerator
+    /// address) so the redeemed tokens stay out of circulation. Calling it again
+    /// replaces the previous account.
+    pub fn set_incinerator(ctx: Context<SetIncinerator>) -> Result<()> {
+        instructions::set_incinerator(ctx)
+    }
+
+    /// Have `redeem_rewards` mint a claim receipt to every redeemer
+    /// 
+    /// Only the minter can call this instruction. The mint must have 0 decimals and the
+    /// escrow lock account as its mint authority. From then on each `redeem_rewards`
+    /// call mints one receipt token into the user's receipt account, which must be
+    /// passed with the receipt mint. The other redemption instructions, which don't
+    /// mint receipts, are rejected with `ReceiptMintRequired`. Escrows without a
+    /// receipt mint skip this entirely.
+    pub fn set_receipt_mint(ctx: Context<SetReceiptMint>) -> Result<()> {
+        instructions::set_receipt_mint(ctx)
+    }
+
+    /// Keep an on-chain log of the escrow's most recent redemptions
+    /// 
+    /// Only the minter can call this instruction, once per escrow; the minter pays the
+    /// log's rent. The `RedemptionLog` PDA holds the last `REDEMPTION_LOG_CAPACITY`
+    /// redemptions (user, burn amount, reward after fees, time), oldest overwritten
+    /// first. From then on every `redeem_rewards` call must pass the log, and the
+    /// other redemption instructions fail with `RedemptionLogRequired`.
+    pub fn initialize_redemption_log(ctx: Context<InitializeRedemptionLog>) -> Result<()> {
+        instructions::initialize_redemption_log(ctx)
+    }
+
+    /// Pause or unpause redemptions from one escrow (minter only)
+    /// 
+    /// While paused, every redeem instruction for this escrow reverts with `EscrowPaused`,
+    /// without affecting other escrows. Expired withdrawals are unaffected, so the minter
+    /// can always recover the funds.
+    /// 
+    /// # Arguments
+    /// * `paused` - Whether redemptions should be paused
+    pub fn set_escrow_paused(ctx: Context<SetEscrowPaused>, paused: bool) -> Result<()> {
+        instructions::set_escrow_paused(ctx, paused)
+    }
+
+    /// Choose what happens to the rewards left in the escrow at expiry
+    /// 
+    /// Only the minter can call this instruction, and only while the escrow is active.
+    /// `EXPIRY_ACTION_RETURN` (the default) lets the minter take the leftovers back;
+    /// `EXPIRY_ACTION_BURN` burns them through `settle_expired`, which anyone can call,
+    /// and blocks the minter's withdrawals (`LeftoversCommittedToBurn`). A commitment
+    /// to burn can't be switched back, and burning needs an SPL Token reward mint
+    /// (`InvalidExpiryAction` otherwise).
+    /// 
+    /// # Arguments
+    /// * `expiry_action` - `EXPIRY_ACTION_RETURN` or `EXPIRY_ACTION_BURN`
+    pub fn set_expiry_action(ctx: Context<SetExpiryAction>, expiry_action: u8) -> Result<()> {
+        instructions::set_expiry_action(ctx, expiry_action)
+    }
+
+    /// Switch an escrow to the fixed-rate reward model
+    /// 
+    /// Only the minter can call this instruction, and only before the first redemption
+    /// (`RedemptionsInProgress` otherwise). In `REWARD_MODE_FIXED_RATE` every burned token
+    /// pays exactly `rate` reward tokens, however many others redeem, and a redemption
+    /// only reverts (`InsufficientFunds`) when the escrow's remaining (vested) rewards
+    /// can't cover it. Reward tracks keep paying proportionally.
+    /// 
+    /// # Arguments
+    /// * `rate` - Reward tokens (base units) paid per burned main token (base units);
+    ///   0 switches back to the proportional mode the escrow was locked with
+    pub fn set_fixed_rate(ctx: Context<SetFixedRate>, rate: u64) -> Result<()> {
+        instructions::set_fixed_rate(ctx, rate)
+    }
+
+    /// Choose how redemption rewards are rounded
+    /// 
+    /// Only the minter can call this instruction, and only before the first redemption
+    /// (`RedemptionsInProgress` otherwise), so every holder is paid under the same rule.
+    /// `ROUNDING_MODE_FLOOR` (the default) rounds down, `ROUNDING_MODE_CEIL` rounds up
+    /// and `ROUNDING_MODE_NEAREST` rounds to the nearest token. A reward is never rounded
+    /// past the escrow's remaining rewards, and a burn worth less than one reward token
+    /// still pays nothing.
+    /// 
+    /// # Arguments
+    /// * `rounding_mode` - One of the `ROUNDING_MODE_*` constants
+    pub fn set_rounding_mode(ctx: Context<SetRoundingMode>, rounding_mode: u8) -> Result<()> {
+        instructions::set_rounding_mode(ctx, rounding_mode)
+    }
+
+    /// Set the least time one user must wait between redemptions
+    /// 
+    /// Only the minter can call this instruction. Once `cooldown_secs` is above 0, every
+    /// redemption must pass the user's `UserRedemption` record, and a user whose last
+    /// redemption was less than `cooldown_secs` ago is rejected with `CooldownActive`.
+    /// Setting it back to 0 turns the cooldown off.
+    pub fn set_redemption_cooldown(ctx: Context<SetRedemptionCooldown>, cooldown_secs: i64) -> Result<()> {
+        instructions::set_redemption_cooldown(ctx, cooldown_secs)
+    }
+
+    /// Rewrite the escrow's stored wallet addresses from the canonical PDAs
+    /// 
+    /// Only the minter can call this instruction. It is a repair tool for an escrow whose
+    /// `escrow_wallets` got out of step with its wallets during setup. Each wallet is
+    /// re-derived from its seeds and must already exist, hold the reward token and be
+    /// controlled by the escrow lock account; the addresses and bumps are then
+    /// overwritten and `EscrowWalletsResynced` is emitted. The escrow wallets are passed
+    /// via remaining accounts, in index order.
+    pub fn resync_escrow_wallets<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResyncEscrowWallets<'info>>,
+    ) -> Result<()> {
+        instructions::resync_escrow_wallets(ctx)
+    }
+
+    /// Record that an escrow has expired (anyone can call)
+    /// 
+    /// Emits `EscrowExpired` once per expiry, so indexers get a marker for the escrow
+    /// closing to redemption. A redemption that fails with `EscrowExpired` reverts and
+    /// cannot record this itself; `withdraw_expired_rewards` emits it too if nobody
+    /// has called this first. Extending the expiry re-arms the event.
+    pub fn mark_escrow_expired(ctx: Context<MarkEscrowExpired>) -> Result<()> {
+        instructions::mark_escrow_expired(ctx)
+    }
+
+    /// Create the global program config
+    /// 
+    /// The signer becomes the config admin. The config must exist before any
+    /// lock, redemption or withdrawal, since those instructions check it.
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::initialize_config(ctx)
+    }
+
+    /// Bring an escrow lock account up to the current account layout
+    /// 
+    /// Escrows created before a layout change can't be loaded by the other
+    /// instructions. This grows the account to the current size (the payer covers the
+    /// extra rent), leaves every field added since at its zero default, and records
+    /// `ESCROW_VERSION`. Anyone can call it, and calling it again is harmless.
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        instructions::migrate_escrow(ctx)
+    }
+
+    /// Pause or unpause the program (admin only)
+    /// 
+    /// While paused, locks, redemptions and expired withdrawals revert with
+    /// `ProgramPaused`, so the program can be halted without touching each escrow.
+    /// 
+    /// # Arguments
+    /// * `paused` - Whether the program should be paused
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    /// Set how many escrows one minter may create (admin only)
+    /// 
+    /// Bounds state growth from a single minter. Once a minter's registry
+    /// (`MinterAccount.total_escrows`) reaches the limit, every further lock reverts
+    /// with `MinterEscrowLimitReached`. Closed escrows still count, since the registry
+    /// counts every escrow the minter has created.
+    /// 
+    /// # Arguments
+    /// * `max_escrows_per_minter` - Most escrows per minter; 0 (the default) means unlimited
+    pub fn set_max_escrows_per_minter(ctx: Context<SetMaxEscrowsPerMinter>, max_escrows_per_minter: u64) -> Result<()> {
+        instructions::set_max_escrows_per_minter(ctx, max_escrows_per_minter)
+    }
+
+    /// Set the program-wide minimum lock duration (admin only)
+    /// 
+    /// Every new lock must then expire at least this long after it is made, or it
+    /// reverts with `LockDurationTooShort`, so each escrow gives holders a real
+    /// redemption window. Existing escrows and expiry extensions are unaffected.
+    /// 
+    /// # Arguments
+    /// * `min_lock_duration` - Minimum seconds from lock to expiry; 0 (the default)
+    ///   removes the minimum
+    pub fn set_min_lock_duration(ctx: Context<SetMinLockDuration>, min_lock_duration: i64) -> Result<()> {
+        instructions::set_min_lock_duration(ctx, min_lock_duration)
+    }
+
+    /// Add a user to an escrow's redeemer allowlist (minter only)
+    /// 
+    /// Creates the user's `RedeemerEntry` PDA. Escrows locked with
+    /// `requires_whitelist` only accept redemptions that pass this entry.
+    /// 
+    /// # Arguments
+    /// * `redeemer` - User allowed to redeem
+    pub fn authorize_redeemer(ctx: Context<AuthorizeRedeemer>, redeemer: Pubkey) -> Result<()> {
+        instructions::authorize_redeemer(ctx, redeemer)
+    }
+
+    /// Remove a user from an escrow's redeemer allowlist (minter only)
+    /// 
+    /// Closes the user's `RedeemerEntry` and returns its rent to the minter.
+    /// 
+    /// # Arguments
+    /// * `redeemer` - User to remove
+    pub fn revoke_redeemer(ctx: Context<RevokeRedeemer>, redeemer: Pubkey) -> Result<()> {
+        instructions::revoke_redeemer(ctx, redeemer)
+    }
+
+    /// Token-2022 variant of `initialize_escrow_wallet`
+    /// 
+    /// Creates the escrow wallet under the reward token's own program, so reward
+    /// tokens issued under Token-2022 can be locked.
+    pub fn initialize_escrow_wallet_2022(
+        ctx: Context<InitializeEscrowWallet2022>,
+        token: Pubkey,
+        wallet_index: u8,
+    ) -> Result<()> {
+        instructions::initialize_escrow_wallet_2022(ctx, token, wallet_index)
+    }
+
+    /// Token-2022 variant of `lock_funds_v2`
+    /// 
+    /// Transfers with `transfer_checked`. For mints with a transfer fee, the escrow
+    /// records the amounts the escrow wallets actually received, not the amounts sent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_funds_2022<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockFunds2022<'info>>,
+        token: Pubkey,
+        reward_token: Pubkey,
+        minter: Pubkey,
+        reward_value: u64,
+        token_supply: u64,
+        expiry: i64,
+        params: LockParams,
+    ) -> Result<()> {
+        instructions::lock_funds_2022(ctx, token, reward_token, minter, reward_value, token_supply, expiry, params)
+    }
+
+    /// Token-2022 variant of `redeem_rewards`
+    /// 
+    /// The main token is burned as usual; rewards are paid with `transfer_checked`.
+    /// For mints with a transfer fee, the user receives the reward minus the fee.
+    /// A missing reward token account is created as for `redeem_rewards`; accounts for
+    /// mints with extensions are larger, so their rent is slightly higher.
+    pub fn redeem_rewards_2022<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewards2022<'info>>,
+        burn_amount: u64,
+        min_reward_out: Option<u64>,
+    ) -> Result<u64> {
+        instructions::redeem_rewards_2022(ctx, burn_amount, min_reward_out)
+    }
+
+    /// Token-2022 variant of `withdraw_expired_rewards`
+    pub fn withdraw_expired_rewards_2022<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawExpiredRewards2022<'info>>,
+    ) -> Result<u64> {
+        instructions::withdraw_expired_rewards_2022(ctx)
+    }
+
+    /// Close a finished escrow and reclaim its rent
+    /// 
+    /// Only the minter can call this instruction, once the escrow has been withdrawn
+    /// (or has expired with no rewards remaining). All escrow wallets must be empty.
+    /// The escrow wallets and the escrow lock account are closed and their rent is
+    /// returned to the minter, or to `rent_recipient` when one is passed (e.g. a
+    /// platform treasury that fronted the rent). The minter must sign either way.
+    /// The escrow wallets are passed via remaining accounts.
+    /// Works for escrows of both SPL Token and Token-2022 reward tokens.
+    pub fn close_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseEscrow<'info>>,
+    ) -> Result<()> {
+        instructions::close_escrow(ctx)
+    }
 }
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::memo::Memo;
 use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{self, TokenInterface};
 use crate::error::FluterByError;
 
 #[derive(Accounts)]
@@ -35,93 +38,1742 @@ pub struct InitializeEscrowWallet<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Transfers reward tokens into one escrow wallet ahead of `activate_lock`.
+#[derive(Accounts)]
+#[instruction(token: Pubkey, wallet_index: u8)]
+pub struct FundEscrowWallet<'info> {
+    pub minter: Signer<'info>,
+    
+    /// Global program config; funding is rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// The escrow lock account; wallets can only be funded before it is created
+    #[account(
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump,
+        constraint = escrow_lock_account.data_is_empty() @ FluterByError::EscrowAlreadyExists
+    )]
+    /// CHECK: This is the PDA that will hold the lock once activated
+    pub escrow_lock_account: UncheckedAccount<'info>,
+    
+    /// Escrow wallet - PDA-owned token account, created with `initialize_escrow_wallet`
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[wallet_index]],
+        bump
+    )]
+    pub escrow_wallet: Account<'info, TokenAccount>,
+    
+    /// Minter's reward token account (source of funds)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == escrow_wallet.mint
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Creates escrow wallets 1-5 in one instruction. The wallets are boxed to keep
+/// the five `init` accounts off the stack.
+#[derive(Accounts)]
+#[instruction(token: Pubkey)]
+pub struct BatchInitializeEscrowWallets<'info> {
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// The reward token mint
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// The escrow lock account (must exist as authority)
+    #[account(
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is the PDA that will be the authority
+    pub escrow_lock_account: UncheckedAccount<'info>,
+    
+    /// Escrow wallet 1 - PDA-owned token account
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[1]],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub escrow_wallet_1: Box<Account<'info, TokenAccount>>,
+    
+    /// Escrow wallet 2 - PDA-owned token account
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[2]],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub escrow_wallet_2: Box<Account<'info, TokenAccount>>,
+    
+    /// Escrow wallet 3 - PDA-owned token account
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[3]],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub escrow_wallet_3: Box<Account<'info, TokenAccount>>,
+    
+    /// Escrow wallet 4 - PDA-owned token account
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[4]],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub escrow_wallet_4: Box<Account<'info, TokenAccount>>,
+    
+    /// Escrow wallet 5 - PDA-owned token account
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[5]],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub escrow_wallet_5: Box<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
 #[derive(Accounts)]
 #[instruction(token: Pubkey)]
 pub struct LockFunds<'info> {
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowLockAccount::INIT_SPACE,
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; locks are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// The minter's escrow registry, created on their first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + MinterAccount::INIT_SPACE,
+        seeds = [b"minter", minter.key().as_ref()],
+        bump
+    )]
+    pub minter_account: Box<Account<'info, MinterAccount>>,
+    
+    /// Global counter assigning each escrow its `escrow_id`, created by the first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowCounter::INIT_SPACE,
+        seeds = [b"escrow_counter"],
+        bump
+    )]
+    pub escrow_counter: Box<Account<'info, EscrowCounter>>,
+    
+    /// The main token mint (its supply is recorded as the escrow's token supply)
+    #[account(
+        constraint = token_mint.key() == token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+    
+    /// The reward token mint
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// Minter's reward token account (source of funds)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key()
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    /// Treasury reward token account (receives redemption fees, required when `fee_bps` > 0)
+    #[account(
+        constraint = treasury_reward_account.mint == reward_token_mint.key()
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Escrow wallet 1 - must be pre-created; checked in `lock_funds` before any transfer
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[1]],
+        bump,
+    )]
+    pub escrow_wallet_1: UncheckedAccount<'info>,
+    
+    /// CHECK: Escrow wallet 2 - must be pre-created; checked in `lock_funds` before any transfer
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[2]],
+        bump,
+    )]
+    pub escrow_wallet_2: UncheckedAccount<'info>,
+    
+    /// CHECK: Escrow wallet 3 - must be pre-created; checked in `lock_funds` before any transfer
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[3]],
+        bump,
+    )]
+    pub escrow_wallet_3: UncheckedAccount<'info>,
+    
+    /// CHECK: Escrow wallet 4 - must be pre-created; checked in `lock_funds` before any transfer
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[4]],
+        bump,
+    )]
+    pub escrow_wallet_4: UncheckedAccount<'info>,
+    
+    /// CHECK: Escrow wallet 5 - must be pre-created; checked in `lock_funds` before any transfer
+    #[account(
+        mut,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[5]],
+        bump,
+    )]
+    pub escrow_wallet_5: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `LockFunds`, but the escrow wallets are passed via `remaining_accounts`
+/// in index order (wallet 1 first), one per `wallet_count`.
+#[derive(Accounts)]
+#[instruction(token: Pubkey)]
+pub struct LockFundsV2<'info> {
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowLockAccount::INIT_SPACE,
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; locks are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// The minter's escrow registry, created on their first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + MinterAccount::INIT_SPACE,
+        seeds = [b"minter", minter.key().as_ref()],
+        bump
+    )]
+    pub minter_account: Box<Account<'info, MinterAccount>>,
+    
+    /// Global counter assigning each escrow its `escrow_id`, created by the first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowCounter::INIT_SPACE,
+        seeds = [b"escrow_counter"],
+        bump
+    )]
+    pub escrow_counter: Box<Account<'info, EscrowCounter>>,
+    
+    /// The main token mint (its supply is recorded as the escrow's token supply)
+    #[account(
+        constraint = token_mint.key() == token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+    
+    /// The reward token mint
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// Minter's reward token account (source of funds)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key()
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    /// Treasury reward token account (receives redemption fees, required when `fee_bps` > 0)
+    #[account(
+        constraint = treasury_reward_account.mint == reward_token_mint.key()
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Each escrow's accounts are passed via `remaining_accounts`, one set after another:
+/// the escrow lock account, its main token mint, the user's main token account, the
+/// user's reward token account, the treasury reward account (the program ID when the
+/// escrow charges no fee), then the escrow wallets in index order.
+#[derive(Accounts)]
+pub struct RedeemRewardsBatch<'info> {
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct RedeemRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be burned). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
+    pub token: UncheckedAccount<'info>,
+    
+    /// The main token mint account
+    #[account(
+        mut,
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// User's token account (holds main tokens to burn)
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the reward token
+    #[account(
+        constraint = reward_token.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token: UncheckedAccount<'info>,
+    
+    /// The reward token mint; the escrow wallets are checked against it as well
+    #[account(
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// User's reward token account (receives rewards). This is the user's associated
+    /// token account for the reward token; if it doesn't exist yet it is created here,
+    /// with the user paying its rent (~0.002 SOL, refundable by closing the account).
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&user_reward_account.key()) @ FluterByError::InvalidDistributionAmount
+    )]
+    pub user_reward_account: Box<Account<'info, TokenAccount>>,
+    
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
+    #[account(
+        mut,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
+    #[account(
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
+    
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
+    
+    /// The escrow's receipt mint (required when the escrow mints claim receipts)
+    #[account(
+        mut,
+        constraint = receipt_mint.key() == escrow_lock_account.receipt_mint @ FluterByError::InvalidReceiptMint
+    )]
+    pub receipt_mint: Option<Box<Account<'info, Mint>>>,
+    
+    /// User's receipt token account, receiving one receipt per redemption (required
+    /// when the escrow mints claim receipts)
+    #[account(
+        mut,
+        constraint = user_receipt_account.mint == escrow_lock_account.receipt_mint @ FluterByError::InvalidReceiptMint,
+        constraint = user_receipt_account.owner == user.key() @ FluterByError::InvalidReceiptMint
+    )]
+    pub user_receipt_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// The escrow's redemption log (required once the minter has created one)
+    #[account(
+        mut,
+        seeds = [b"redemption_log", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_log: Option<AccountLoader<'info, RedemptionLog>>,
+    
+    /// SPL Memo program; when passed, the payout is tagged with a memo naming the escrow and redeemer
+    pub memo_program: Option<Program<'info, Memo>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `RedeemRewards`, but the reward is paid into any token account of the
+/// reward mint. The signer must still own the main tokens being burned.
+/// The escrow wallets are passed via `remaining_accounts`, in index order.
+#[derive(Accounts)]
+pub struct RedeemRewardsTo<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be burned). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
+    pub token: UncheckedAccount<'info>,
+    
+    /// The main token mint account
+    #[account(
+        mut,
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// User's token account (holds main tokens to burn)
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the reward token
+    #[account(
+        constraint = reward_token.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token: UncheckedAccount<'info>,
+    
+    /// Recipient's reward token account (receives rewards, may be owned by anyone)
+    #[account(
+        mut,
+        constraint = recipient_reward_account.mint == reward_token.key(),
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&recipient_reward_account.key()) @ FluterByError::InvalidDistributionAmount
+    )]
+    pub recipient_reward_account: Account<'info, TokenAccount>,
+    
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
+    #[account(
+        mut,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
+    #[account(
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
+    
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `RedeemRewardsTo`, plus the user's own reward token account, which is
+/// closed to the user once the redemption leaves it empty.
+/// The escrow wallets are passed via `remaining_accounts`, in index order.
+#[derive(Accounts)]
+pub struct RedeemRewardsAndClose<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be burned). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
+    pub token: UncheckedAccount<'info>,
+    
+    /// The main token mint account
+    #[account(
+        mut,
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// User's token account (holds main tokens to burn)
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the reward token
+    #[account(
+        constraint = reward_token.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token: UncheckedAccount<'info>,
+    
+    /// Recipient's reward token account (receives rewards, may be owned by anyone)
+    #[account(
+        mut,
+        constraint = recipient_reward_account.mint == reward_token.key(),
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&recipient_reward_account.key()) @ FluterByError::InvalidDistributionAmount
+    )]
+    pub recipient_reward_account: Account<'info, TokenAccount>,
+    
+    /// The user's own reward token account, closed to the user when empty
+    #[account(
+        mut,
+        associated_token::mint = reward_token,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+        constraint = user_reward_account.key() != recipient_reward_account.key() @ FluterByError::InvalidDistributionAmount
+    )]
+    pub user_reward_account: Box<Account<'info, TokenAccount>>,
+    
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
+    #[account(
+        mut,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
+    #[account(
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
+    
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// An escrow lock account created with an earlier layout is loaded by hand, since it
+/// may be too short to deserialize until it has been grown.
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    /// CHECK: An escrow lock account in any layout; its discriminator is checked in the instruction
+    #[account(mut, owner = crate::ID)]
+    pub escrow_lock_account: UncheckedAccount<'info>,
+    
+    /// Pays the rent for the account's extra space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `RedeemRewardsTo`, but the reward is paid to the user's own reward account
+/// and then split across the recipient reward accounts passed via `remaining_accounts`,
+/// after the escrow wallets (and any reward track accounts).
+#[derive(Accounts)]
+pub struct RedeemRewardsSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be burned). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
+    pub token: UncheckedAccount<'info>,
+    
+    /// The main token mint account
+    #[account(
+        mut,
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// User's token account (holds main tokens to burn)
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the reward token
+    #[account(
+        constraint = reward_token.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token: UncheckedAccount<'info>,
+    
+    /// User's reward token account (receives the payout, then sends the recipients their shares)
+    #[account(
+        mut,
+        constraint = user_reward_account.mint == reward_token.key(),
+        constraint = user_reward_account.owner == user.key(),
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&user_reward_account.key()) @ FluterByError::InvalidDistributionAmount
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
+    #[account(
+        mut,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
+    #[account(
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
+    
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `RedeemRewards`, but the main tokens are moved to the escrow's configured
+/// incinerator account instead of being burned, for mints whose holders can't burn.
+/// The escrow wallets are passed via `remaining_accounts`, in index order.
+#[derive(Accounts)]
+pub struct RedeemRewardsTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be sent to the incinerator). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
+    pub token: UncheckedAccount<'info>,
+    
+    /// The main token mint account
+    #[account(
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    /// User's token account (holds main tokens to redeem)
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == token.key()
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// The escrow's incinerator account (receives the redeemed main tokens)
+    #[account(
+        mut,
+        constraint = incinerator_account.key() == escrow_lock_account.incinerator @ FluterByError::InvalidIncinerator,
+        constraint = incinerator_account.mint == token.key() @ FluterByError::InvalidIncinerator
+    )]
+    pub incinerator_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the reward token
+    #[account(
+        constraint = reward_token.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token: UncheckedAccount<'info>,
+    
+    /// User's reward token account (receives rewards), created on first use as for
+    /// `RedeemRewards`
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&user_reward_account.key()) @ FluterByError::InvalidDistributionAmount
+    )]
+    pub user_reward_account: Box<Account<'info, TokenAccount>>,
+    
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
+    #[account(
+        mut,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
+    )]
+    pub treasury_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
+    #[account(
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
+    
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
+    )]
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteRedemption<'info> {
+    #[account(
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteMaxRedemption<'info> {
+    #[account(
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// Main token account whose whole balance would be burned
+    #[account(
+        constraint = user_token_account.mint == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct GetEscrowStatus<'info> {
+    #[account(
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct QuoteExpiredWithdrawal<'info> {
+    #[account(
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct WithdrawExpiredRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; withdrawals are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// Minter's reward token account (receives remaining rewards)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key()
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The old escrow's wallets are passed via `remaining_accounts`, in index order, followed
+/// by the new escrow's wallets, in index order.
+#[derive(Accounts)]
+pub struct RolloverExpired<'info> {
+    /// The expired escrow being emptied and closed
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// The escrow receiving the rewards; same minter and reward token as the old one
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", new_escrow_lock_account.token.as_ref(), new_escrow_lock_account.creator.as_ref()],
+        bump = new_escrow_lock_account.lock_bump,
+        constraint = new_escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter,
+        constraint = new_escrow_lock_account.reward_token == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub new_escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// Global program config; rollovers are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the old escrow's main token
+    pub token: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; cancellations are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// Minter's reward token account (receives the refund)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key()
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct TopUpRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint
+    #[account(
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// Minter's reward token account (source of funds)
+    #[account(
+        mut,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key()
+    )]
+    pub minter_reward_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(track_index: u8)]
+pub struct AddRewardTrack<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; new tracks are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The escrow's primary reward mint. Typed as an SPL Token mint, so reward tracks
+    /// can't be attached to Token-2022 escrows: redemptions pay tracks through the
+    /// escrow's reward token program, which must then be SPL Token.
+    #[account(
+        constraint = primary_reward_mint.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub primary_reward_mint: Box<Account<'info, Mint>>,
+    
+    /// The extra reward token mint
+    pub track_reward_mint: Box<Account<'info, Mint>>,
+    
+    /// The new reward track
+    #[account(
+        init,
+        payer = minter,
+        space = 8 + RewardTrack::INIT_SPACE,
+        seeds = [b"reward_track", escrow_lock_account.key().as_ref(), &[track_index]],
+        bump
+    )]
+    pub reward_track: Account<'info, RewardTrack>,
+    
+    /// Token account holding the track's rewards, owned by the escrow lock PDA
+    #[account(
+        init,
+        payer = minter,
+        seeds = [b"track_vault", escrow_lock_account.key().as_ref(), &[track_index]],
+        bump,
+        token::mint = track_reward_mint,
+        token::authority = escrow_lock_account,
+    )]
+    pub track_vault: Box<Account<'info, TokenAccount>>,
+    
+    /// Minter's account for the extra reward token (source of funds)
+    #[account(
+        mut,
+        constraint = minter_track_account.owner == minter.key(),
+        constraint = minter_track_account.mint == track_reward_mint.key()
+    )]
+    pub minter_track_account: Box<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(track_index: u8)]
+pub struct WithdrawRewardTrack<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; withdrawals are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward track being withdrawn; closed with its rent returned to the minter
+    #[account(
+        mut,
+        seeds = [b"reward_track", escrow_lock_account.key().as_ref(), &[track_index]],
+        bump,
+        close = minter
+    )]
+    pub reward_track: Account<'info, RewardTrack>,
+    
+    /// The track's token account; emptied and closed
+    #[account(
+        mut,
+        constraint = track_vault.key() == reward_track.vault @ FluterByError::InvalidRewardTrack
+    )]
+    pub track_vault: Box<Account<'info, TokenAccount>>,
+    
+    /// Minter's account for the extra reward token (receives the remaining rewards)
+    #[account(
+        mut,
+        constraint = minter_track_account.owner == minter.key(),
+        constraint = minter_track_account.mint == reward_track.reward_token
+    )]
+    pub minter_track_account: Box<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferMinterAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendExpiry<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetIncinerator<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// Main token account that redeemed tokens will be sent to
+    #[account(
+        constraint = incinerator_account.mint == escrow_lock_account.token @ FluterByError::InvalidIncinerator
+    )]
+    pub incinerator_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetReceiptMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// Mint claim receipts are minted from; whole tokens only, with the escrow lock
+    /// account as its mint authority
+    #[account(
+        constraint = receipt_mint.decimals == 0 @ FluterByError::InvalidReceiptMint,
+        constraint = receipt_mint.mint_authority.contains(&escrow_lock_account.key()) @ FluterByError::InvalidReceiptMint
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRedemptionLog<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// Pays the log's rent
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The escrow's redemption log
+    #[account(
+        init,
+        payer = minter,
+        space = 8 + std::mem::size_of::<RedemptionLog>(),
+        seeds = [b"redemption_log", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_log: AccountLoader<'info, RedemptionLog>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEscrowPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetExpiryAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// CHECK: The reward token mint; only its owning token program is read
+    #[account(
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token_mint: UncheckedAccount<'info>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct SettleExpired<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// Anyone when the escrow burns its leftovers; only the minter when it returns them
+    pub caller: Signer<'info>,
+    
+    /// Global program config; settlements are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint (its supply shrinks when leftovers are burned)
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+    
+    /// The reward token account the lock was funded from; only needed to return leftovers
+    #[account(
+        mut,
+        constraint = minter_reward_account.key() == escrow_lock_account.minter_reward_account @ FluterByError::InvalidRefundAccount
+    )]
+    pub minter_reward_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoundingMode<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFixedRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedemptionCooldown<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct ResyncEscrowWallets<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MarkEscrowExpired<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+}
+
+/// The escrow wallets are passed via `remaining_accounts`, in index order,
+/// one per `escrow_lock_account.wallet_count`.
+#[derive(Accounts)]
+pub struct CloseEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives the rent instead of the minter (e.g. a platform treasury that
+    /// fronted it); chosen by the signing minter
+    #[account(
+        mut,
+        constraint = rent_recipient.key() != escrow_lock_account.key() @ FluterByError::InvalidRentRecipient
+    )]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+    
+    /// The reward token's program (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [b"program_config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// Becomes the config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.admin == admin.key() @ FluterByError::UnauthorizedAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxEscrowsPerMinter<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.admin == admin.key() @ FluterByError::UnauthorizedAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinLockDuration<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.admin == admin.key() @ FluterByError::UnauthorizedAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(redeemer: Pubkey)]
+pub struct AuthorizeRedeemer<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// Allowlist entry for `redeemer` on this escrow
     #[account(
         init,
         payer = minter,
-        space = 8 + EscrowLockAccount::INIT_SPACE,
-        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        space = 8 + RedeemerEntry::INIT_SPACE,
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), redeemer.as_ref()],
         bump
     )]
+    pub redeemer_entry: Account<'info, RedeemerEntry>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(redeemer: Pubkey)]
+pub struct RevokeRedeemer<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
+    )]
     pub escrow_lock_account: Account<'info, EscrowLockAccount>,
     
+    /// Receives the entry's rent
     #[account(mut)]
     pub minter: Signer<'info>,
     
-    /// The reward token mint
-    pub reward_token_mint: Account<'info, Mint>,
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
     
-    /// Minter's reward token account (source of funds)
+    /// Allowlist entry for `redeemer` on this escrow
     #[account(
         mut,
-        constraint = minter_reward_account.owner == minter.key(),
-        constraint = minter_reward_account.mint == reward_token_mint.key()
+        close = minter,
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), redeemer.as_ref()],
+        bump
     )]
-    pub minter_reward_account: Account<'info, TokenAccount>,
+    pub redeemer_entry: Account<'info, RedeemerEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(token: Pubkey, wallet_index: u8)]
+pub struct InitializeEscrowWallet2022<'info> {
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// The reward token mint (Token-2022 or SPL Token)
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_token_mint: InterfaceAccount<'info, token_interface::Mint>,
     
-    /// Escrow wallet 1 - must be pre-created
+    /// The escrow lock account (must exist as authority)
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[1]],
-        bump,
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump
     )]
-    pub escrow_wallet_1: Account<'info, TokenAccount>,
+    /// CHECK: This is the PDA that will be the authority
+    pub escrow_lock_account: UncheckedAccount<'info>,
     
-    /// Escrow wallet 2 - must be pre-created
+    /// Escrow wallet - PDA-owned token account under the reward token program
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[2]],
+        init,
+        payer = minter,
+        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[wallet_index]],
         bump,
+        token::mint = reward_token_mint,
+        token::authority = escrow_lock_account,
+        token::token_program = reward_token_program,
     )]
-    pub escrow_wallet_2: Account<'info, TokenAccount>,
+    pub escrow_wallet: InterfaceAccount<'info, token_interface::TokenAccount>,
     
-    /// Escrow wallet 3 - must be pre-created
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Token-2022 variant of `LockFundsV2`. The escrow wallets are passed via
+/// `remaining_accounts` in index order (wallet 1 first), one per `wallet_count`.
+#[derive(Accounts)]
+#[instruction(token: Pubkey)]
+pub struct LockFunds2022<'info> {
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[3]],
-        bump,
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowLockAccount::INIT_SPACE,
+        seeds = [b"escrow_lock", token.as_ref(), minter.key().as_ref()],
+        bump
     )]
-    pub escrow_wallet_3: Account<'info, TokenAccount>,
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    #[account(mut)]
+    pub minter: Signer<'info>,
     
-    /// Escrow wallet 4 - must be pre-created
+    /// Global program config; locks are rejected while the program is paused
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[4]],
+        seeds = [b"program_config"],
         bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// The minter's escrow registry, created on their first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + MinterAccount::INIT_SPACE,
+        seeds = [b"minter", minter.key().as_ref()],
+        bump
+    )]
+    pub minter_account: Box<Account<'info, MinterAccount>>,
+    
+    /// Global counter assigning each escrow its `escrow_id`, created by the first lock
+    #[account(
+        init_if_needed,
+        payer = minter,
+        space = 8 + EscrowCounter::INIT_SPACE,
+        seeds = [b"escrow_counter"],
+        bump
+    )]
+    pub escrow_counter: Box<Account<'info, EscrowCounter>>,
+    
+    /// The main token mint (its supply is recorded as the escrow's token supply)
+    #[account(
+        constraint = token_mint.key() == token @ FluterByError::TokenMismatch
     )]
-    pub escrow_wallet_4: Account<'info, TokenAccount>,
+    pub token_mint: Box<Account<'info, Mint>>,
     
-    /// Escrow wallet 5 - must be pre-created
+    /// The reward token mint (Token-2022 or SPL Token)
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_token_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    /// Minter's reward token account (source of funds)
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.as_ref(), minter.key().as_ref(), &[5]],
-        bump,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key(),
+        token::token_program = reward_token_program
     )]
-    pub escrow_wallet_5: Account<'info, TokenAccount>,
+    pub minter_reward_account: InterfaceAccount<'info, token_interface::TokenAccount>,
     
-    pub token_program: Program<'info, Token>,
+    /// Treasury reward token account (receives redemption fees, required when `fee_bps` > 0)
+    #[account(
+        constraint = treasury_reward_account.mint == reward_token_mint.key(),
+        token::token_program = reward_token_program
+    )]
+    pub treasury_reward_account: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    
+    pub reward_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
+/// Token-2022 variant of `RedeemRewards`. The main token is still burned through
+/// the SPL Token program; the reward token may live under either token program.
+/// The escrow wallets are passed via `remaining_accounts`, in index order.
 #[derive(Accounts)]
-pub struct RedeemRewards<'info> {
+pub struct RedeemRewards2022<'info> {
     #[account(
         mut,
-        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.minter.key().as_ref()],
-        bump
+        seeds = [b"escrow_lock", escrow_lock_account.token.as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
     )]
     pub escrow_lock_account: Account<'info, EscrowLockAccount>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// CHECK: This is the main token (to be burned)
+    /// Global program config; redemptions are rejected while the program is paused
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    /// CHECK: This is the main token (to be burned). The lock PDA is derived from the
+    /// escrow's stored token, so a token that doesn't match it names no escrow at all
+    #[account(
+        constraint = token.key() == escrow_lock_account.token @ FluterByError::EscrowNotFound
+    )]
     pub token: UncheckedAccount<'info>,
     
     /// The main token mint account
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = token_mint.key() == escrow_lock_account.token @ FluterByError::TokenMismatch
+    )]
     pub token_mint: Account<'info, Mint>,
     
     /// User's token account (holds main tokens to burn)
@@ -132,130 +1784,287 @@ pub struct RedeemRewards<'info> {
     )]
     pub user_token_account: Account<'info, TokenAccount>,
     
-    /// CHECK: This is the reward token
-    pub reward_token: UncheckedAccount<'info>,
+    /// The reward token mint
+    #[account(
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token,
+        mint::token_program = reward_token_program
+    )]
+    pub reward_token_mint: InterfaceAccount<'info, token_interface::Mint>,
     
-    /// User's reward token account (receives rewards)
+    /// User's reward token account (receives rewards). This is the user's associated
+    /// token account for the reward token; if it doesn't exist yet it is created here,
+    /// with the user paying its rent (~0.002 SOL, more for mints with extensions).
     #[account(
-        mut,
-        constraint = user_reward_account.owner == user.key(),
-        constraint = user_reward_account.mint == reward_token.key()
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user,
+        associated_token::token_program = reward_token_program,
+        constraint = !escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&user_reward_account.key()) @ FluterByError::InvalidDistributionAmount
     )]
-    pub user_reward_account: Account<'info, TokenAccount>,
+    pub user_reward_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     
-    /// Escrow wallet 1 - PDA-owned token account
+    /// Treasury reward token account (receives the redemption fee, required when the escrow charges one)
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), escrow_lock_account.minter.key().as_ref(), &[1]],
-        bump,
+        constraint = treasury_reward_account.key() == escrow_lock_account.treasury @ FluterByError::InvalidTreasury
     )]
-    pub escrow_wallet_1: Account<'info, TokenAccount>,
+    pub treasury_reward_account: Option<InterfaceAccount<'info, token_interface::TokenAccount>>,
     
-    /// Escrow wallet 2 - PDA-owned token account
+    /// The user's redeemer allowlist entry (required when the escrow requires a whitelist)
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), escrow_lock_account.minter.key().as_ref(), &[2]],
-        bump,
+        seeds = [b"redeemer", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
     )]
-    pub escrow_wallet_2: Account<'info, TokenAccount>,
+    pub redeemer_entry: Option<Account<'info, RedeemerEntry>>,
     
-    /// Escrow wallet 3 - PDA-owned token account
+    /// The user's cumulative redemption record, created on first use (required when the
+    /// escrow caps redemptions per user)
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), escrow_lock_account.minter.key().as_ref(), &[3]],
-        bump,
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRedemption::INIT_SPACE,
+        seeds = [b"user_redeem", escrow_lock_account.key().as_ref(), &escrow_lock_account.escrow_id.to_le_bytes(), user.key().as_ref()],
+        bump
     )]
-    pub escrow_wallet_3: Account<'info, TokenAccount>,
+    pub user_redemption: Option<Account<'info, UserRedemption>>,
     
-    /// Escrow wallet 4 - PDA-owned token account
+    pub token_program: Program<'info, Token>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Break-glass sweep of an escrow back to the account it was funded from. Only the
+/// program admin can call it, and only while the program is paused. Works for both
+/// SPL Token and Token-2022 reward tokens. The escrow wallets are passed via
+/// `remaining_accounts`, in index order.
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), escrow_lock_account.minter.key().as_ref(), &[4]],
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump
+    )]
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
+    
+    pub admin: Signer<'info>,
+    
+    /// Global program config; the sweep is only allowed while the program is paused
+    #[account(
+        seeds = [b"program_config"],
         bump,
+        constraint = program_config.admin == admin.key() @ FluterByError::UnauthorizedAdmin,
+        constraint = program_config.paused @ FluterByError::ProgramNotPaused
     )]
-    pub escrow_wallet_4: Account<'info, TokenAccount>,
+    pub program_config: Account<'info, ProgramConfig>,
     
-    /// Escrow wallet 5 - PDA-owned token account
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint
+    #[account(
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount,
+        mint::token_program = reward_token_program
+    )]
+    pub reward_token_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    /// The reward token account the lock was funded from (receives everything)
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), escrow_lock_account.minter.key().as_ref(), &[5]],
-        bump,
+        constraint = minter_reward_account.key() == escrow_lock_account.minter_reward_account @ FluterByError::InvalidRefundAccount,
+        token::token_program = reward_token_program
     )]
-    pub escrow_wallet_5: Account<'info, TokenAccount>,
+    pub minter_reward_account: InterfaceAccount<'info, token_interface::TokenAccount>,
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
 }
 
+/// Pulls reward tokens back from a redeemer into one of the escrow's wallets. Both the
+/// program admin and the escrow's minter must sign, and the redeemer's account must
+/// have delegated at least the amount to the escrow lock account. Redemption doesn't
+/// create that delegation; the redeemer has to approve it themselves.
 #[derive(Accounts)]
-pub struct WithdrawExpiredRewards<'info> {
+pub struct ClawbackRewards<'info> {
     #[account(
         mut,
-        seeds = [b"escrow_lock", token.key().as_ref(), minter.key().as_ref()],
-        bump,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
         constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
     )]
     pub escrow_lock_account: Account<'info, EscrowLockAccount>,
     
-    #[account(mut)]
+    pub admin: Signer<'info>,
+    
     pub minter: Signer<'info>,
     
+    /// Global program config; `admin` must be its admin
+    #[account(
+        seeds = [b"program_config"],
+        bump,
+        constraint = program_config.admin == admin.key() @ FluterByError::UnauthorizedAdmin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
     /// CHECK: This is the main token
     pub token: UncheckedAccount<'info>,
     
-    /// The reward token mint
-    pub reward_token_mint: Account<'info, Mint>,
-    
-    /// Minter's reward token account (receives remaining rewards)
+    /// The redeemer's reward token account the tokens are pulled from
     #[account(
         mut,
-        constraint = minter_reward_account.owner == minter.key(),
-        constraint = minter_reward_account.mint == reward_token_mint.key()
+        constraint = redeemer_reward_account.mint == escrow_lock_account.reward_token @ FluterByError::InvalidDistributionAmount
     )]
-    pub minter_reward_account: Account<'info, TokenAccount>,
+    pub redeemer_reward_account: Account<'info, TokenAccount>,
     
-    /// Escrow wallet 1 - PDA-owned token account
+    /// The escrow wallet receiving the clawed-back tokens
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), minter.key().as_ref(), &[1]],
-        bump,
+        constraint = escrow_lock_account.escrow_wallets[..escrow_lock_account.wallet_count as usize]
+            .contains(&escrow_wallet.key()) @ FluterByError::InvalidEscrowWalletIndex
     )]
-    pub escrow_wallet_1: Account<'info, TokenAccount>,
+    pub escrow_wallet: Account<'info, TokenAccount>,
     
-    /// Escrow wallet 2 - PDA-owned token account
+    pub token_program: Program<'info, Token>,
+}
+
+/// Token-2022 variant of `WithdrawExpiredRewards`. The escrow wallets are passed
+/// via `remaining_accounts`, in index order.
+#[derive(Accounts)]
+pub struct WithdrawExpiredRewards2022<'info> {
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), minter.key().as_ref(), &[2]],
-        bump,
+        seeds = [b"escrow_lock", token.key().as_ref(), escrow_lock_account.creator.as_ref()],
+        bump = escrow_lock_account.lock_bump,
+        constraint = escrow_lock_account.minter == minter.key() @ FluterByError::UnauthorizedMinter
     )]
-    pub escrow_wallet_2: Account<'info, TokenAccount>,
+    pub escrow_lock_account: Account<'info, EscrowLockAccount>,
     
-    /// Escrow wallet 3 - PDA-owned token account
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    
+    /// Global program config; withdrawals are rejected while the program is paused
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), minter.key().as_ref(), &[3]],
+        seeds = [b"program_config"],
         bump,
+        constraint = !program_config.paused @ FluterByError::ProgramPaused
     )]
-    pub escrow_wallet_3: Account<'info, TokenAccount>,
+    pub program_config: Account<'info, ProgramConfig>,
     
-    /// Escrow wallet 4 - PDA-owned token account
+    /// CHECK: This is the main token
+    pub token: UncheckedAccount<'info>,
+    
+    /// The reward token mint
     #[account(
-        mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), minter.key().as_ref(), &[4]],
-        bump,
+        constraint = reward_token_mint.key() == escrow_lock_account.reward_token,
+        mint::token_program = reward_token_program
     )]
-    pub escrow_wallet_4: Account<'info, TokenAccount>,
+    pub reward_token_mint: InterfaceAccount<'info, token_interface::Mint>,
     
-    /// Escrow wallet 5 - PDA-owned token account
+    /// Minter's reward token account (receives remaining rewards)
     #[account(
         mut,
-        seeds = [b"escrow_wallet", token.key().as_ref(), minter.key().as_ref(), &[5]],
-        bump,
+        constraint = minter_reward_account.owner == minter.key(),
+        constraint = minter_reward_account.mint == reward_token_mint.key(),
+        token::token_program = reward_token_program
     )]
-    pub escrow_wallet_5: Account<'info, TokenAccount>,
+    pub minter_reward_account: InterfaceAccount<'info, token_interface::TokenAccount>,
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Number of escrow wallets used by `lock_funds`, and by `lock_funds_v2` when no count is given
+pub const DEFAULT_ESCROW_WALLET_COUNT: u8 = 5;
+/// Smallest number of escrow wallets a lock can be spread across
+pub const MIN_ESCROW_WALLET_COUNT: u8 = 2;
+/// Largest number of escrow wallets a lock can be spread across
+pub const MAX_ESCROW_WALLET_COUNT: u8 = 10;
+/// Largest redemption fee a lock can charge, in basis points (10%)
+pub const MAX_FEE_BPS: u16 = 1000;
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Reward mode sharing the remaining pool among the supply still outstanding
+pub const REWARD_MODE_LIVE: u8 = 0;
+/// Reward mode sharing the whole pool among the supply recorded at lock time
+pub const REWARD_MODE_SNAPSHOT: u8 = 1;
+/// Reward mode paying a fixed `rate` of reward tokens per burned token
+pub const REWARD_MODE_FIXED_RATE: u8 = 2;
+/// Rounding mode paying the reward rounded down (the default)
+pub const ROUNDING_MODE_FLOOR: u8 = 0;
+/// Rounding mode paying the reward rounded up
+pub const ROUNDING_MODE_CEIL: u8 = 1;
+/// Rounding mode paying the reward rounded to the nearest token, halves up
+pub const ROUNDING_MODE_NEAREST: u8 = 2;
+/// Expiry action returning leftover rewards to the minter (the default)
+pub const EXPIRY_ACTION_RETURN: u8 = 0;
+/// Expiry action burning leftover rewards
+pub const EXPIRY_ACTION_BURN: u8 = 1;
+/// Extra reward tokens an escrow can pay alongside its primary reward token
+pub const MAX_REWARD_TRACKS: u8 = 2;
+/// Largest reward value an escrow may hold, at lock time and after top-ups. With
+/// `MAX_TOKEN_SUPPLY` this keeps every `u128` product in the reward math at most
+/// ~1e36, far below `u128::MAX` (~3.4e38).
+pub const MAX_REWARD_VALUE: u64 = 1_000_000_000_000_000_000;
+/// Largest main token supply a lock may record
+pub const MAX_TOKEN_SUPPLY: u64 = 1_000_000_000_000_000_000;
+/// Base units each escrow wallet's balance may drift from the recorded remaining
+/// value before `verify_escrow_solvency` reports a mismatch (covers the one-token
+/// remainder shares)
+pub const SOLVENCY_TOLERANCE_PER_WALLET: u64 = 1;
+/// Most recent redemptions a `RedemptionLog` keeps
+pub const REDEMPTION_LOG_CAPACITY: usize = 64;
+/// Most escrows one `redeem_rewards_batch` call may redeem from
+pub const MAX_BATCH_REDEMPTIONS: usize = 3;
+/// Accounts passed per escrow in a batch redemption before its escrow wallets
+pub const BATCH_REDEMPTION_ACCOUNTS: usize = 5;
+/// Most recipients one `redeem_rewards_split` call may pay
+pub const MAX_SPLIT_RECIPIENTS: usize = 10;
+/// Layout version of new escrow lock accounts; bump it with every layout change and
+/// teach `migrate_escrow` to fill in the new fields
+pub const ESCROW_VERSION: u8 = 3;
+
+/// Lifecycle of an escrow lock. A lock starts `Active`; after that the stored status
+/// only changes through `EscrowLockAccount::transition_to`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum EscrowState {
+    Active,                         // Redeemable until expires_at
+    Expired,                        // Past expires_at with rewards left; withdrawable after the grace period
+    Drained,                        // Redemptions paid out the whole pool
+    Closed,                         // Withdrawn, cancelled or emergency-swept by the minter
+}
+
+/// Snapshot of an escrow's state returned by `get_escrow_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EscrowStatus {
+    pub remaining_reward_value: u64,
+    pub total_reward_value: u64,
+    pub status: EscrowState,        // Status at the time of the call
+    pub expires_at: i64,
+    pub seconds_until_expiry: i64,  // 0 once the escrow has expired
+    pub wallet_balance_total: u64,  // Live sum of the escrow wallets' balances
+}
+
+/// One escrow's part of a `redeem_rewards_batch` call, in the same order as its accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchRedemption {
+    pub burn_amount: u64,           // Main tokens to burn for this escrow
+    pub min_reward_out: Option<u64>, // Least reward after fees this escrow must pay
+}
+
+/// Optional settings of a lock, shared by every `lock_funds` variant. A field left as
+/// `None` takes its default; see `lock_funds` for what each one does.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LockParams {
+    pub wallet_count: Option<u8>,           // Escrow wallets, 2-10 (default 5; the 5-wallet locks accept only 5)
+    pub fee_bps: Option<u16>,               // Redemption fee in basis points (default 0)
+    pub grace_period: Option<i64>,          // Seconds after expiry before the minter can withdraw (default 0)
+    pub vesting_start: Option<i64>,         // Start of the linear reward unlock (default: lock time)
+    pub vesting_duration: Option<i64>,      // Length of the linear reward unlock (default: no vesting)
+    pub requires_whitelist: Option<bool>,   // Only authorized redeemers can redeem (default false)
+    pub mode: Option<u8>,                   // REWARD_MODE_LIVE (default) or REWARD_MODE_SNAPSHOT
+    pub max_per_user: Option<u64>,          // Most main tokens one user may burn (default: no cap)
+    pub max_reward_per_redeem: Option<u64>, // Most reward tokens one redemption may pay (default: no cap)
+    pub weights: Option<Vec<u16>>,          // Share of each escrow wallet in basis points (default: even split)
 }
 
 #[account]
@@ -263,15 +2072,56 @@ pub struct WithdrawExpiredRewards<'info> {
 pub struct EscrowLockAccount {
     pub token: Pubkey,              // Main token (users hold this)
     pub reward_token: Pubkey,       // Reward token (locked in escrow)
-    pub minter: Pubkey,
+    pub minter: Pubkey,             // Current withdrawal authority
+    pub creator: Pubkey,            // Minter at lock time; the escrow PDAs are derived from this key
     pub total_reward_value: u64,    // Total reward tokens locked
     pub remaining_reward_value: u64, // Remaining reward tokens
     pub reward_per_wallet: u64,     // Reward tokens per wallet
+    pub reward_remainder: u64,      // Wallets 1..=reward_remainder hold one extra reward token
     pub total_token_supply: u64,    // Total supply of main token
-    pub escrow_wallets: [Pubkey; 5],
+    pub escrow_wallets: [Pubkey; 10], // Only the first `wallet_count` entries are used
+    pub wallet_count: u8,           // Number of escrow wallets holding rewards
     pub expires_at: i64,
     pub created_at: i64,
-    pub is_active: bool,
+    pub status: EscrowState,        // Lifecycle status, see `status_at`
+    pub total_burned: u64,          // Main tokens burned across all redemptions
+    pub redemption_count: u64,      // Number of redemptions
+    pub last_redeemed_at: i64,      // Time of the latest redemption (0 if none)
+    pub fee_bps: u16,               // Redemption fee in basis points
+    pub treasury: Pubkey,           // Treasury reward token account receiving fees
+    pub is_redeeming: bool,         // Processing guard, only set while a redemption runs
+    pub grace_period: i64,          // Seconds after expiry before the minter can withdraw
+    pub vesting_start: i64,         // Start of the linear reward unlock (unused if no vesting)
+    pub vesting_duration: i64,      // Length of the linear reward unlock (0 = no vesting)
+    pub next_remainder_wallet: u8,  // 0-based wallet that covers the next redemption's first extra token
+    pub requires_whitelist: bool,   // Only users with a RedeemerEntry can redeem
+    pub mode: u8,                   // REWARD_MODE_LIVE, REWARD_MODE_SNAPSHOT or REWARD_MODE_FIXED_RATE
+    pub snapshot_supply: u64,       // Main token supply at lock time
+    pub expired_emitted: bool,      // EscrowExpired has been emitted for the current expiry
+    pub token_decimals: u8,         // Decimals of the main token mint
+    pub reward_token_decimals: u8,  // Decimals of the reward token mint
+    pub max_per_user: u64,          // Most main tokens one user may burn in total (0 = no cap)
+    pub max_reward_per_redeem: u64, // Most reward tokens one redemption may pay (0 = no cap)
+    pub minter_reward_account: Pubkey, // Reward token account the lock was funded from
+    pub lock_bump: u8,              // Bump of this escrow lock PDA
+    pub wallet_bumps: [u8; 10],     // Bumps of the escrow wallet PDAs, same order as escrow_wallets
+    pub reward_track_count: u8,     // Extra reward tracks added to this escrow
+    pub open_reward_tracks: u8,     // Reward tracks not yet withdrawn
+    pub incinerator: Pubkey,        // Main token account `redeem_rewards_transfer` sends tokens to (default = unset)
+    pub wallet_weights: [u16; 10],  // Share of each escrow wallet in basis points (all 0 = even split)
+    pub receipt_mint: Pubkey,       // Mint `redeem_rewards` mints one claim receipt from (default = unset)
+    pub first_redemption_at: i64,   // Time of the first redemption (0 if none)
+    pub escrow_id: u64,             // Program-wide sequence number, starting at 1
+    pub cooldown_secs: i64,         // Least time between one user's redemptions (0 = no cooldown)
+    pub has_redemption_log: bool,   // A RedemptionLog exists; `redeem_rewards` must write to it
+    pub rounding_mode: u8,          // ROUNDING_MODE_FLOOR, ROUNDING_MODE_CEIL or ROUNDING_MODE_NEAREST
+    pub expiry_action: u8,          // EXPIRY_ACTION_RETURN or EXPIRY_ACTION_BURN
+    pub paused: bool,               // Redemptions from this escrow are paused by the minter
+    pub wallet_amounts: [u64; 10],  // Amount each escrow wallet was locked with by `lock_funds_custom` (all 0 = unset)
+    pub rate: u64,                  // Reward tokens paid per burned token in REWARD_MODE_FIXED_RATE
+    pub version: u8,                // Layout version (ESCROW_VERSION once created or migrated)
+    pub event_seq: u64,             // Sequence number of the last event emitted for this escrow
+    pub lock_mode: u8,              // Proportional mode chosen at lock time, restored when a fixed rate is cleared
 }
 
 impl EscrowLockAccount {
@@ -279,12 +2129,185 @@ impl EscrowLockAccount {
         32 + // token
         32 + // reward_token
         32 + // minter
+        32 + // creator
         8 +  // total_reward_value
         8 +  // remaining_reward_value
         8 +  // reward_per_wallet
+        8 +  // reward_remainder
         8 +  // total_token_supply
-        (32 * 5) + // escrow_wallets array (5 wallets)
+        (32 * MAX_ESCROW_WALLET_COUNT as usize) + // escrow_wallets array (up to 10 wallets)
+        1 +  // wallet_count
         8 +  // expires_at
         8 +  // created_at
-        1;   // is_active
+        1 +  // status
+        8 +  // total_burned
+        8 +  // redemption_count
+        8 +  // last_redeemed_at
+        2 +  // fee_bps
+        32 + // treasury
+        1 +  // is_redeeming
+        8 +  // grace_period
+        8 +  // vesting_start
+        8 +  // vesting_duration
+        1 +  // next_remainder_wallet
+        1 +  // requires_whitelist
+        1 +  // mode
+        8 +  // snapshot_supply
+        1 +  // expired_emitted
+        1 +  // token_decimals
+        1 +  // reward_token_decimals
+        8 +  // max_per_user
+        8 +  // max_reward_per_redeem
+        32 + // minter_reward_account
+        1 +  // lock_bump
+        MAX_ESCROW_WALLET_COUNT as usize + // wallet_bumps
+        1 +  // reward_track_count
+        1 +  // open_reward_tracks
+        32 + // incinerator
+        (2 * MAX_ESCROW_WALLET_COUNT as usize) + // wallet_weights
+        32 + // receipt_mint
+        8 +  // first_redemption_at
+        8 +  // escrow_id
+        8 +  // cooldown_secs
+        1 +  // has_redemption_log
+        1 +  // rounding_mode
+        1 +  // expiry_action
+        1 +  // paused
+        (8 * MAX_ESCROW_WALLET_COUNT as usize) + // wallet_amounts
+        8 +  // rate
+        1 +  // version
+        8 +  // event_seq
+        1;   // lock_mode
+    
+    /// Weights of the escrow wallets in use, in index order (all 0 for an even split).
+    /// A custom lock is weighted by the amounts its wallets were locked with, otherwise
+    /// the basis-point weights apply.
+    pub fn weights(&self) -> Vec<u64> {
+        let wallet_count = self.wallet_count as usize;
+        if self.wallet_amounts[..wallet_count].iter().any(|amount| *amount > 0) {
+            return self.wallet_amounts[..wallet_count].to_vec();
+        }
+        
+        self.wallet_weights[..wallet_count].iter().map(|weight| *weight as u64).collect()
+    }
+    
+    /// Status of the escrow at `now`. An active escrow counts as expired from
+    /// `expires_at` (and as active again once its expiry is extended), and as drained
+    /// when nothing is left to pay out. Drained and closed escrows stay that way.
+    pub fn status_at(&self, now: i64) -> EscrowState {
+        match self.status {
+            EscrowState::Active | EscrowState::Expired if self.remaining_reward_value == 0 => EscrowState::Drained,
+            EscrowState::Active | EscrowState::Expired if now >= self.expires_at => EscrowState::Expired,
+            EscrowState::Active | EscrowState::Expired => EscrowState::Active,
+            status => status,
+        }
+    }
+    
+    /// Moves the escrow to `next`, rejecting transitions its lifecycle doesn't allow:
+    /// an open escrow (active or expired) may expire, be reopened by an extension,
+    /// drain or be closed, a drained one may only be closed, and a closed one is final.
+    pub fn transition_to(&mut self, next: EscrowState) -> Result<()> {
+        let allowed = match (self.status, next) {
+            (current, next) if current == next => true,
+            (EscrowState::Active | EscrowState::Expired, _) => true,
+            (EscrowState::Drained, EscrowState::Closed) => true,
+            _ => false,
+        };
+        require!(allowed, FluterByError::InvalidStatusTransition);
+        
+        self.status = next;
+        Ok(())
+    }
+    
+    /// Records the status the escrow has reached by `now` (see `status_at`).
+    pub fn refresh_status(&mut self, now: i64) -> Result<()> {
+        let status = self.status_at(now);
+        self.transition_to(status)
+    }
+    
+    /// Advances `event_seq` and returns it, for the next event emitted about this
+    /// escrow. Indexers use it to order the escrow's events and spot gaps.
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq = self.event_seq.saturating_add(1);
+        self.event_seq
+    }
+}
+
+/// Program-wide settings, stored in a single PDA
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,              // Key allowed to pause and unpause the program
+    pub paused: bool,               // Locks, redemptions and withdrawals are rejected while set
+    pub min_lock_duration: i64,     // Least time a new lock must run before it expires (0 = no minimum)
+    pub max_escrows_per_minter: u64, // Most escrows one minter may create (0 = unlimited)
+}
+
+/// Marks a user as allowed to redeem from an escrow that requires a whitelist
+#[account]
+#[derive(InitSpace)]
+pub struct RedeemerEntry {
+    pub escrow: Pubkey,             // Escrow lock account the entry belongs to
+    pub redeemer: Pubkey,           // User allowed to redeem
+    pub authorized_at: i64,
+}
+
+/// Cumulative redemptions of one user against one escrow
+#[account]
+#[derive(InitSpace)]
+pub struct UserRedemption {
+    pub escrow: Pubkey,             // Escrow lock account the record belongs to
+    pub user: Pubkey,               // User who redeemed
+    pub total_burned: u64,          // Main tokens burned by this user
+    pub total_claimed: u64,         // Reward tokens paid out for this user's burns
+    pub last_redeemed_at: i64,      // Time of this user's latest redemption (0 if none)
+}
+
+/// An extra reward token paid alongside the escrow's primary reward token, in
+/// proportion to the same burn
+#[account]
+#[derive(InitSpace)]
+pub struct RewardTrack {
+    pub escrow: Pubkey,             // Escrow lock account the track belongs to
+    pub track_index: u8,            // 0-based position among the escrow's tracks
+    pub reward_token: Pubkey,       // Extra reward token mint
+    pub vault: Pubkey,              // Token account holding the track's rewards
+    pub total_reward_value: u64,    // Reward tokens locked in the track
+    pub remaining_reward_value: u64, // Reward tokens not yet paid out
+}
+
+/// One redemption recorded in a `RedemptionLog`
+#[zero_copy]
+#[derive(Default)]
+pub struct RedemptionLogEntry {
+    pub user: Pubkey,               // User who redeemed
+    pub burn_amount: u64,           // Main tokens burned
+    pub reward_amount: u64,         // Reward tokens paid to the user, after fees
+    pub timestamp: i64,             // Time of the redemption
+}
+
+/// Ring buffer of an escrow's most recent redemptions, kept on-chain so the history
+/// doesn't depend on an indexer having seen the events. Zero-copy, since the entries
+/// are too large to deserialize on the stack.
+#[account(zero_copy)]
+pub struct RedemptionLog {
+    pub escrow: Pubkey,             // Escrow lock account the log belongs to
+    pub total_entries: u64,         // Redemptions ever written; the next one goes to total_entries % capacity
+    pub entries: [RedemptionLogEntry; REDEMPTION_LOG_CAPACITY], // Oldest entries are overwritten first
+}
+
+/// Program-wide count of escrows, assigning each new escrow its `escrow_id`
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowCounter {
+    pub total_escrows: u64,         // Escrows created across all minters
+}
+
+/// Registry of the escrows one minter has created
+#[account]
+#[derive(InitSpace)]
+pub struct MinterAccount {
+    pub minter: Pubkey,             // Minter the registry belongs to
+    pub total_escrows: u64,         // Escrows created by this minter
+    pub registered_at: i64,         // Time of the minter's first lock
 }
@@ -4,11 +4,28 @@ import { FluterBy } from "../target/types/fluter_by";
 import {
   createMint,
   getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
   mintTo,
   getAccount,
+  getMint,
+  freezeAccount,
+  thawAccount,
+  approve,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
 } from "@solana/spl-token";
-import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
 import { assert } from "chai";
 
 describe("fluter-by", () => {
@@ -30,7 +47,7 @@ describe("fluter-by", () => {
       program.programId
     );
 
-    for (let i = 1; i <= 5; i++) {
+    for (let i = 1; i <= wallets.length; i++) {
       await program.methods
         .initializeEscrowWallet(mainToken, i)
         .accounts({
@@ -46,6 +63,80 @@ describe("fluter-by", () => {
         .rpc();
     }
   }
+
+  // Helper function to pass escrow wallets as remaining accounts (in index order)
+  function escrowWalletMetas(wallets: PublicKey[]) {
+    return wallets.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
+  }
+
+  // Helper function to build the optional lock settings, every field defaulted unless overridden
+  function lockParams(overrides: Record<string, any> = {}) {
+    return {
+      walletCount: null,
+      feeBps: null,
+      gracePeriod: null,
+      vestingStart: null,
+      vestingDuration: null,
+      requiresWhitelist: null,
+      mode: null,
+      maxPerUser: null,
+      maxRewardPerRedeem: null,
+      weights: null,
+      ...overrides,
+    };
+  }
+
+  // Helper function to derive escrow wallet PDAs for indices 1..count
+  function deriveEscrowWallets(mainToken: PublicKey, minterKey: PublicKey, count: number): PublicKey[] {
+    const wallets: PublicKey[] = [];
+    for (let i = 1; i <= count; i++) {
+      const [wallet] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_wallet"), mainToken.toBuffer(), minterKey.toBuffer(), Buffer.from([i])],
+        program.programId
+      );
+      wallets.push(wallet);
+    }
+    return wallets;
+  }
+
+  // Helper function to fetch an escrow's escrow_id as it appears in the seeds of the
+  // accounts tied to one lock (redeemer entries, user redemptions, the redemption log)
+  async function escrowIdSeed(escrowLock: PublicKey): Promise<Buffer> {
+    const escrow = await program.account.escrowLockAccount.fetch(escrowLock);
+    return escrow.escrowId.toArrayLike(Buffer, "le", 8);
+  }
+
+  // Helper function to mint a main token's supply to its mint authority, so the
+  // mint supply matches the token_supply passed to lock_funds
+  async function mintMainSupply(mainToken: PublicKey, mintAuthority: Keypair, amount: BN) {
+    const holderAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      mintAuthority,
+      mainToken,
+      mintAuthority.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      mintAuthority,
+      mainToken,
+      holderAccount.address,
+      mintAuthority,
+      BigInt(amount.toString())
+    );
+    return holderAccount.address;
+  }
+
+  // Helper function to wait until the cluster clock has passed an escrow's expiry.
+  // Locks must expire in the future, so expiry tests lock a few seconds ahead and wait.
+  async function waitForExpiry(expiry: BN) {
+    for (;;) {
+      const blockTime = await provider.connection.getBlockTime(await provider.connection.getSlot());
+      if (blockTime !== null && blockTime >= expiry.toNumber()) {
+        return;
+      }
+      await new Promise((resolve) => setTimeout(resolve, 500));
+    }
+  }
   
   // Test accounts
   let minter: Keypair;
@@ -153,6 +244,14 @@ describe("fluter-by", () => {
       TOKEN_SUPPLY.toNumber()
     );
 
+    // Create the global program config, with the provider wallet as admin
+    await program.methods
+      .initializeConfig()
+      .accounts({
+        admin: provider.wallet.publicKey,
+      })
+      .rpc();
+
     console.log("✅ Test accounts and tokens created successfully");
   });
 
@@ -254,13 +353,16 @@ describe("fluter-by", () => {
         minter.publicKey,
         TOTAL_REWARD_VALUE,
         TOKEN_SUPPLY,
-        expiryTime
+        expiryTime,
+        lockParams()
       )
       .accounts({
         escrowLockAccount,
         minter: minter.publicKey,
+        tokenMint: mainTokenMint,
         rewardTokenMint,
         minterRewardAccount,
+        treasuryRewardAccount: null,
         escrowWallet1,
         escrowWallet2,
         escrowWallet3,
@@ -283,7 +385,7 @@ describe("fluter-by", () => {
     assert.equal(escrowData.remainingRewardValue.toString(), TOTAL_REWARD_VALUE.toString());
     assert.equal(escrowData.rewardPerWallet.toString(), REWARD_PER_WALLET.toString());
     assert.equal(escrowData.totalTokenSupply.toString(), TOKEN_SUPPLY.toString());
-    assert.equal(escrowData.isActive, true);
+    assert.deepEqual(escrowData.status, { active: {} });
 
     console.log("✅ Escrow lock account created successfully");
     console.log("  Total Reward Value:", escrowData.totalRewardValue.toString());
@@ -317,7 +419,7 @@ describe("fluter-by", () => {
     console.log("User reward balance before:", userRewardAccountBefore.amount.toString());
 
     const tx = await program.methods
-      .redeemRewards(burnAmount)
+      .redeemRewards(burnAmount, expectedReward)
       .accounts({
         escrowLockAccount,
         user: user.publicKey,
@@ -325,15 +427,21 @@ describe("fluter-by", () => {
         tokenMint: mainTokenMint,
         userTokenAccount: userMainTokenAccount,
         rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
         userRewardAccount,
-        escrowWallet1,
-        escrowWallet2,
-        escrowWallet3,
-        escrowWallet4,
-        escrowWallet5,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(
+        escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+      )
       .signers([user])
       .rpc();
 
@@ -368,8 +476,7 @@ describe("fluter-by", () => {
   });
 
   it("Prevents redemption after expiry", async () => {
-    // Create a new escrow with immediate expiry for testing
-    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) - 1); // Already expired
+    // Create a new escrow with a short expiry for testing
     const tempMinter = Keypair.generate();
     
     // Airdrop to temp minter
@@ -444,7 +551,27 @@ describe("fluter-by", () => {
       [tempEscrowWallet1, tempEscrowWallet2, tempEscrowWallet3, tempEscrowWallet4, tempEscrowWallet5]
     );
 
-    // Lock funds with expired date
+    // Create user token account
+    const tempUserTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      tempMainToken,
+      user.publicKey
+    );
+
+    // Mint tokens to user
+    await mintTo(
+      provider.connection,
+      tempMinter,
+      tempMainToken,
+      tempUserTokenAccount.address,
+      tempMinter,
+      TOKEN_SUPPLY.toNumber()
+    );
+
+    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    // Lock funds with a short expiry, then let it pass
     await program.methods
       .lockFunds(
         tempMainToken,
@@ -452,13 +579,16 @@ describe("fluter-by", () => {
         tempMinter.publicKey,
         TOTAL_REWARD_VALUE,
         TOKEN_SUPPLY,
-        immediateExpiry
+        immediateExpiry,
+        lockParams()
       )
       .accounts({
         escrowLockAccount: tempEscrowLockAccount,
         minter: tempMinter.publicKey,
+        tokenMint: tempMainToken,
         rewardTokenMint,
         minterRewardAccount: tempMinterRewardAccount.address,
+        treasuryRewardAccount: null,
         escrowWallet1: tempEscrowWallet1,
         escrowWallet2: tempEscrowWallet2,
         escrowWallet3: tempEscrowWallet3,
@@ -470,28 +600,12 @@ describe("fluter-by", () => {
       .signers([tempMinter])
       .rpc();
 
-    // Create user token account
-    const tempUserTokenAccount = await getOrCreateAssociatedTokenAccount(
-      provider.connection,
-      user,
-      tempMainToken,
-      user.publicKey
-    );
-
-    // Mint tokens to user
-    await mintTo(
-      provider.connection,
-      tempMinter,
-      tempMainToken,
-      tempUserTokenAccount.address,
-      tempMinter,
-      TOKEN_SUPPLY.toNumber()
-    );
+    await waitForExpiry(immediateExpiry);
 
     // Try to redeem (should fail)
     try {
       await program.methods
-        .redeemRewards(new BN(100_000_000))
+        .redeemRewards(new BN(100_000_000), null)
         .accounts({
           escrowLockAccount: tempEscrowLockAccount,
           user: user.publicKey,
@@ -499,15 +613,21 @@ describe("fluter-by", () => {
           tokenMint: tempMainToken,
           userTokenAccount: tempUserTokenAccount.address,
           rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
           userRewardAccount,
-          escrowWallet1: tempEscrowWallet1,
-          escrowWallet2: tempEscrowWallet2,
-          escrowWallet3: tempEscrowWallet3,
-          escrowWallet4: tempEscrowWallet4,
-          escrowWallet5: tempEscrowWallet5,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(
+          escrowWalletMetas([tempEscrowWallet1, tempEscrowWallet2, tempEscrowWallet3, tempEscrowWallet4, tempEscrowWallet5])
+        )
         .signers([user])
         .rpc();
 
@@ -588,6 +708,8 @@ describe("fluter-by", () => {
       TOTAL_REWARD_VALUE.toNumber()
     );
 
+    await mintMainSupply(expiredMainToken, expiredMinter, TOKEN_SUPPLY);
+
     // Initialize escrow wallets
     await initializeEscrowWallets(
       expiredMainToken,
@@ -596,8 +718,8 @@ describe("fluter-by", () => {
       [expiredEscrowWallet1, expiredEscrowWallet2, expiredEscrowWallet3, expiredEscrowWallet4, expiredEscrowWallet5]
     );
 
-    // Lock funds with immediate expiry
-    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) - 1);
+    // Lock funds with a short expiry, then let it pass
+    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) + 3);
     
     await program.methods
       .lockFunds(
@@ -606,13 +728,16 @@ describe("fluter-by", () => {
         expiredMinter.publicKey,
         TOTAL_REWARD_VALUE,
         TOKEN_SUPPLY,
-        immediateExpiry
+        immediateExpiry,
+        lockParams()
       )
       .accounts({
         escrowLockAccount: expiredEscrowLockAccount,
         minter: expiredMinter.publicKey,
+        tokenMint: expiredMainToken,
         rewardTokenMint,
         minterRewardAccount: expiredMinterRewardAccount.address,
+        treasuryRewardAccount: null,
         escrowWallet1: expiredEscrowWallet1,
         escrowWallet2: expiredEscrowWallet2,
         escrowWallet3: expiredEscrowWallet3,
@@ -624,6 +749,8 @@ describe("fluter-by", () => {
       .signers([expiredMinter])
       .rpc();
 
+    await waitForExpiry(immediateExpiry);
+
     console.log("✅ Created expired escrow for withdrawal test");
 
     // Get minter balance before withdrawal
@@ -634,6 +761,39 @@ describe("fluter-by", () => {
 
     console.log("Minter balance before withdrawal:", minterBalanceBefore.amount.toString());
 
+    // Preview the sweep; only the minter may quote it, and nothing changes
+    const expiredWalletMetas = escrowWalletMetas([expiredEscrowWallet1, expiredEscrowWallet2, expiredEscrowWallet3, expiredEscrowWallet4, expiredEscrowWallet5]);
+    const quoted = await program.methods
+      .quoteExpiredWithdrawal()
+      .accounts({
+        escrowLockAccount: expiredEscrowLockAccount,
+        minter: expiredMinter.publicKey,
+        token: expiredMainToken,
+      })
+      .remainingAccounts(expiredWalletMetas)
+      .signers([expiredMinter])
+      .view();
+    assert.equal(quoted.toString(), TOTAL_REWARD_VALUE.toString());
+
+    try {
+      await program.methods
+        .quoteExpiredWithdrawal()
+        .accounts({
+          escrowLockAccount: expiredEscrowLockAccount,
+          minter: user.publicKey,
+          token: expiredMainToken,
+        })
+        .remainingAccounts(expiredWalletMetas)
+        .signers([user])
+        .view();
+      assert.fail("Should have thrown an error for a non-minter quote");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    const quotedEscrow = await program.account.escrowLockAccount.fetch(expiredEscrowLockAccount);
+    assert.deepEqual(quotedEscrow.status, { active: {} });
+
     // Minter withdraws all rewards
     const tx = await program.methods
       .withdrawExpiredRewards()
@@ -643,14 +803,12 @@ describe("fluter-by", () => {
         token: expiredMainToken,
         rewardTokenMint,
         minterRewardAccount: expiredMinterRewardAccount.address,
-        escrowWallet1: expiredEscrowWallet1,
-        escrowWallet2: expiredEscrowWallet2,
-        escrowWallet3: expiredEscrowWallet3,
-        escrowWallet4: expiredEscrowWallet4,
-        escrowWallet5: expiredEscrowWallet5,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(
+        escrowWalletMetas([expiredEscrowWallet1, expiredEscrowWallet2, expiredEscrowWallet3, expiredEscrowWallet4, expiredEscrowWallet5])
+      )
       .signers([expiredMinter])
       .rpc();
 
@@ -673,7 +831,7 @@ describe("fluter-by", () => {
 
     // Verify escrow is now inactive
     const escrowData = await program.account.escrowLockAccount.fetch(expiredEscrowLockAccount);
-    assert.equal(escrowData.isActive, false);
+    assert.deepEqual(escrowData.status, { closed: {} });
     assert.equal(escrowData.remainingRewardValue.toString(), "0");
 
     console.log("✅ Escrow marked as inactive");
@@ -694,6 +852,51 @@ describe("fluter-by", () => {
     }
 
     console.log("✅ All escrow wallets emptied");
+
+    // A second withdrawal finds the escrow inactive, not missing
+    try {
+      await program.methods
+        .withdrawExpiredRewards()
+        .accounts({
+          escrowLockAccount: expiredEscrowLockAccount,
+          minter: expiredMinter.publicKey,
+          token: expiredMainToken,
+          rewardTokenMint,
+          minterRewardAccount: expiredMinterRewardAccount.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([expiredEscrowWallet1, expiredEscrowWallet2, expiredEscrowWallet3, expiredEscrowWallet4, expiredEscrowWallet5])
+        )
+        .signers([expiredMinter])
+        .rpc();
+      assert.fail("Should have thrown an error for a second withdrawal");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowInactive");
+    }
+
+    // Minter closes the escrow to reclaim rent
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        escrowLockAccount: expiredEscrowLockAccount,
+        minter: expiredMinter.publicKey,
+        token: expiredMainToken,
+        rentRecipient: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        escrowWalletMetas([expiredEscrowWallet1, expiredEscrowWallet2, expiredEscrowWallet3, expiredEscrowWallet4, expiredEscrowWallet5])
+      )
+      .signers([expiredMinter])
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(expiredEscrowLockAccount));
+    assert.isNull(await provider.connection.getAccountInfo(expiredEscrowWallet1));
+
+    console.log("✅ Escrow closed and rent reclaimed");
   });
 
   it("Prevents non-minter from withdrawing expired rewards", async () => {
@@ -753,6 +956,8 @@ describe("fluter-by", () => {
       TOTAL_REWARD_VALUE.toNumber()
     );
 
+    await mintMainSupply(expiredMainToken, minter, TOKEN_SUPPLY);
+
     // Initialize escrow wallets
     await initializeEscrowWallets(
       expiredMainToken,
@@ -761,7 +966,7 @@ describe("fluter-by", () => {
       [testEscrowWallet1, testEscrowWallet2, testEscrowWallet3, testEscrowWallet4, testEscrowWallet5]
     );
 
-    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) - 1);
+    const immediateExpiry = new BN(Math.floor(Date.now() / 1000) + 3);
 
     await program.methods
       .lockFunds(
@@ -770,13 +975,16 @@ describe("fluter-by", () => {
         minter.publicKey,
         TOTAL_REWARD_VALUE,
         TOKEN_SUPPLY,
-        immediateExpiry
+        immediateExpiry,
+        lockParams()
       )
       .accounts({
         escrowLockAccount: testEscrowLockAccount,
         minter: minter.publicKey,
+        tokenMint: expiredMainToken,
         rewardTokenMint,
         minterRewardAccount: testMinterRewardAccount.address,
+        treasuryRewardAccount: null,
         escrowWallet1: testEscrowWallet1,
         escrowWallet2: testEscrowWallet2,
         escrowWallet3: testEscrowWallet3,
@@ -788,6 +996,8 @@ describe("fluter-by", () => {
       .signers([minter])
       .rpc();
 
+    await waitForExpiry(immediateExpiry);
+
     // Try to withdraw with user (not minter)
     try {
       await program.methods
@@ -798,14 +1008,12 @@ describe("fluter-by", () => {
           token: expiredMainToken,
           rewardTokenMint,
           minterRewardAccount: userRewardAccount, // User's account
-          escrowWallet1: testEscrowWallet1,
-          escrowWallet2: testEscrowWallet2,
-          escrowWallet3: testEscrowWallet3,
-          escrowWallet4: testEscrowWallet4,
-          escrowWallet5: testEscrowWallet5,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(
+          escrowWalletMetas([testEscrowWallet1, testEscrowWallet2, testEscrowWallet3, testEscrowWallet4, testEscrowWallet5])
+        )
         .signers([user])
         .rpc();
 
@@ -815,4 +1023,7573 @@ describe("fluter-by", () => {
       console.log("✅ Correctly prevented non-minter from withdrawing");
     }
   });
+
+  it("Locks funds across a custom wallet count with lock_funds_v2", async () => {
+    const walletCount = 3;
+    const rewardValue = new BN(9_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const v2MainToken = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+
+    const [v2EscrowLockAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), v2MainToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const v2Wallets = deriveEscrowWallets(v2MainToken, minter.publicKey, walletCount);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+
+    await mintMainSupply(v2MainToken, minter, TOKEN_SUPPLY);
+    await initializeEscrowWallets(v2MainToken, minter, rewardTokenMint, v2Wallets);
+
+    await program.methods
+      .lockFundsV2(
+        v2MainToken,
+        rewardTokenMint,
+        minter.publicKey,
+        rewardValue,
+        TOKEN_SUPPLY,
+        expiryTime,
+        lockParams({ walletCount })
+      )
+      .accounts({
+        escrowLockAccount: v2EscrowLockAccount,
+        minter: minter.publicKey,
+        tokenMint: v2MainToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(v2Wallets))
+      .signers([minter])
+      .rpc();
+
+    const escrowData = await program.account.escrowLockAccount.fetch(v2EscrowLockAccount);
+    assert.equal(escrowData.walletCount, walletCount);
+    assert.equal(escrowData.rewardPerWallet.toString(), rewardValue.div(new BN(walletCount)).toString());
+
+    for (let i = 0; i < walletCount; i++) {
+      assert.equal(escrowData.escrowWallets[i].toString(), v2Wallets[i].toString());
+      const walletAccount = await getAccount(provider.connection, v2Wallets[i]);
+      assert.equal(walletAccount.amount.toString(), rewardValue.div(new BN(walletCount)).toString());
+    }
+
+    console.log("✅ Locked funds across", walletCount, "escrow wallets");
+  });
+
+  it("Rejects an out-of-range escrow wallet index", async () => {
+    const badIndexToken = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+
+    const [badIndexEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), badIndexToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const [badIndexWallet] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_wallet"), badIndexToken.toBuffer(), minter.publicKey.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .initializeEscrowWallet(badIndexToken, 0)
+        .accounts({
+          minter: minter.publicKey,
+          rewardTokenMint,
+          escrowLockAccount: badIndexEscrowLock,
+          escrowWallet: badIndexWallet,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for wallet index 0");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidEscrowWalletIndex");
+      console.log("✅ Correctly rejected escrow wallet index 0");
+    }
+  });
+
+  it("Minter tops up rewards on a live escrow", async () => {
+    const topUpAmount = new BN(5_000_000);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      topUpAmount.toNumber()
+    );
+
+    const escrowBefore = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+
+    await program.methods
+      .topUpRewards(topUpAmount)
+      .accounts({
+        escrowLockAccount,
+        minter: minter.publicKey,
+        token: mainTokenMint,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+      )
+      .signers([minter])
+      .rpc();
+
+    const escrowAfter = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(
+      escrowAfter.totalRewardValue.toString(),
+      escrowBefore.totalRewardValue.add(topUpAmount).toString()
+    );
+    assert.equal(
+      escrowAfter.remainingRewardValue.toString(),
+      escrowBefore.remainingRewardValue.add(topUpAmount).toString()
+    );
+    assert.equal(
+      escrowAfter.rewardPerWallet.toString(),
+      escrowAfter.totalRewardValue.div(new BN(5)).toString()
+    );
+
+    console.log("✅ Escrow topped up by", topUpAmount.toString());
+  });
+
+  it("Minter extends expiry but cannot shorten it", async () => {
+    const escrowBefore = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    const newExpiry = escrowBefore.expiresAt.add(new BN(7 * 24 * 60 * 60));
+
+    await program.methods
+      .extendExpiry(newExpiry)
+      .accounts({
+        escrowLockAccount,
+        minter: minter.publicKey,
+        token: mainTokenMint,
+      })
+      .signers([minter])
+      .rpc();
+
+    const escrowAfter = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(escrowAfter.expiresAt.toString(), newExpiry.toString());
+
+    try {
+      await program.methods
+        .extendExpiry(escrowBefore.expiresAt)
+        .accounts({
+          escrowLockAccount,
+          minter: minter.publicKey,
+          token: mainTokenMint,
+        })
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a shorter expiry");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidExpiry");
+      console.log("✅ Correctly prevented shortening the expiry");
+    }
+  });
+
+  it("Locks and redeems a Token-2022 reward token", async () => {
+    const rewardValue = new BN(5_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const mainToken2022 = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+    const rewardMint2022 = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const [escrowLock2022] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), mainToken2022.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const wallets2022 = deriveEscrowWallets(mainToken2022, minter.publicKey, 5);
+
+    const minterReward2022 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      rewardMint2022,
+      minter.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardMint2022,
+      minterReward2022.address,
+      minter,
+      rewardValue.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // The user holds the whole main token supply
+    const userMain2022 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mainToken2022,
+      user.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      mainToken2022,
+      userMain2022.address,
+      minter,
+      TOKEN_SUPPLY.toNumber()
+    );
+
+    for (let i = 1; i <= 5; i++) {
+      await program.methods
+        .initializeEscrowWallet2022(mainToken2022, i)
+        .accounts({
+          minter: minter.publicKey,
+          rewardTokenMint: rewardMint2022,
+          escrowLockAccount: escrowLock2022,
+          escrowWallet: wallets2022[i - 1],
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    }
+
+    await program.methods
+      .lockFunds2022(
+        mainToken2022,
+        rewardMint2022,
+        minter.publicKey,
+        rewardValue,
+        TOKEN_SUPPLY,
+        expiryTime,
+        lockParams()
+      )
+      .accounts({
+        escrowLockAccount: escrowLock2022,
+        minter: minter.publicKey,
+        tokenMint: mainToken2022,
+        rewardTokenMint: rewardMint2022,
+        minterRewardAccount: minterReward2022.address,
+        treasuryRewardAccount: null,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(wallets2022))
+      .signers([minter])
+      .rpc();
+
+    const escrowData = await program.account.escrowLockAccount.fetch(escrowLock2022);
+    assert.equal(escrowData.totalRewardValue.toString(), rewardValue.toString());
+    assert.equal(escrowData.walletCount, 5);
+
+    // User redeems 10% of supply into a Token-2022 reward account
+    const userReward2022 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      rewardMint2022,
+      user.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // Token-2022 associated token accounts carry ImmutableOwner, so they are longer
+    // than 165 bytes; the frozen-account check must still read them
+    const userRewardInfo = await provider.connection.getAccountInfo(userReward2022.address);
+    assert.isAbove(userRewardInfo.data.length, 165);
+
+    const burnAmount = TOKEN_SUPPLY.div(new BN(10));
+    await program.methods
+      .redeemRewards2022(burnAmount, null)
+      .accounts({
+        escrowLockAccount: escrowLock2022,
+        user: user.publicKey,
+        token: mainToken2022,
+        tokenMint: mainToken2022,
+        userTokenAccount: userMain2022.address,
+        rewardTokenMint: rewardMint2022,
+        userRewardAccount: userReward2022.address,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(escrowWalletMetas(wallets2022))
+      .signers([user])
+      .rpc();
+
+    const userRewardAfter = await getAccount(
+      provider.connection,
+      userReward2022.address,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(userRewardAfter.amount.toString(), rewardValue.div(new BN(10)).toString());
+
+    console.log("✅ Token-2022 rewards locked and redeemed");
+  });
+
+  it("Locks and redeems a Token-2022 reward token with a transfer fee", async () => {
+    const rewardValue = new BN(5_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const feeMain = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+
+    // Reward mint withholding 1% of every transfer, so its token accounts carry the
+    // TransferFeeAmount extension and are longer than the 165-byte SPL layout
+    const feeMintKeypair = Keypair.generate();
+    const feeMint = feeMintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: minter.publicKey,
+          newAccountPubkey: feeMint,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint,
+          minter.publicKey,
+          minter.publicKey,
+          100,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(feeMint, 6, minter.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [minter, feeMintKeypair]
+    );
+
+    const [feeEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), feeMain.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const feeWallets = deriveEscrowWallets(feeMain, minter.publicKey, 5);
+
+    const minterFeeReward = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      feeMint,
+      minter.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      feeMint,
+      minterFeeReward.address,
+      minter,
+      rewardValue.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const userFeeMain = await getOrCreateAssociatedTokenAccount(provider.connection, user, feeMain, user.publicKey);
+    await mintTo(provider.connection, minter, feeMain, userFeeMain.address, minter, TOKEN_SUPPLY.toNumber());
+
+    for (let i = 1; i <= 5; i++) {
+      await program.methods
+        .initializeEscrowWallet2022(feeMain, i)
+        .accounts({
+          minter: minter.publicKey,
+          rewardTokenMint: feeMint,
+          escrowLockAccount: feeEscrowLock,
+          escrowWallet: feeWallets[i - 1],
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    }
+
+    const walletInfo = await provider.connection.getAccountInfo(feeWallets[0]);
+    assert.isAbove(walletInfo.data.length, 165);
+
+    await program.methods
+      .lockFunds2022(feeMain, feeMint, minter.publicKey, rewardValue, TOKEN_SUPPLY, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: feeEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: feeMain,
+        rewardTokenMint: feeMint,
+        minterRewardAccount: minterFeeReward.address,
+        treasuryRewardAccount: null,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(feeWallets))
+      .signers([minter])
+      .rpc();
+
+    // Each wallet was sent 1_000_000 and received 990_000 after the 1% fee
+    let escrowData = await program.account.escrowLockAccount.fetch(feeEscrowLock);
+    assert.equal(escrowData.totalRewardValue.toString(), "4950000");
+
+    const userFeeReward = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      feeMint,
+      user.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // 10% of supply is worth 495_000, drawn as 99_000 per wallet; each draw withholds 990
+    await program.methods
+      .redeemRewards2022(TOKEN_SUPPLY.div(new BN(10)), null)
+      .accounts({
+        escrowLockAccount: feeEscrowLock,
+        user: user.publicKey,
+        token: feeMain,
+        tokenMint: feeMain,
+        userTokenAccount: userFeeMain.address,
+        rewardTokenMint: feeMint,
+        userRewardAccount: userFeeReward.address,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(escrowWalletMetas(feeWallets))
+      .signers([user])
+      .rpc();
+
+    const userRewardAfter = await getAccount(provider.connection, userFeeReward.address, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(userRewardAfter.amount.toString(), "490050");
+
+    escrowData = await program.account.escrowLockAccount.fetch(feeEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "4455000");
+
+    console.log("✅ Transfer-fee reward token locked and redeemed");
+  });
+
+  it("Reverts redemption below min_reward_out without burning", async () => {
+    const burnAmount = new BN(10_000_000);
+    const userMainBefore = await getAccount(provider.connection, userMainTokenAccount);
+
+    try {
+      await program.methods
+        .redeemRewards(burnAmount, new BN("18446744073709551615"))
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: mainTokenMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for slippage");
+    } catch (error) {
+      assert.include(error.toString(), "SlippageExceeded");
+    }
+
+    const userMainAfter = await getAccount(provider.connection, userMainTokenAccount);
+    assert.equal(userMainAfter.amount.toString(), userMainBefore.amount.toString());
+    console.log("✅ Slippage guard reverted without burning");
+  });
+
+  it("Drains the escrow when every holder redeems", async () => {
+    const rewardValue = new BN(7_000_003);
+    const supply = new BN(3_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const drainToken = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+    const [drainEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), drainToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const drainWallets = deriveEscrowWallets(drainToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await initializeEscrowWallets(drainToken, minter, rewardTokenMint, drainWallets);
+
+    // Three holders with uneven balances that add up to the full supply
+    const holderBalances = [new BN(1_700_000), new BN(900_000), new BN(400_000)];
+    const holders = [];
+    for (const balance of holderBalances) {
+      const holder = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        holder.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      const holderMain = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        holder,
+        drainToken,
+        holder.publicKey
+      );
+      await mintTo(provider.connection, minter, drainToken, holderMain.address, minter, balance.toNumber());
+      const holderReward = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        holder,
+        rewardTokenMint,
+        holder.publicKey
+      );
+      holders.push({ holder, holderMain, holderReward, balance });
+    }
+
+    await program.methods
+      .lockFundsV2(drainToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: drainEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: drainToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(drainWallets))
+      .signers([minter])
+      .rpc();
+
+    for (const { holder, holderMain, holderReward, balance } of holders) {
+      await program.methods
+        .redeemRewards(balance, null)
+        .accounts({
+          escrowLockAccount: drainEscrowLock,
+          user: holder.publicKey,
+          token: drainToken,
+          tokenMint: drainToken,
+          userTokenAccount: holderMain.address,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: holderReward.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(drainWallets))
+        .signers([holder])
+        .rpc();
+    }
+
+    const escrowData = await program.account.escrowLockAccount.fetch(drainEscrowLock);
+    assert.equal(escrowData.totalTokenSupply.toString(), "0");
+    assert.equal(escrowData.remainingRewardValue.toString(), "0");
+    assert.equal(escrowData.totalBurned.toString(), supply.toString());
+    assert.equal(escrowData.redemptionCount.toString(), holderBalances.length.toString());
+    assert.isAbove(escrowData.lastRedeemedAt.toNumber(), 0);
+    assert.deepEqual(escrowData.status, { drained: {} });
+
+    // A late holder must not be able to burn into the empty pool
+    const { holder: lateHolder, holderMain: lateMain, holderReward: lateReward } = holders[0];
+    await mintTo(provider.connection, minter, drainToken, lateMain.address, minter, 100_000);
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: drainEscrowLock,
+          user: lateHolder.publicKey,
+          token: drainToken,
+          tokenMint: drainToken,
+          userTokenAccount: lateMain.address,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: lateReward.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(drainWallets))
+        .signers([lateHolder])
+        .rpc();
+
+      assert.fail("Should have thrown an error for an empty pool");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+    }
+
+    const lateMainAfter = await getAccount(provider.connection, lateMain.address);
+    assert.equal(lateMainAfter.amount.toString(), "100000");
+
+    console.log("✅ Escrow fully drained after every holder redeemed");
+  });
+
+  it("Rejects redemption with a reward token other than the escrow's", async () => {
+    const otherRewardMint = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+    const userOtherRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      otherRewardMint,
+      user.publicKey
+    );
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: mainTokenMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: otherRewardMint,
+          rewardTokenMint: otherRewardMint,
+          userRewardAccount: userOtherRewardAccount.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a mismatched reward token");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    console.log("✅ Mismatched reward token rejected");
+  });
+
+  it("Rejects redemption with a reward mint account other than the escrow's", async () => {
+    const otherRewardMint = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: mainTokenMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: otherRewardMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a mismatched reward mint");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    console.log("✅ Mismatched reward mint rejected");
+  });
+
+  it("Rejects redemption that would burn a different mint", async () => {
+    const otherMint = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: mainTokenMint,
+          tokenMint: otherMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a mismatched token mint");
+    } catch (error) {
+      assert.include(error.toString(), "TokenMismatch");
+    }
+
+    console.log("✅ Mismatched token mint rejected");
+  });
+
+  it("Rejects redemption naming a token the escrow wasn't locked for", async () => {
+    const otherMint = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6
+    );
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: otherMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a token with no escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotFound");
+      assert.notInclude(error.toString(), "ConstraintSeeds");
+    }
+
+    // The other redemption instructions report it the same way
+    try {
+      await program.methods
+        .redeemRewardsTo(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: otherMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount: userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a token with no escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotFound");
+      assert.notInclude(error.toString(), "ConstraintSeeds");
+    }
+
+    try {
+      await program.methods
+        .redeemRewardsSplit(new BN(1_000_000), null, [10_000])
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: otherMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          ...escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5]),
+          { pubkey: userRewardAccount, isWritable: true, isSigner: false },
+        ])
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a token with no escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotFound");
+      assert.notInclude(error.toString(), "ConstraintSeeds");
+    }
+
+    try {
+      await program.methods
+        .redeemRewardsAndClose(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: otherMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount: minterRewardAccount,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a token with no escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotFound");
+      assert.notInclude(error.toString(), "ConstraintSeeds");
+    }
+
+    console.log("✅ Mismatched token reported as EscrowNotFound");
+  });
+
+  it("Quotes a redemption without burning", async () => {
+    const burnAmount = new BN(25_000_000);
+    const escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    const userMainBefore = await getAccount(provider.connection, userMainTokenAccount);
+
+    const quote = await program.methods
+      .quoteRedemption(burnAmount)
+      .accounts({
+        escrowLockAccount,
+        token: mainTokenMint,
+      })
+      .view();
+
+    const expectedReward = burnAmount
+      .mul(escrowData.remainingRewardValue)
+      .div(escrowData.totalTokenSupply);
+    assert.equal(quote.toString(), expectedReward.toString());
+
+    const userMainAfter = await getAccount(provider.connection, userMainTokenAccount);
+    assert.equal(userMainAfter.amount.toString(), userMainBefore.amount.toString());
+    console.log("✅ Quoted", quote.toString(), "reward tokens for", burnAmount.toString(), "burned");
+  });
+
+  it("Quotes burning a user's whole balance", async () => {
+    const escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    const balance = (await getAccount(provider.connection, userMainTokenAccount)).amount;
+
+    const maxQuote = await program.methods
+      .quoteMaxRedemption()
+      .accounts({
+        escrowLockAccount,
+        token: mainTokenMint,
+        userTokenAccount: userMainTokenAccount,
+      })
+      .view();
+
+    // Same as quoting the balance by hand, and never more than the pool holds
+    const quote = await program.methods
+      .quoteRedemption(new BN(balance.toString()))
+      .accounts({
+        escrowLockAccount,
+        token: mainTokenMint,
+      })
+      .view();
+    assert.equal(maxQuote.toString(), quote.toString());
+    assert.isTrue(maxQuote.lte(escrowData.remainingRewardValue));
+
+    const userMainAfter = await getAccount(provider.connection, userMainTokenAccount);
+    assert.equal(userMainAfter.amount.toString(), balance.toString());
+    console.log("✅ Quoted", maxQuote.toString(), "reward tokens for the whole balance of", balance.toString());
+  });
+
+  it("Returns the payout of a redemption as return data", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(250_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const returnToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [returnEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), returnToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const returnWallets = deriveEscrowWallets(returnToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(returnToken, minter, supply);
+    await initializeEscrowWallets(returnToken, minter, rewardTokenMint, returnWallets);
+
+    await program.methods
+      .lockFundsV2(returnToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: returnEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: returnToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(returnWallets))
+      .signers([minter])
+      .rpc();
+
+    const quote = await program.methods
+      .quoteRedemption(burnAmount)
+      .accounts({
+        escrowLockAccount: returnEscrowLock,
+        token: returnToken,
+      })
+      .view();
+
+    const signature = await program.methods
+      .redeemRewards(burnAmount, null)
+      .accounts({
+        escrowLockAccount: returnEscrowLock,
+        user: minter.publicKey,
+        token: returnToken,
+        tokenMint: returnToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(returnWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    assert.equal(tx.meta.returnData.programId.toString(), program.programId.toString());
+    const payout = new BN(Buffer.from(tx.meta.returnData.data[0], "base64"), "le");
+    assert.equal(payout.toString(), quote.toString());
+    assert.equal(payout.toString(), "250000");
+
+    console.log("✅ Redemption payout returned to the caller:", payout.toString());
+  });
+
+  it("Minter hands authority to a new key without moving the escrow PDAs", async () => {
+    const newMinter = Keypair.generate();
+
+    await program.methods
+      .transferMinterAuthority(newMinter.publicKey)
+      .accounts({
+        escrowLockAccount,
+        minter: minter.publicKey,
+        token: mainTokenMint,
+      })
+      .signers([minter])
+      .rpc();
+
+    let escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(escrowData.minter.toString(), newMinter.publicKey.toString());
+    assert.equal(escrowData.creator.toString(), minter.publicKey.toString());
+
+    // The old minter can no longer act on the escrow
+    try {
+      await program.methods
+        .extendExpiry(escrowData.expiresAt.add(new BN(60)))
+        .accounts({
+          escrowLockAccount,
+          minter: minter.publicKey,
+          token: mainTokenMint,
+        })
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for the old minter");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    // The new minter acts on the same PDA and hands authority back
+    await program.methods
+      .transferMinterAuthority(minter.publicKey)
+      .accounts({
+        escrowLockAccount,
+        minter: newMinter.publicKey,
+        token: mainTokenMint,
+      })
+      .signers([newMinter])
+      .rpc();
+
+    escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(escrowData.minter.toString(), minter.publicKey.toString());
+    console.log("✅ Minter authority transferred and returned");
+  });
+
+  it("Charges a redemption fee to the treasury", async () => {
+    const rewardValue = new BN(1_000_000_000);
+    const supply = new BN(1_000_000);
+    const feeBps = 250;
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const feeToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [feeEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), feeToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const feeWallets = deriveEscrowWallets(feeToken, minter.publicKey, 5);
+
+    const treasury = Keypair.generate();
+    const treasuryRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      treasury.publicKey
+    );
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await initializeEscrowWallets(feeToken, minter, rewardTokenMint, feeWallets);
+
+    // The user holds the whole main token supply
+    const userFeeTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      feeToken,
+      user.publicKey
+    );
+    await mintTo(provider.connection, minter, feeToken, userFeeTokenAccount.address, minter, supply.toNumber());
+
+    await program.methods
+      .lockFundsV2(feeToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ feeBps }))
+      .accounts({
+        escrowLockAccount: feeEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: feeToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: treasuryRewardAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(feeWallets))
+      .signers([minter])
+      .rpc();
+
+    const burnAmount = new BN(100_000);
+
+    const userRewardBefore = await getAccount(provider.connection, userRewardAccount);
+
+    await program.methods
+      .redeemRewards(burnAmount, null)
+      .accounts({
+        escrowLockAccount: feeEscrowLock,
+        user: user.publicKey,
+        token: feeToken,
+        tokenMint: feeToken,
+        userTokenAccount: userFeeTokenAccount.address,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount,
+        treasuryRewardAccount: treasuryRewardAccount.address,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(feeWallets))
+      .signers([user])
+      .rpc();
+
+    // 10% of supply burned → 100,000,000 reward, 2.5% of which is the fee
+    const expectedReward = burnAmount.mul(rewardValue).div(supply);
+    const expectedFee = expectedReward.mul(new BN(feeBps)).div(new BN(10_000));
+
+    const userRewardAfter = await getAccount(provider.connection, userRewardAccount);
+    const treasuryAfter = await getAccount(provider.connection, treasuryRewardAccount.address);
+    assert.equal(
+      (userRewardAfter.amount - userRewardBefore.amount).toString(),
+      expectedReward.sub(expectedFee).toString()
+    );
+    assert.equal(treasuryAfter.amount.toString(), expectedFee.toString());
+
+    const escrowData = await program.account.escrowLockAccount.fetch(feeEscrowLock);
+    assert.equal(
+      escrowData.remainingRewardValue.toString(),
+      rewardValue.sub(expectedReward).toString()
+    );
+    console.log("✅ Treasury received", expectedFee.toString(), "in fees");
+  });
+
+  it("Rejects a redemption fee above the cap", async () => {
+    const capToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    await mintMainSupply(capToken, minter, new BN(1_000_000));
+    const [capEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), capToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .lockFundsV2(
+          capToken,
+          rewardTokenMint,
+          minter.publicKey,
+          new BN(1_000_000),
+          new BN(1_000_000),
+          new BN(Math.floor(Date.now() / 1000) + 60 * 60),
+          lockParams({ feeBps: 1001 })
+        )
+        .accounts({
+          escrowLockAccount: capEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: capToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a fee above the cap");
+    } catch (error) {
+      assert.include(error.toString(), "FeeTooHigh");
+    }
+
+    console.log("✅ Fee above 1000 bps rejected");
+  });
+
+  it("Reverts the whole redemption when a reward transfer fails", async () => {
+    const rewardValue = new BN(5_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(200_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // A reward mint with a freeze authority lets us make the payout transfer fail
+    const freezableReward = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      minter.publicKey,
+      6
+    );
+    const minterFreezable = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      freezableReward,
+      minter.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      freezableReward,
+      minterFreezable.address,
+      minter,
+      rewardValue.toNumber()
+    );
+
+    const guardToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [guardEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), guardToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const guardWallets = deriveEscrowWallets(guardToken, minter.publicKey, 5);
+    await initializeEscrowWallets(guardToken, minter, freezableReward, guardWallets);
+
+    // The user holds the whole main token supply
+    const userGuardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      guardToken,
+      user.publicKey
+    );
+    await mintTo(provider.connection, minter, guardToken, userGuardTokenAccount.address, minter, supply.toNumber());
+
+    await program.methods
+      .lockFundsV2(guardToken, freezableReward, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: guardEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: guardToken,
+        rewardTokenMint: freezableReward,
+        minterRewardAccount: minterFreezable.address,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(guardWallets))
+      .signers([minter])
+      .rpc();
+
+    const userFreezable = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      freezableReward,
+      user.publicKey
+    );
+    await freezeAccount(provider.connection, minter, userFreezable.address, freezableReward, minter);
+
+    try {
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: guardEscrowLock,
+          user: user.publicKey,
+          token: guardToken,
+          tokenMint: guardToken,
+          userTokenAccount: userGuardTokenAccount.address,
+          rewardToken: freezableReward,
+          rewardTokenMint: freezableReward,
+          userRewardAccount: userFreezable.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(guardWallets))
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a frozen reward account");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+    }
+
+    // Nothing was burned, paid out or recorded
+    const userGuardAfter = await getAccount(provider.connection, userGuardTokenAccount.address);
+    assert.equal(userGuardAfter.amount.toString(), supply.toString());
+    const escrowData = await program.account.escrowLockAccount.fetch(guardEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), rewardValue.toString());
+    assert.equal(escrowData.totalTokenSupply.toString(), supply.toString());
+    assert.equal(escrowData.redemptionCount.toString(), "0");
+    assert.isFalse(escrowData.isRedeeming);
+    console.log("✅ Failed transfer reverted the burn and the state update");
+  });
+
+  it("Batch-initializes all five escrow wallets in one instruction", async () => {
+    const batchToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [batchEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), batchToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const batchWallets = deriveEscrowWallets(batchToken, minter.publicKey, 5);
+
+    await program.methods
+      .batchInitializeEscrowWallets(batchToken)
+      .accounts({
+        minter: minter.publicKey,
+        rewardTokenMint,
+        escrowLockAccount: batchEscrowLock,
+        escrowWallet1: batchWallets[0],
+        escrowWallet2: batchWallets[1],
+        escrowWallet3: batchWallets[2],
+        escrowWallet4: batchWallets[3],
+        escrowWallet5: batchWallets[4],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([minter])
+      .rpc();
+
+    for (const wallet of batchWallets) {
+      const walletAccount = await getAccount(provider.connection, wallet);
+      assert.equal(walletAccount.mint.toString(), rewardTokenMint.toString());
+      assert.equal(walletAccount.owner.toString(), batchEscrowLock.toString());
+    }
+    console.log("✅ All five escrow wallets created in one instruction");
+  });
+
+  it("Redeems rewards to a third-party recipient", async () => {
+    const burnAmount = new BN(5_000_000);
+    const custodian = Keypair.generate();
+    const custodianRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      custodian.publicKey
+    );
+
+    const expectedReward = await program.methods
+      .quoteRedemption(burnAmount)
+      .accounts({ escrowLockAccount, token: mainTokenMint })
+      .view();
+    const userRewardBefore = await getAccount(provider.connection, userRewardAccount);
+
+    await program.methods
+      .redeemRewardsTo(burnAmount, null)
+      .accounts({
+        escrowLockAccount,
+        user: user.publicKey,
+        token: mainTokenMint,
+        tokenMint: mainTokenMint,
+        userTokenAccount: userMainTokenAccount,
+        rewardToken: rewardTokenMint,
+        recipientRewardAccount: custodianRewardAccount.address,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+      )
+      .signers([user])
+      .rpc();
+
+    const custodianAfter = await getAccount(provider.connection, custodianRewardAccount.address);
+    const userRewardAfter = await getAccount(provider.connection, userRewardAccount);
+    assert.equal(custodianAfter.amount.toString(), expectedReward.toString());
+    assert.equal(userRewardAfter.amount.toString(), userRewardBefore.amount.toString());
+    console.log("✅ Rewards paid to the recipient, not the signer");
+  });
+
+  it("Rejects an escrow wallet as the reward recipient", async () => {
+    try {
+      await program.methods
+        .redeemRewardsTo(new BN(1_000_000), null)
+        .accounts({
+          escrowLockAccount,
+          user: user.publicKey,
+          token: mainTokenMint,
+          tokenMint: mainTokenMint,
+          userTokenAccount: userMainTokenAccount,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount: escrowWallet1,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          escrowWalletMetas([escrowWallet1, escrowWallet2, escrowWallet3, escrowWallet4, escrowWallet5])
+        )
+        .signers([user])
+        .rpc();
+      assert.fail("Should have thrown an error for paying rewards into an escrow wallet");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+    console.log("✅ Escrow wallets can't receive their own rewards");
+  });
+
+  it("Rejects burning more than the recorded supply", async () => {
+    const rewardValue = new BN(1_000_000);
+    // The supply at lock time is small; the mint authority inflates it afterwards
+    const recordedSupply = new BN(1_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const lowSupplyToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [lowSupplyEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), lowSupplyToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const lowSupplyWallets = deriveEscrowWallets(lowSupplyToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(lowSupplyToken, minter, recordedSupply);
+    await initializeEscrowWallets(lowSupplyToken, minter, rewardTokenMint, lowSupplyWallets);
+
+    await program.methods
+      .lockFundsV2(lowSupplyToken, rewardTokenMint, minter.publicKey, rewardValue, recordedSupply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: lowSupplyEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: lowSupplyToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(lowSupplyWallets))
+      .signers([minter])
+      .rpc();
+
+    const userLowSupplyAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lowSupplyToken,
+      user.publicKey
+    );
+    await mintTo(provider.connection, minter, lowSupplyToken, userLowSupplyAccount.address, minter, 5_000);
+
+    try {
+      await program.methods
+        .redeemRewards(new BN(2_000), null)
+        .accounts({
+          escrowLockAccount: lowSupplyEscrowLock,
+          user: user.publicKey,
+          token: lowSupplyToken,
+          tokenMint: lowSupplyToken,
+          userTokenAccount: userLowSupplyAccount.address,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(lowSupplyWallets))
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for burning more than the supply");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    const escrowData = await program.account.escrowLockAccount.fetch(lowSupplyEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), rewardValue.toString());
+    console.log("✅ Burn above the recorded supply rejected");
+  });
+
+  it("Rejects a token_supply that does not match the mint", async () => {
+    const mismatchToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    await mintMainSupply(mismatchToken, minter, new BN(1_000_000));
+    const [mismatchEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), mismatchToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .lockFundsV2(
+          mismatchToken,
+          rewardTokenMint,
+          minter.publicKey,
+          new BN(1_000_000),
+          new BN(2_000_000),
+          new BN(Math.floor(Date.now() / 1000) + 60 * 60),
+          lockParams()
+        )
+        .accounts({
+          escrowLockAccount: mismatchEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: mismatchToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a mismatched token supply");
+    } catch (error) {
+      assert.include(error.toString(), "TokenSupplyMismatch");
+    }
+
+    console.log("✅ token_supply checked against the mint");
+  });
+
+  it("Holds back the minter's withdrawal until the grace period ends", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const gracePeriod = new BN(60 * 60);
+
+    const graceToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [graceEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), graceToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const graceWallets = deriveEscrowWallets(graceToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(graceToken, minter, supply);
+    await initializeEscrowWallets(graceToken, minter, rewardTokenMint, graceWallets);
+
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    await program.methods
+      .lockFundsV2(graceToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ gracePeriod }))
+      .accounts({
+        escrowLockAccount: graceEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: graceToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(graceWallets))
+      .signers([minter])
+      .rpc();
+
+    // Expired, but still inside the one-hour grace period
+    await waitForExpiry(expiryTime);
+
+    try {
+      await program.methods
+        .withdrawExpiredRewards()
+        .accounts({
+          escrowLockAccount: graceEscrowLock,
+          minter: minter.publicKey,
+          token: graceToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(graceWallets))
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error during the grace period");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotExpired");
+    }
+
+    const escrowData = await program.account.escrowLockAccount.fetch(graceEscrowLock);
+    assert.equal(escrowData.gracePeriod.toString(), gracePeriod.toString());
+    assert.equal(escrowData.remainingRewardValue.toString(), rewardValue.toString());
+    console.log("✅ Withdrawal blocked during the grace period");
+  });
+
+  it("Caps redemptions to the linearly vested part of the pool", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(100_000);
+    const vestingDuration = new BN(1_000_000);
+    const now = Math.floor(Date.now() / 1000);
+    const expiryTime = new BN(now + 30 * 24 * 60 * 60);
+
+    // Locks a fresh escrow with the given vesting start, then burns 10% of the supply
+    async function redeemVested(vestingStart: BN) {
+      const vestToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+      const [vestEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), vestToken.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const vestWallets = deriveEscrowWallets(vestToken, minter.publicKey, 5);
+
+      await mintTo(
+        provider.connection,
+        minter,
+        rewardTokenMint,
+        minterRewardAccount,
+        minter,
+        rewardValue.toNumber()
+      );
+      const minterMainAccount = await mintMainSupply(vestToken, minter, supply);
+      await initializeEscrowWallets(vestToken, minter, rewardTokenMint, vestWallets);
+
+      await program.methods
+        .lockFundsV2(vestToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ vestingStart, vestingDuration }))
+        .accounts({
+          escrowLockAccount: vestEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: vestToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(vestWallets))
+        .signers([minter])
+        .rpc();
+
+      const before = await getAccount(provider.connection, minterRewardAccount);
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: vestEscrowLock,
+          user: minter.publicKey,
+          token: vestToken,
+          tokenMint: vestToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(vestWallets))
+        .signers([minter])
+        .rpc();
+      const after = await getAccount(provider.connection, minterRewardAccount);
+
+      return Number(after.amount - before.amount);
+    }
+
+    // 0% elapsed: the schedule has not started yet
+    try {
+      await redeemVested(new BN(now + 24 * 60 * 60));
+      assert.fail("Should have thrown an error before vesting starts");
+    } catch (error) {
+      assert.include(error.toString(), "RewardsNotVested");
+    }
+
+    // 50% elapsed: half of the pool is redeemable (allowing for clock drift)
+    const halfReward = await redeemVested(new BN(now - vestingDuration.toNumber() / 2));
+    assert.closeTo(halfReward, 50_000, 100);
+
+    // 100% elapsed: the full proportional reward is paid
+    const fullReward = await redeemVested(new BN(now - vestingDuration.toNumber()));
+    assert.equal(fullReward, 100_000);
+
+    console.log("✅ Redemptions follow the linear vesting schedule");
+  });
+
+  it("Rejects locks while the program is paused", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // Only the config admin can pause
+    try {
+      await program.methods
+        .setPaused(true)
+        .accounts({ admin: user.publicKey })
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a non-admin");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedAdmin");
+    }
+
+    const pausedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [pausedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), pausedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const pausedWallets = deriveEscrowWallets(pausedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(pausedToken, minter, supply);
+    await initializeEscrowWallets(pausedToken, minter, rewardTokenMint, pausedWallets);
+
+    await program.methods
+      .setPaused(true)
+      .accounts({ admin: provider.wallet.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .lockFundsV2(pausedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: pausedEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: pausedToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(pausedWallets))
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error while paused");
+    } catch (error) {
+      assert.include(error.toString(), "ProgramPaused");
+    }
+
+    await program.methods
+      .setPaused(false)
+      .accounts({ admin: provider.wallet.publicKey })
+      .rpc();
+
+    const [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    const configData = await program.account.programConfig.fetch(programConfig);
+    assert.isFalse(configData.paused);
+    assert.equal(configData.admin.toString(), provider.wallet.publicKey.toString());
+
+    console.log("✅ Locks rejected while paused");
+  });
+
+  it("Cancels a lock before any redemption and refunds the minter", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // Locks a fresh escrow whose supply is held by the minter
+    async function lockFresh() {
+      const cancelToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+      const [cancelEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), cancelToken.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const cancelWallets = deriveEscrowWallets(cancelToken, minter.publicKey, 5);
+
+      await mintTo(
+        provider.connection,
+        minter,
+        rewardTokenMint,
+        minterRewardAccount,
+        minter,
+        rewardValue.toNumber()
+      );
+      const minterMainAccount = await mintMainSupply(cancelToken, minter, supply);
+      await initializeEscrowWallets(cancelToken, minter, rewardTokenMint, cancelWallets);
+
+      await program.methods
+        .lockFundsV2(cancelToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: cancelEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: cancelToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cancelWallets))
+        .signers([minter])
+        .rpc();
+
+      return { cancelToken, cancelEscrowLock, cancelWallets, minterMainAccount };
+    }
+
+    async function cancel(cancelToken: PublicKey, cancelEscrowLock: PublicKey, cancelWallets: PublicKey[]) {
+      await program.methods
+        .cancelLock()
+        .accounts({
+          escrowLockAccount: cancelEscrowLock,
+          minter: minter.publicKey,
+          token: cancelToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cancelWallets))
+        .signers([minter])
+        .rpc();
+    }
+
+    // No redemptions yet: the whole pool goes back to the minter
+    const untouched = await lockFresh();
+    const before = await getAccount(provider.connection, minterRewardAccount);
+    await cancel(untouched.cancelToken, untouched.cancelEscrowLock, untouched.cancelWallets);
+    const after = await getAccount(provider.connection, minterRewardAccount);
+
+    assert.equal((after.amount - before.amount).toString(), rewardValue.toString());
+    const escrowData = await program.account.escrowLockAccount.fetch(untouched.cancelEscrowLock);
+    assert.deepEqual(escrowData.status, { closed: {} });
+    assert.equal(escrowData.remainingRewardValue.toString(), "0");
+
+    // After a redemption the lock can no longer be cancelled
+    const redeemed = await lockFresh();
+    await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: redeemed.cancelEscrowLock,
+        user: minter.publicKey,
+        token: redeemed.cancelToken,
+        tokenMint: redeemed.cancelToken,
+        userTokenAccount: redeemed.minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(redeemed.cancelWallets))
+      .signers([minter])
+      .rpc();
+
+    try {
+      await cancel(redeemed.cancelToken, redeemed.cancelEscrowLock, redeemed.cancelWallets);
+      assert.fail("Should have thrown an error after a redemption");
+    } catch (error) {
+      assert.include(error.toString(), "RedemptionsInProgress");
+    }
+
+    console.log("✅ Lock cancelled before redemptions, blocked after");
+  });
+
+  it("Reclaims dust left in the escrow wallets once no rewards remain", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const dustToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [dustEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), dustToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const dustWallets = deriveEscrowWallets(dustToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(dustToken, minter, supply);
+    await initializeEscrowWallets(dustToken, minter, rewardTokenMint, dustWallets);
+
+    await program.methods
+      .lockFundsV2(dustToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: dustEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: dustToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(dustWallets))
+      .signers([minter])
+      .rpc();
+
+    const reclaim = () =>
+      program.methods
+        .reclaimDust()
+        .accounts({
+          escrowLockAccount: dustEscrowLock,
+          minter: minter.publicKey,
+          token: dustToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(dustWallets))
+        .signers([minter])
+        .rpc();
+
+    // Funds holders can still redeem are never touched
+    try {
+      await reclaim();
+      assert.fail("Should have thrown an error while rewards remain");
+    } catch (error) {
+      assert.include(error.toString(), "RewardsStillRemaining");
+    }
+
+    await program.methods
+      .cancelLock()
+      .accounts({
+        escrowLockAccount: dustEscrowLock,
+        minter: minter.publicKey,
+        token: dustToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(dustWallets))
+      .signers([minter])
+      .rpc();
+
+    // Stray tokens land in a wallet after the pool was emptied
+    await mintTo(provider.connection, minter, rewardTokenMint, dustWallets[2], minter, 7);
+
+    const before = await getAccount(provider.connection, minterRewardAccount);
+    await reclaim();
+    const after = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal((after.amount - before.amount).toString(), "7");
+    const wallet = await getAccount(provider.connection, dustWallets[2]);
+    assert.equal(wallet.amount.toString(), "0");
+
+    // Reclaiming again with empty wallets moves nothing and doesn't fail
+    await reclaim();
+    const again = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal(again.amount.toString(), after.amount.toString());
+
+    console.log("✅ Dust reclaimed only once no rewards remain");
+  });
+
+  it("Reports drift between the swept total and the recorded remaining rewards", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    const driftToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [driftEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), driftToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const driftWallets = deriveEscrowWallets(driftToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(driftToken, minter, supply);
+    await initializeEscrowWallets(driftToken, minter, rewardTokenMint, driftWallets);
+
+    await program.methods
+      .lockFundsV2(driftToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: driftEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: driftToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(driftWallets))
+      .signers([minter])
+      .rpc();
+
+    // Stray tokens land in a wallet, so the wallets hold more than was recorded
+    await mintTo(provider.connection, minter, rewardTokenMint, driftWallets[1], minter, 9);
+    await waitForExpiry(expiryTime);
+
+    const signature = await program.methods
+      .withdrawExpiredRewards()
+      .accounts({
+        escrowLockAccount: driftEscrowLock,
+        minter: minter.publicKey,
+        token: driftToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(driftWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const events = [...eventParser.parseLogs(tx.meta.logMessages)];
+
+    const drift = events.filter((event) => event.name === "remainingRewardReconciled");
+    assert.equal(drift.length, 1);
+    assert.equal(drift[0].data.recordedValue.toString(), "1000000");
+    assert.equal(drift[0].data.walletBalanceTotal.toString(), "1000009");
+
+    const withdrawn = events.filter((event) => event.name === "expiredRewardsWithdrawn");
+    assert.equal(withdrawn.length, 1);
+    assert.equal(withdrawn[0].data.amountWithdrawn.toString(), "1000009");
+    assert.equal(withdrawn[0].data.remainingRewardValue.toString(), "1000000");
+
+    console.log("✅ Withdrawal drift surfaced in events");
+  });
+
+  it("Rolls an expired escrow's rewards into a new escrow", async () => {
+    const oldRewardValue = new BN(1_000_000);
+    const newRewardValue = new BN(500_000);
+    const supply = new BN(1_000_000);
+    const oldExpiry = new BN(Math.floor(Date.now() / 1000) + 3);
+    const newExpiry = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // Locks one fresh escrow for the minter and returns its PDA, token and wallets
+    async function lockCampaign(rewardValue: BN, expiryTime: BN) {
+      const campaignToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+      const [campaignEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), campaignToken.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const campaignWallets = deriveEscrowWallets(campaignToken, minter.publicKey, 5);
+
+      await mintTo(
+        provider.connection,
+        minter,
+        rewardTokenMint,
+        minterRewardAccount,
+        minter,
+        rewardValue.toNumber()
+      );
+      await mintMainSupply(campaignToken, minter, supply);
+      await initializeEscrowWallets(campaignToken, minter, rewardTokenMint, campaignWallets);
+
+      await program.methods
+        .lockFundsV2(campaignToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: campaignEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: campaignToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(campaignWallets))
+        .signers([minter])
+        .rpc();
+
+      return { campaignToken, campaignEscrowLock, campaignWallets };
+    }
+
+    const oldCampaign = await lockCampaign(oldRewardValue, oldExpiry);
+    const newCampaign = await lockCampaign(newRewardValue, newExpiry);
+
+    const rollover = () =>
+      program.methods
+        .rolloverExpired()
+        .accounts({
+          escrowLockAccount: oldCampaign.campaignEscrowLock,
+          newEscrowLockAccount: newCampaign.campaignEscrowLock,
+          minter: minter.publicKey,
+          token: oldCampaign.campaignToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          ...escrowWalletMetas(oldCampaign.campaignWallets),
+          ...escrowWalletMetas(newCampaign.campaignWallets),
+        ])
+        .signers([minter])
+        .rpc({ commitment: "confirmed" });
+
+    // Rewards holders can still redeem are never rolled over
+    try {
+      await rollover();
+      assert.fail("Should have thrown an error before the old escrow expired");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotExpired");
+    }
+
+    await waitForExpiry(oldExpiry);
+
+    const minterBefore = await getAccount(provider.connection, minterRewardAccount);
+    const signature = await rollover();
+
+    // Nothing passes through the minter's wallet
+    const minterAfter = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal(minterAfter.amount.toString(), minterBefore.amount.toString());
+
+    const oldEscrow = await program.account.escrowLockAccount.fetch(oldCampaign.campaignEscrowLock);
+    assert.deepEqual(oldEscrow.status, { closed: {} });
+    assert.equal(oldEscrow.remainingRewardValue.toString(), "0");
+    for (const wallet of oldCampaign.campaignWallets) {
+      assert.equal((await getAccount(provider.connection, wallet)).amount.toString(), "0");
+    }
+
+    const newEscrow = await program.account.escrowLockAccount.fetch(newCampaign.campaignEscrowLock);
+    assert.equal(newEscrow.totalRewardValue.toString(), "1500000");
+    assert.equal(newEscrow.remainingRewardValue.toString(), "1500000");
+    for (const wallet of newCampaign.campaignWallets) {
+      assert.equal((await getAccount(provider.connection, wallet)).amount.toString(), "300000");
+    }
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const events = [...eventParser.parseLogs(tx.meta.logMessages)];
+    const withdrawn = events.filter((event) => event.name === "expiredRewardsWithdrawn");
+    const locked = events.filter((event) => event.name === "fundsLocked");
+    assert.equal(withdrawn.length, 1);
+    assert.equal(withdrawn[0].data.amountWithdrawn.toString(), "1000000");
+    assert.equal(locked.length, 1);
+    assert.equal(locked[0].data.mint.toString(), newCampaign.campaignToken.toString());
+    assert.equal(locked[0].data.value.toString(), "1000000");
+
+    console.log("✅ Expired rewards rolled into the new escrow");
+  });
+
+  it("Burns an escrow's leftovers at expiry once it commits to burning", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    const burnToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [burnEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), burnToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const burnWallets = deriveEscrowWallets(burnToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(burnToken, minter, supply);
+    await initializeEscrowWallets(burnToken, minter, rewardTokenMint, burnWallets);
+
+    await program.methods
+      .lockFundsV2(burnToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: burnEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: burnToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(burnWallets))
+      .signers([minter])
+      .rpc();
+
+    const setExpiryAction = (expiryAction: number) =>
+      program.methods
+        .setExpiryAction(expiryAction)
+        .accounts({
+          escrowLockAccount: burnEscrowLock,
+          minter: minter.publicKey,
+          token: burnToken,
+          rewardTokenMint,
+        })
+        .signers([minter])
+        .rpc();
+
+    await setExpiryAction(1);
+    const escrowData = await program.account.escrowLockAccount.fetch(burnEscrowLock);
+    assert.equal(escrowData.expiryAction, 1);
+
+    // A commitment to burn can't be taken back
+    try {
+      await setExpiryAction(0);
+      assert.fail("Should have thrown an error for switching back to returning leftovers");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidExpiryAction");
+    }
+
+    await waitForExpiry(expiryTime);
+
+    // Nor can the minter sweep the leftovers instead
+    try {
+      await program.methods
+        .withdrawExpiredRewards()
+        .accounts({
+          escrowLockAccount: burnEscrowLock,
+          minter: minter.publicKey,
+          token: burnToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(burnWallets))
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for withdrawing leftovers committed to burning");
+    } catch (error) {
+      assert.include(error.toString(), "LeftoversCommittedToBurn");
+    }
+
+    // Anyone can settle a burning escrow
+    const supplyBefore = (await getMint(provider.connection, rewardTokenMint)).supply;
+    await program.methods
+      .settleExpired()
+      .accounts({
+        escrowLockAccount: burnEscrowLock,
+        caller: user.publicKey,
+        token: burnToken,
+        rewardTokenMint,
+        minterRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(escrowWalletMetas(burnWallets))
+      .signers([user])
+      .rpc();
+    const supplyAfter = (await getMint(provider.connection, rewardTokenMint)).supply;
+
+    assert.equal((supplyBefore - supplyAfter).toString(), rewardValue.toString());
+    for (const wallet of burnWallets) {
+      assert.equal((await getAccount(provider.connection, wallet)).amount.toString(), "0");
+    }
+    const settled = await program.account.escrowLockAccount.fetch(burnEscrowLock);
+    assert.deepEqual(settled.status, { closed: {} });
+
+    console.log("✅ Leftover rewards burned at expiry");
+  });
+
+  it("Rotates the remainder across escrow wallets between redemptions", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const rotateToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [rotateEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), rotateToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const rotateWallets = deriveEscrowWallets(rotateToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(rotateToken, minter, supply);
+    await initializeEscrowWallets(rotateToken, minter, rewardTokenMint, rotateWallets);
+
+    await program.methods
+      .lockFundsV2(rotateToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: rotateEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: rotateToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(rotateWallets))
+      .signers([minter])
+      .rpc();
+
+    // Each burn of 6 pays 6 reward tokens: 1 per wallet plus a remainder of 1
+    for (let i = 0; i < 2; i++) {
+      await program.methods
+        .redeemRewards(new BN(6), null)
+        .accounts({
+          escrowLockAccount: rotateEscrowLock,
+          user: minter.publicKey,
+          token: rotateToken,
+          tokenMint: rotateToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(rotateWallets))
+        .signers([minter])
+        .rpc();
+    }
+
+    // The extra token came from wallet 1 the first time and wallet 2 the second
+    const expected = [199_997, 199_997, 199_998, 199_998, 199_998];
+    for (let i = 0; i < rotateWallets.length; i++) {
+      const walletAccount = await getAccount(provider.connection, rotateWallets[i]);
+      assert.equal(walletAccount.amount.toString(), expected[i].toString());
+    }
+
+    const escrowData = await program.account.escrowLockAccount.fetch(rotateEscrowLock);
+    assert.equal(escrowData.nextRemainderWallet, 2);
+
+    console.log("✅ Remainder rotated across escrow wallets");
+  });
+
+  it("Restricts redemption to authorized redeemers when the escrow requires a whitelist", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(1_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const listToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [listEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), listToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const listWallets = deriveEscrowWallets(listToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(listToken, minter, supply);
+    await initializeEscrowWallets(listToken, minter, rewardTokenMint, listWallets);
+
+    await program.methods
+      .lockFundsV2(listToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ requiresWhitelist: true }))
+      .accounts({
+        escrowLockAccount: listEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: listToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(listWallets))
+      .signers([minter])
+      .rpc();
+
+    const [redeemerEntry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redeemer"), listEscrowLock.toBuffer(), await escrowIdSeed(listEscrowLock), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    async function redeem(entry: PublicKey | null) {
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: listEscrowLock,
+          user: minter.publicKey,
+          token: listToken,
+          tokenMint: listToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: entry,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(listWallets))
+        .signers([minter])
+        .rpc();
+    }
+
+    // Not on the allowlist yet
+    try {
+      await redeem(null);
+      assert.fail("Should have thrown an error for an unlisted redeemer");
+    } catch (error) {
+      assert.include(error.toString(), "RedeemerNotAuthorized");
+    }
+
+    await program.methods
+      .authorizeRedeemer(minter.publicKey)
+      .accounts({
+        escrowLockAccount: listEscrowLock,
+        minter: minter.publicKey,
+        token: listToken,
+        redeemerEntry,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([minter])
+      .rpc();
+
+    await redeem(redeemerEntry);
+    let escrowData = await program.account.escrowLockAccount.fetch(listEscrowLock);
+    assert.equal(escrowData.totalBurned.toString(), burnAmount.toString());
+
+    await program.methods
+      .revokeRedeemer(minter.publicKey)
+      .accounts({
+        escrowLockAccount: listEscrowLock,
+        minter: minter.publicKey,
+        token: listToken,
+        redeemerEntry,
+      })
+      .signers([minter])
+      .rpc();
+
+    // Revoked: the entry is gone and redemption is blocked again
+    assert.isNull(await provider.connection.getAccountInfo(redeemerEntry));
+    try {
+      await redeem(null);
+      assert.fail("Should have thrown an error for a revoked redeemer");
+    } catch (error) {
+      assert.include(error.toString(), "RedeemerNotAuthorized");
+    }
+
+    escrowData = await program.account.escrowLockAccount.fetch(listEscrowLock);
+    assert.equal(escrowData.redemptionCount.toString(), "1");
+
+    console.log("✅ Whitelist enforced, including after revocation");
+  });
+
+  it("Pays a fixed share per token in snapshot mode regardless of redemption order", async () => {
+    const rewardValue = new BN(1_000_003);
+    const supply = new BN(3_000_000);
+    const burnAmount = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const snapToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [snapEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), snapToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const snapWallets = deriveEscrowWallets(snapToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(snapToken, minter, supply);
+    await initializeEscrowWallets(snapToken, minter, rewardTokenMint, snapWallets);
+
+    await program.methods
+      .lockFundsV2(snapToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ mode: 1 }))
+      .accounts({
+        escrowLockAccount: snapEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: snapToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(snapWallets))
+      .signers([minter])
+      .rpc();
+
+    // Every third of the supply earns floor(1_000_003 / 3), however late it redeems.
+    // The live formula would pay the last third 333_335 instead.
+    for (let i = 0; i < 3; i++) {
+      const before = await getAccount(provider.connection, minterRewardAccount);
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: snapEscrowLock,
+          user: minter.publicKey,
+          token: snapToken,
+          tokenMint: snapToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(snapWallets))
+        .signers([minter])
+        .rpc();
+      const after = await getAccount(provider.connection, minterRewardAccount);
+      assert.equal((after.amount - before.amount).toString(), "333334");
+    }
+
+    const escrowData = await program.account.escrowLockAccount.fetch(snapEscrowLock);
+    assert.equal(escrowData.mode, 1);
+    assert.equal(escrowData.snapshotSupply.toString(), supply.toString());
+    assert.equal(escrowData.totalTokenSupply.toString(), "0");
+    assert.equal(escrowData.remainingRewardValue.toString(), "1");
+
+    console.log("✅ Snapshot mode pays a fixed share per token");
+  });
+
+  it("Rejects an expired withdrawal that substitutes an escrow wallet", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+
+    const sweepToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [sweepEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), sweepToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const sweepWallets = deriveEscrowWallets(sweepToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(sweepToken, minter, supply);
+    await initializeEscrowWallets(sweepToken, minter, rewardTokenMint, sweepWallets);
+
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    await program.methods
+      .lockFundsV2(sweepToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: sweepEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: sweepToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(sweepWallets))
+      .signers([minter])
+      .rpc();
+
+    await waitForExpiry(expiryTime);
+
+    async function withdraw(wallets: PublicKey[]) {
+      await program.methods
+        .withdrawExpiredRewards()
+        .accounts({
+          escrowLockAccount: sweepEscrowLock,
+          minter: minter.publicKey,
+          token: sweepToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(wallets))
+        .signers([minter])
+        .rpc();
+    }
+
+    // A reward token account owned by the minter in place of wallet 1
+    try {
+      await withdraw([minterRewardAccount, ...sweepWallets.slice(1)]);
+      assert.fail("Should have thrown an error for a substituted wallet");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidEscrowWalletIndex");
+    }
+
+    // The real wallets are all swept back to the minter
+    const before = await getAccount(provider.connection, minterRewardAccount);
+    await withdraw(sweepWallets);
+    const after = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal((after.amount - before.amount).toString(), rewardValue.toString());
+
+    for (const wallet of sweepWallets) {
+      const walletAccount = await getAccount(provider.connection, wallet);
+      assert.equal(walletAccount.owner.toString(), sweepEscrowLock.toString());
+      assert.equal(walletAccount.mint.toString(), rewardTokenMint.toString());
+      assert.equal(walletAccount.amount.toString(), "0");
+    }
+
+    console.log("✅ Substituted escrow wallet rejected on withdrawal");
+  });
+
+  it("Emits EscrowExpired once when an expired escrow is marked", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+
+    const expiredToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [expiredEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), expiredToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const expiredWallets = deriveEscrowWallets(expiredToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(expiredToken, minter, supply);
+    await initializeEscrowWallets(expiredToken, minter, rewardTokenMint, expiredWallets);
+
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    await program.methods
+      .lockFundsV2(expiredToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: expiredEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: expiredToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(expiredWallets))
+      .signers([minter])
+      .rpc();
+
+    await waitForExpiry(expiryTime);
+
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+    // Returns the EscrowExpired events emitted by one mark_escrow_expired call
+    async function markExpired() {
+      const signature = await program.methods
+        .markEscrowExpired()
+        .accounts({
+          escrowLockAccount: expiredEscrowLock,
+          token: expiredToken,
+        })
+        .rpc({ commitment: "confirmed" });
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      return [...eventParser.parseLogs(tx.meta.logMessages)].filter(
+        (event) => event.name === "escrowExpired"
+      );
+    }
+
+    const firstEvents = await markExpired();
+    assert.equal(firstEvents.length, 1);
+    assert.equal(firstEvents[0].data.totalValue.toString(), rewardValue.toString());
+    assert.equal(firstEvents[0].data.remaining.toString(), rewardValue.toString());
+
+    // A second call is a no-op
+    const secondEvents = await markExpired();
+    assert.equal(secondEvents.length, 0);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(expiredEscrowLock);
+    assert.isTrue(escrowData.expiredEmitted);
+
+    console.log("✅ EscrowExpired emitted once");
+  });
+
+  it("Moves the same amounts through all five fixed wallets on lock and redeem", async () => {
+    // 1_000_003 leaves a remainder of 3 for wallets 1-3
+    const rewardValue = new BN(1_000_003);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const fixedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [fixedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), fixedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const fixedWallets = deriveEscrowWallets(fixedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(fixedToken, minter, supply);
+    await initializeEscrowWallets(fixedToken, minter, rewardTokenMint, fixedWallets);
+
+    await program.methods
+      .lockFunds(fixedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: fixedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: fixedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        escrowWallet1: fixedWallets[0],
+        escrowWallet2: fixedWallets[1],
+        escrowWallet3: fixedWallets[2],
+        escrowWallet4: fixedWallets[3],
+        escrowWallet5: fixedWallets[4],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([minter])
+      .rpc();
+
+    async function walletBalances() {
+      const balances: string[] = [];
+      for (const wallet of fixedWallets) {
+        balances.push((await getAccount(provider.connection, wallet)).amount.toString());
+      }
+      return balances;
+    }
+
+    assert.deepEqual(await walletBalances(), ["200001", "200001", "200001", "200000", "200000"]);
+
+    // Burning 10% pays 100_000, an even 20_000 from each wallet
+    await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: fixedEscrowLock,
+        user: minter.publicKey,
+        token: fixedToken,
+        tokenMint: fixedToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(fixedWallets))
+      .signers([minter])
+      .rpc();
+
+    assert.deepEqual(await walletBalances(), ["180001", "180001", "180001", "180000", "180000"]);
+
+    console.log("✅ All five fixed wallets handled identically");
+  });
+
+  it("Sweeps ten escrow wallets within a fixed compute budget", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    // Well under the 200k default, leaving room for the minter's other instructions
+    const computeBudget = 150_000;
+
+    const wideToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [wideEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), wideToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const wideWallets = deriveEscrowWallets(wideToken, minter.publicKey, 10);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(wideToken, minter, supply);
+    await initializeEscrowWallets(wideToken, minter, rewardTokenMint, wideWallets);
+
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    await program.methods
+      .lockFundsV2(wideToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ walletCount: 10 }))
+      .accounts({
+        escrowLockAccount: wideEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: wideToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(wideWallets))
+      .signers([minter])
+      .rpc();
+
+    await waitForExpiry(expiryTime);
+
+    const signature = await program.methods
+      .withdrawExpiredRewards()
+      .accounts({
+        escrowLockAccount: wideEscrowLock,
+        minter: minter.publicKey,
+        token: wideToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(wideWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    console.log("  Compute units for a 10-wallet sweep:", tx.meta.computeUnitsConsumed);
+    assert.isBelow(tx.meta.computeUnitsConsumed, computeBudget);
+
+    // One consolidated sweep log instead of a line per wallet
+    const sweepLogs = tx.meta.logMessages.filter((log) => log.includes("from wallet"));
+    assert.equal(sweepLogs.length, 0);
+
+    console.log("✅ Ten-wallet sweep stays within the compute budget");
+  });
+
+  it("Reports escrow status with the live wallet balance sum", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(250_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const statusToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [statusEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), statusToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const statusWallets = deriveEscrowWallets(statusToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(statusToken, minter, supply);
+    await initializeEscrowWallets(statusToken, minter, rewardTokenMint, statusWallets);
+
+    await program.methods
+      .lockFundsV2(statusToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: statusEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: statusToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(statusWallets))
+      .signers([minter])
+      .rpc();
+
+    await program.methods
+      .redeemRewards(burnAmount, null)
+      .accounts({
+        escrowLockAccount: statusEscrowLock,
+        user: minter.publicKey,
+        token: statusToken,
+        tokenMint: statusToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(statusWallets))
+      .signers([minter])
+      .rpc();
+
+    const status = await program.methods
+      .getEscrowStatus()
+      .accounts({
+        escrowLockAccount: statusEscrowLock,
+        token: statusToken,
+      })
+      .remainingAccounts(escrowWalletMetas(statusWallets))
+      .view();
+
+    assert.equal(status.totalRewardValue.toString(), rewardValue.toString());
+    assert.equal(status.remainingRewardValue.toString(), "750000");
+    assert.equal(status.walletBalanceTotal.toString(), "750000");
+    assert.deepEqual(status.status, { active: {} });
+    assert.equal(status.expiresAt.toString(), expiryTime.toString());
+    assert.isAbove(status.secondsUntilExpiry.toNumber(), 0);
+    assert.isAtMost(status.secondsUntilExpiry.toNumber(), 30 * 24 * 60 * 60);
+
+    console.log("✅ Escrow status reported in one call");
+  });
+
+  it("Warns when the main token and reward token decimals differ", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // 9-decimal main token against the 6-decimal reward token
+    const nineDecimalToken = await createMint(provider.connection, minter, minter.publicKey, null, 9);
+    const [decimalsEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), nineDecimalToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const decimalsWallets = deriveEscrowWallets(nineDecimalToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(nineDecimalToken, minter, supply);
+    await initializeEscrowWallets(nineDecimalToken, minter, rewardTokenMint, decimalsWallets);
+
+    const signature = await program.methods
+      .lockFundsV2(nineDecimalToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: decimalsEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: nineDecimalToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(decimalsWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const warnings = [...eventParser.parseLogs(tx.meta.logMessages)].filter(
+      (event) => event.name === "decimalsMismatch"
+    );
+    assert.equal(warnings.length, 1);
+    assert.equal(warnings[0].data.tokenDecimals, 9);
+    assert.equal(warnings[0].data.rewardTokenDecimals, 6);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(decimalsEscrowLock);
+    assert.equal(escrowData.tokenDecimals, 9);
+    assert.equal(escrowData.rewardTokenDecimals, 6);
+
+    console.log("✅ Decimals mismatch surfaced at lock time");
+  });
+
+  it("Caps how much one user can burn when max_per_user is set", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const maxPerUser = new BN(300_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const cappedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [cappedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), cappedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const cappedWallets = deriveEscrowWallets(cappedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(cappedToken, minter, supply);
+    await initializeEscrowWallets(cappedToken, minter, rewardTokenMint, cappedWallets);
+
+    await program.methods
+      .lockFundsV2(cappedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ maxPerUser }))
+      .accounts({
+        escrowLockAccount: cappedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: cappedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(cappedWallets))
+      .signers([minter])
+      .rpc();
+
+    const [userRedemption] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_redeem"), cappedEscrowLock.toBuffer(), await escrowIdSeed(cappedEscrowLock), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const redeem = async (burnAmount: BN, record: PublicKey | null) => {
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: cappedEscrowLock,
+          user: minter.publicKey,
+          token: cappedToken,
+          tokenMint: cappedToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: record,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cappedWallets))
+        .signers([minter])
+        .rpc();
+    };
+
+    // A capped escrow can't be redeemed without the running total
+    try {
+      await redeem(new BN(100_000), null);
+      assert.fail("Should have thrown an error for a missing user redemption account");
+    } catch (error) {
+      assert.include(error.toString(), "UserRedemptionRequired");
+    }
+
+    // The first redemption creates the record
+    await redeem(new BN(200_000), userRedemption);
+    let record = await program.account.userRedemption.fetch(userRedemption);
+    assert.equal(record.escrow.toString(), cappedEscrowLock.toString());
+    assert.equal(record.user.toString(), minter.publicKey.toString());
+    assert.equal(record.totalBurned.toString(), "200000");
+    assert.equal(record.totalClaimed.toString(), "200000");
+
+    // 200k + 150k would exceed the 300k cap
+    try {
+      await redeem(new BN(150_000), userRedemption);
+      assert.fail("Should have thrown an error for exceeding the per-user cap");
+    } catch (error) {
+      assert.include(error.toString(), "UserCapExceeded");
+    }
+
+    // Exactly reaching the cap is allowed
+    await redeem(new BN(100_000), userRedemption);
+    record = await program.account.userRedemption.fetch(userRedemption);
+    assert.equal(record.totalBurned.toString(), maxPerUser.toString());
+
+    console.log("✅ Per-user cap enforced across redemptions");
+  });
+
+  it("Throttles a user's redemptions with a cooldown", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const cooldownToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [cooldownEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), cooldownToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const cooldownWallets = deriveEscrowWallets(cooldownToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(cooldownToken, minter, supply);
+    await initializeEscrowWallets(cooldownToken, minter, rewardTokenMint, cooldownWallets);
+
+    await program.methods
+      .lockFundsV2(cooldownToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: cooldownEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: cooldownToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(cooldownWallets))
+      .signers([minter])
+      .rpc();
+
+    const [userRedemption] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_redeem"), cooldownEscrowLock.toBuffer(), await escrowIdSeed(cooldownEscrowLock), minter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const setCooldown = (cooldownSecs: BN) =>
+      program.methods
+        .setRedemptionCooldown(cooldownSecs)
+        .accounts({
+          escrowLockAccount: cooldownEscrowLock,
+          minter: minter.publicKey,
+          token: cooldownToken,
+        })
+        .signers([minter])
+        .rpc();
+
+    const redeem = async (burnAmount: BN, record: PublicKey | null) => {
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: cooldownEscrowLock,
+          user: minter.publicKey,
+          token: cooldownToken,
+          tokenMint: cooldownToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: record,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cooldownWallets))
+        .signers([minter])
+        .rpc();
+    };
+
+    try {
+      await setCooldown(new BN(-1));
+      assert.fail("Should have thrown an error for a negative cooldown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidCooldown");
+    }
+
+    await setCooldown(new BN(3600));
+    let escrowData = await program.account.escrowLockAccount.fetch(cooldownEscrowLock);
+    assert.equal(escrowData.cooldownSecs.toString(), "3600");
+
+    // With a cooldown set, the user's last redemption time must be passed
+    try {
+      await redeem(new BN(100_000), null);
+      assert.fail("Should have thrown an error for a missing user redemption account");
+    } catch (error) {
+      assert.include(error.toString(), "UserRedemptionRequired");
+    }
+
+    await redeem(new BN(100_000), userRedemption);
+    const record = await program.account.userRedemption.fetch(userRedemption);
+    assert.isAbove(record.lastRedeemedAt.toNumber(), 0);
+
+    // A second redemption within the hour is throttled
+    try {
+      await redeem(new BN(100_000), userRedemption);
+      assert.fail("Should have thrown an error for redeeming during the cooldown");
+    } catch (error) {
+      assert.include(error.toString(), "CooldownActive");
+    }
+
+    // Clearing the cooldown lets the user redeem again straight away
+    await setCooldown(new BN(0));
+    await redeem(new BN(100_000), userRedemption);
+    escrowData = await program.account.escrowLockAccount.fetch(cooldownEscrowLock);
+    assert.equal(escrowData.redemptionCount.toString(), "2");
+
+    console.log("✅ Redemption cooldown enforced per user");
+  });
+
+  it("Rounds redemption rewards up once the minter picks ceil rounding", async () => {
+    // 3 tokens sharing 10 reward tokens: burning 1 is worth 3.33
+    const rewardValue = new BN(10);
+    const supply = new BN(3);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const roundedToken = await createMint(provider.connection, minter, minter.publicKey, null, 0);
+    const [roundedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), roundedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const roundedWallets = deriveEscrowWallets(roundedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(roundedToken, minter, supply);
+    await initializeEscrowWallets(roundedToken, minter, rewardTokenMint, roundedWallets);
+
+    await program.methods
+      .lockFundsV2(roundedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: roundedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: roundedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(roundedWallets))
+      .signers([minter])
+      .rpc();
+
+    const setRoundingMode = (roundingMode: number) =>
+      program.methods
+        .setRoundingMode(roundingMode)
+        .accounts({
+          escrowLockAccount: roundedEscrowLock,
+          minter: minter.publicKey,
+          token: roundedToken,
+        })
+        .signers([minter])
+        .rpc();
+
+    let escrowData = await program.account.escrowLockAccount.fetch(roundedEscrowLock);
+    assert.equal(escrowData.roundingMode, 0, "Floor rounding is the default");
+
+    try {
+      await setRoundingMode(3);
+      assert.fail("Should have thrown an error for an unknown rounding mode");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidRoundingMode");
+    }
+
+    await setRoundingMode(1);
+
+    const rewardBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    await program.methods
+      .redeemRewards(new BN(1), null)
+      .accounts({
+        escrowLockAccount: roundedEscrowLock,
+        user: minter.publicKey,
+        token: roundedToken,
+        tokenMint: roundedToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(roundedWallets))
+      .signers([minter])
+      .rpc();
+    const rewardAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+
+    // Ceil pays 4 where floor would have paid 3
+    assert.equal((rewardAfter - rewardBefore).toString(), "4");
+    escrowData = await program.account.escrowLockAccount.fetch(roundedEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "6");
+
+    // The rule can't change once holders have started redeeming
+    try {
+      await setRoundingMode(0);
+      assert.fail("Should have thrown an error for changing the rounding mode after a redemption");
+    } catch (error) {
+      assert.include(error.toString(), "RedemptionsInProgress");
+    }
+
+    console.log("✅ Ceil rounding applied to the redemption payout");
+  });
+
+  it("Redeems from two escrows in one batch", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    // Locks one fresh escrow for the minter and returns its accounts
+    async function lockBatchEscrow() {
+      const batchToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+      const [batchEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), batchToken.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const batchWallets = deriveEscrowWallets(batchToken, minter.publicKey, 5);
+
+      await mintTo(
+        provider.connection,
+        minter,
+        rewardTokenMint,
+        minterRewardAccount,
+        minter,
+        rewardValue.toNumber()
+      );
+      const minterMainAccount = await mintMainSupply(batchToken, minter, supply);
+      await initializeEscrowWallets(batchToken, minter, rewardTokenMint, batchWallets);
+
+      await program.methods
+        .lockFundsV2(batchToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: batchEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: batchToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(batchWallets))
+        .signers([minter])
+        .rpc();
+
+      return { batchToken, batchEscrowLock, batchWallets, minterMainAccount };
+    }
+
+    // The escrow, main mint, user token account, user reward account, treasury
+    // placeholder, then the escrow wallets
+    function batchMetas(escrow: Awaited<ReturnType<typeof lockBatchEscrow>>) {
+      return [
+        { pubkey: escrow.batchEscrowLock, isWritable: true, isSigner: false },
+        { pubkey: escrow.batchToken, isWritable: true, isSigner: false },
+        { pubkey: escrow.minterMainAccount, isWritable: true, isSigner: false },
+        { pubkey: minterRewardAccount, isWritable: true, isSigner: false },
+        { pubkey: program.programId, isWritable: false, isSigner: false },
+        ...escrowWalletMetas(escrow.batchWallets),
+      ];
+    }
+
+    const first = await lockBatchEscrow();
+    const second = await lockBatchEscrow();
+
+    try {
+      await program.methods
+        .redeemRewardsBatch([])
+        .accounts({ user: minter.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for an empty batch");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidBatch");
+    }
+
+    const rewardBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    await program.methods
+      .redeemRewardsBatch([
+        { burnAmount: new BN(100_000), minRewardOut: null },
+        { burnAmount: new BN(250_000), minRewardOut: null },
+      ])
+      .accounts({ user: minter.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts([...batchMetas(first), ...batchMetas(second)])
+      .signers([minter])
+      .rpc();
+    const rewardAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+
+    assert.equal((rewardAfter - rewardBefore).toString(), "350000");
+    const firstData = await program.account.escrowLockAccount.fetch(first.batchEscrowLock);
+    const secondData = await program.account.escrowLockAccount.fetch(second.batchEscrowLock);
+    assert.equal(firstData.remainingRewardValue.toString(), "900000");
+    assert.equal(secondData.remainingRewardValue.toString(), "750000");
+    assert.equal(firstData.redemptionCount.toString(), "1");
+    assert.equal(secondData.redemptionCount.toString(), "1");
+
+    console.log("✅ Two escrows redeemed in one transaction");
+  });
+
+  it("Pauses redemptions from a single escrow", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const pausedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [pausedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), pausedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const pausedWallets = deriveEscrowWallets(pausedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(pausedToken, minter, supply);
+    await initializeEscrowWallets(pausedToken, minter, rewardTokenMint, pausedWallets);
+
+    await program.methods
+      .lockFundsV2(pausedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: pausedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: pausedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(pausedWallets))
+      .signers([minter])
+      .rpc();
+
+    const setEscrowPaused = (paused: boolean) =>
+      program.methods
+        .setEscrowPaused(paused)
+        .accounts({
+          escrowLockAccount: pausedEscrowLock,
+          minter: minter.publicKey,
+          token: pausedToken,
+        })
+        .signers([minter])
+        .rpc();
+
+    const redeem = () =>
+      program.methods
+        .redeemRewards(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: pausedEscrowLock,
+          user: minter.publicKey,
+          token: pausedToken,
+          tokenMint: pausedToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(pausedWallets))
+        .signers([minter])
+        .rpc();
+
+    // Only the minter can pause the escrow
+    try {
+      await program.methods
+        .setEscrowPaused(true)
+        .accounts({
+          escrowLockAccount: pausedEscrowLock,
+          minter: user.publicKey,
+          token: pausedToken,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail("Should have thrown an error for a non-minter pausing the escrow");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    await setEscrowPaused(true);
+    try {
+      await redeem();
+      assert.fail("Should have thrown an error for redeeming from a paused escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowPaused");
+    }
+
+    // Other escrows keep redeeming while this one is paused
+    const mainEscrow = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.isFalse(mainEscrow.paused);
+
+    await setEscrowPaused(false);
+    await redeem();
+    const escrowData = await program.account.escrowLockAccount.fetch(pausedEscrowLock);
+    assert.equal(escrowData.redemptionCount.toString(), "1");
+
+    console.log("✅ Escrow-level pause blocks only this escrow's redemptions");
+  });
+
+  it("Logs one summary line per redemption outside verbose builds", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const quietToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [quietEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), quietToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const quietWallets = deriveEscrowWallets(quietToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(quietToken, minter, supply);
+    await initializeEscrowWallets(quietToken, minter, rewardTokenMint, quietWallets);
+
+    await program.methods
+      .lockFundsV2(quietToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: quietEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: quietToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(quietWallets))
+      .signers([minter])
+      .rpc();
+
+    const signature = await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: quietEscrowLock,
+        user: minter.publicKey,
+        token: quietToken,
+        tokenMint: quietToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(quietWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    console.log("  Compute units for a 5-wallet redemption:", tx.meta.computeUnitsConsumed);
+
+    // No per-wallet or per-step lines, just the summary
+    const programLogs = tx.meta.logMessages.filter((log) => log.startsWith("Program log:"));
+    assert.equal(programLogs.filter((log) => log.includes("from wallet")).length, 0);
+    assert.equal(programLogs.filter((log) => log.includes("Burning")).length, 0);
+    assert.equal(programLogs.filter((log) => log.includes("Redemption complete")).length, 1);
+
+    console.log("✅ Redemption logs a single summary line");
+  });
+
+  it("Keeps an on-chain log of recent redemptions", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const loggedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [loggedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), loggedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const loggedWallets = deriveEscrowWallets(loggedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(loggedToken, minter, supply);
+    await initializeEscrowWallets(loggedToken, minter, rewardTokenMint, loggedWallets);
+
+    await program.methods
+      .lockFundsV2(loggedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: loggedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: loggedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(loggedWallets))
+      .signers([minter])
+      .rpc();
+
+    const [redemptionLog] = PublicKey.findProgramAddressSync(
+      [Buffer.from("redemption_log"), loggedEscrowLock.toBuffer(), await escrowIdSeed(loggedEscrowLock)],
+      program.programId
+    );
+
+    await program.methods
+      .initializeRedemptionLog()
+      .accounts({
+        escrowLockAccount: loggedEscrowLock,
+        minter: minter.publicKey,
+        token: loggedToken,
+        redemptionLog,
+      })
+      .signers([minter])
+      .rpc();
+
+    const redeem = async (burnAmount: BN, log: PublicKey | null) => {
+      await program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: loggedEscrowLock,
+          user: minter.publicKey,
+          token: loggedToken,
+          tokenMint: loggedToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: log,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(loggedWallets))
+        .signers([minter])
+        .rpc();
+    };
+
+    // Once the log exists, a redemption can't skip it
+    try {
+      await redeem(new BN(100_000), null);
+      assert.fail("Should have thrown an error for a missing redemption log");
+    } catch (error) {
+      assert.include(error.toString(), "RedemptionLogRequired");
+    }
+
+    // Nor can it go through an instruction that doesn't write the log
+    try {
+      await program.methods
+        .redeemRewardsTo(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: loggedEscrowLock,
+          user: minter.publicKey,
+          token: loggedToken,
+          tokenMint: loggedToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(loggedWallets))
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for bypassing the redemption log");
+    } catch (error) {
+      assert.include(error.toString(), "RedemptionLogRequired");
+    }
+
+    await redeem(new BN(100_000), redemptionLog);
+    await redeem(new BN(50_000), redemptionLog);
+
+    const log = await program.account.redemptionLog.fetch(redemptionLog);
+    assert.equal(log.escrow.toString(), loggedEscrowLock.toString());
+    assert.equal(log.totalEntries.toString(), "2");
+    assert.equal(log.entries.length, 64);
+    assert.equal(log.entries[0].user.toString(), minter.publicKey.toString());
+    assert.equal(log.entries[0].burnAmount.toString(), "100000");
+    assert.equal(log.entries[0].rewardAmount.toString(), "100000");
+    assert.equal(log.entries[1].burnAmount.toString(), "50000");
+    assert.isAtLeast(log.entries[1].timestamp.toNumber(), log.entries[0].timestamp.toNumber());
+    assert.equal(log.entries[2].burnAmount.toString(), "0");
+
+    console.log("✅ Redemptions recorded in the on-chain log");
+  });
+
+  it("Creates the reward token account for a first-time redeemer", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(100_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const newcomer = Keypair.generate();
+    const airdropNewcomer = await provider.connection.requestAirdrop(
+      newcomer.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropNewcomer);
+
+    const freshToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [freshEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), freshToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const freshWallets = deriveEscrowWallets(freshToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    // The newcomer holds main tokens but has never held the reward token
+    const newcomerMainAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      freshToken,
+      newcomer.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      freshToken,
+      newcomerMainAccount.address,
+      minter,
+      supply.toNumber()
+    );
+    await initializeEscrowWallets(freshToken, minter, rewardTokenMint, freshWallets);
+
+    await program.methods
+      .lockFundsV2(freshToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: freshEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: freshToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(freshWallets))
+      .signers([minter])
+      .rpc();
+
+    const newcomerRewardAccount = getAssociatedTokenAddressSync(rewardTokenMint, newcomer.publicKey);
+    assert.isNull(await provider.connection.getAccountInfo(newcomerRewardAccount));
+
+    await program.methods
+      .redeemRewards(burnAmount, null)
+      .accounts({
+        escrowLockAccount: freshEscrowLock,
+        user: newcomer.publicKey,
+        token: freshToken,
+        tokenMint: freshToken,
+        userTokenAccount: newcomerMainAccount.address,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: newcomerRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(freshWallets))
+      .signers([newcomer])
+      .rpc();
+
+    const rewardAccount = await getAccount(provider.connection, newcomerRewardAccount);
+    assert.equal(rewardAccount.owner.toString(), newcomer.publicKey.toString());
+    assert.equal(rewardAccount.amount.toString(), "100000");
+
+    console.log("✅ Reward account created during the first redemption");
+  });
+
+  it("Lets the admin sweep an escrow back to the minter only while paused", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const rescueToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [rescueEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), rescueToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const rescueWallets = deriveEscrowWallets(rescueToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(rescueToken, minter, supply);
+    await initializeEscrowWallets(rescueToken, minter, rewardTokenMint, rescueWallets);
+
+    await program.methods
+      .lockFundsV2(rescueToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: rescueEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: rescueToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(rescueWallets))
+      .signers([minter])
+      .rpc();
+
+    const emergencyWithdraw = (admin: PublicKey, destination: PublicKey) =>
+      program.methods
+        .emergencyWithdraw()
+        .accounts({
+          escrowLockAccount: rescueEscrowLock,
+          admin,
+          token: rescueToken,
+          rewardTokenMint,
+          minterRewardAccount: destination,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(escrowWalletMetas(rescueWallets));
+
+    // Not available during normal operation
+    try {
+      await emergencyWithdraw(provider.wallet.publicKey, minterRewardAccount).rpc();
+      assert.fail("Should have thrown an error while the program is running");
+    } catch (error) {
+      assert.include(error.toString(), "ProgramNotPaused");
+    }
+
+    await program.methods
+      .setPaused(true)
+      .accounts({ admin: provider.wallet.publicKey })
+      .rpc();
+
+    try {
+      // Only the admin can break the glass
+      try {
+        await emergencyWithdraw(minter.publicKey, minterRewardAccount).signers([minter]).rpc();
+        assert.fail("Should have thrown an error for a non-admin");
+      } catch (error) {
+        assert.include(error.toString(), "UnauthorizedAdmin");
+      }
+
+      // Funds can only go back to the account the lock was funded from
+      try {
+        await emergencyWithdraw(provider.wallet.publicKey, userRewardAccount).rpc();
+        assert.fail("Should have thrown an error for a different destination");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidRefundAccount");
+      }
+
+      const balanceBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+      await emergencyWithdraw(provider.wallet.publicKey, minterRewardAccount).rpc();
+      const balanceAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+
+      assert.equal((balanceAfter - balanceBefore).toString(), rewardValue.toString());
+      const escrowData = await program.account.escrowLockAccount.fetch(rescueEscrowLock);
+      assert.deepEqual(escrowData.status, { closed: {} });
+      assert.equal(escrowData.remainingRewardValue.toString(), "0");
+    } finally {
+      await program.methods
+        .setPaused(false)
+        .accounts({ admin: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    console.log("✅ Emergency withdrawal restricted to the paused admin path");
+  });
+
+  it("Claws back delegated rewards with admin and minter signatures", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const clawToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [clawEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), clawToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const clawWallets = deriveEscrowWallets(clawToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(clawToken, minter, supply);
+    await initializeEscrowWallets(clawToken, minter, rewardTokenMint, clawWallets);
+
+    await program.methods
+      .lockFundsV2(clawToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: clawEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: clawToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(clawWallets))
+      .signers([minter])
+      .rpc();
+
+    // A holder redeems into a reward account that later turns out to be fraudulent
+    const redeemer = Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(redeemer.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+    const redeemerMainAccount = await getOrCreateAssociatedTokenAccount(provider.connection, minter, clawToken, redeemer.publicKey);
+    const redeemerRewardAccount = getAssociatedTokenAddressSync(rewardTokenMint, redeemer.publicKey);
+    await mintTo(provider.connection, minter, clawToken, redeemerMainAccount.address, minter, 200_000);
+
+    await program.methods
+      .redeemRewards(new BN(200_000), null)
+      .accounts({
+        escrowLockAccount: clawEscrowLock,
+        user: redeemer.publicKey,
+        token: clawToken,
+        tokenMint: clawToken,
+        userTokenAccount: redeemerMainAccount.address,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: redeemerRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(clawWallets))
+      .signers([redeemer])
+      .rpc();
+
+    const remainingBefore = (await program.account.escrowLockAccount.fetch(clawEscrowLock)).remainingRewardValue;
+
+    const clawback = (amount: BN) =>
+      program.methods
+        .clawback(amount)
+        .accounts({
+          escrowLockAccount: clawEscrowLock,
+          admin: provider.wallet.publicKey,
+          minter: minter.publicKey,
+          token: clawToken,
+          redeemerRewardAccount,
+          escrowWallet: clawWallets[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([minter])
+        .rpc({ commitment: "confirmed" });
+
+    // Without the redeemer's delegation nothing can be pulled
+    try {
+      await clawback(new BN(50_000));
+      assert.fail("Should have thrown an error without a delegation");
+    } catch (error) {
+      assert.include(error.toString(), "ClawbackNotAuthorized");
+    }
+
+    await approve(provider.connection, minter, redeemerRewardAccount, clawEscrowLock, redeemer, 50_000);
+
+    // The minter alone can't authorize it
+    try {
+      await program.methods
+        .clawback(new BN(50_000))
+        .accounts({
+          escrowLockAccount: clawEscrowLock,
+          admin: minter.publicKey,
+          minter: minter.publicKey,
+          token: clawToken,
+          redeemerRewardAccount,
+          escrowWallet: clawWallets[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error without the admin");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedAdmin");
+    }
+
+    // Nor can more than the delegated amount be taken
+    try {
+      await clawback(new BN(50_001));
+      assert.fail("Should have thrown an error above the delegated amount");
+    } catch (error) {
+      assert.include(error.toString(), "ClawbackNotAuthorized");
+    }
+
+    const signature = await clawback(new BN(50_000));
+
+    const redeemerAccount = await getAccount(provider.connection, redeemerRewardAccount);
+    assert.equal(redeemerAccount.amount.toString(), "150000");
+    const escrowData = await program.account.escrowLockAccount.fetch(clawEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), remainingBefore.addn(50_000).toString());
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const clawbacks = [...eventParser.parseLogs(tx.meta.logMessages)].filter(
+      (event) => event.name === "clawback"
+    );
+    assert.equal(clawbacks.length, 1);
+    assert.equal(clawbacks[0].data.redeemer.toString(), redeemer.publicKey.toString());
+    assert.equal(clawbacks[0].data.amount.toString(), "50000");
+
+    console.log("✅ Clawback requires both signatures and the redeemer's delegation");
+  });
+
+  it("Stores the lock and wallet bumps that match the PDA derivation", async () => {
+    const escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+
+    const [expectedLock, lockBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), mainTokenMint.toBuffer(), escrowData.creator.toBuffer()],
+      program.programId
+    );
+    assert.equal(expectedLock.toString(), escrowLockAccount.toString());
+    assert.equal(escrowData.lockBump, lockBump);
+
+    for (let i = 0; i < escrowData.walletCount; i++) {
+      const [expectedWallet, walletBump] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_wallet"), mainTokenMint.toBuffer(), escrowData.creator.toBuffer(), Buffer.from([i + 1])],
+        program.programId
+      );
+      assert.equal(escrowData.escrowWallets[i].toString(), expectedWallet.toString());
+      assert.equal(escrowData.walletBumps[i], walletBump);
+
+      // The stored bump re-derives the same address without a search
+      const rederived = PublicKey.createProgramAddressSync(
+        [
+          Buffer.from("escrow_wallet"),
+          mainTokenMint.toBuffer(),
+          escrowData.creator.toBuffer(),
+          Buffer.from([i + 1]),
+          Buffer.from([escrowData.walletBumps[i]]),
+        ],
+        program.programId
+      );
+      assert.equal(rederived.toString(), expectedWallet.toString());
+    }
+
+    // Unused slots stay zeroed
+    for (let i = escrowData.walletCount; i < escrowData.walletBumps.length; i++) {
+      assert.equal(escrowData.walletBumps[i], 0);
+    }
+
+    console.log("✅ Stored bumps match the derived PDAs");
+  });
+
+  it("Rejects a second lock for the same token and minter until the escrow is closed", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const relockToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [relockEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), relockToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const relockWallets = deriveEscrowWallets(relockToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.muln(2).toNumber()
+    );
+    await mintMainSupply(relockToken, minter, supply);
+    await initializeEscrowWallets(relockToken, minter, rewardTokenMint, relockWallets);
+
+    const lock = () =>
+      program.methods
+        .lockFundsV2(relockToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: relockEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: relockToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(relockWallets))
+        .signers([minter])
+        .rpc();
+
+    await lock();
+
+    // The escrow is still open, so the same PDA can't be locked again
+    try {
+      await lock();
+      assert.fail("Should have thrown an error for an existing escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowAlreadyExists");
+    }
+
+    // Once the escrow is finished and closed, the PDA is free again
+    await program.methods
+      .cancelLock()
+      .accounts({
+        escrowLockAccount: relockEscrowLock,
+        minter: minter.publicKey,
+        token: relockToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(relockWallets))
+      .signers([minter])
+      .rpc();
+
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        escrowLockAccount: relockEscrowLock,
+        minter: minter.publicKey,
+        token: relockToken,
+        rentRecipient: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(relockWallets))
+      .signers([minter])
+      .rpc();
+
+    // close_escrow also closes the wallets, so they are recreated before re-locking
+    await initializeEscrowWallets(relockToken, minter, rewardTokenMint, relockWallets);
+    await lock();
+
+    const escrowData = await program.account.escrowLockAccount.fetch(relockEscrowLock);
+    assert.isTrue(escrowData.isActive);
+    assert.equal(escrowData.remainingRewardValue.toString(), rewardValue.toString());
+
+    console.log("✅ Re-lock rejected while open, allowed after close");
+  });
+
+  it("Pays an extra reward track alongside the primary reward in one redemption", async () => {
+    const rewardValue = new BN(1_000_000);
+    const trackValue = new BN(500_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(100_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const trackToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [trackEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), trackToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const trackWallets = deriveEscrowWallets(trackToken, minter.publicKey, 5);
+    const [rewardTrack] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_track"), trackEscrowLock.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+    const [trackVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("track_vault"), trackEscrowLock.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    // Governance token paid as the second reward
+    const governanceMint = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const minterGovernanceAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      governanceMint,
+      minter.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      governanceMint,
+      minterGovernanceAccount.address,
+      minter,
+      trackValue.toNumber()
+    );
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(trackToken, minter, supply);
+    await initializeEscrowWallets(trackToken, minter, rewardTokenMint, trackWallets);
+
+    await program.methods
+      .lockFundsV2(trackToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: trackEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: trackToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(trackWallets))
+      .signers([minter])
+      .rpc();
+
+    await program.methods
+      .addRewardTrack(0, trackValue)
+      .accounts({
+        escrowLockAccount: trackEscrowLock,
+        minter: minter.publicKey,
+        token: trackToken,
+        primaryRewardMint: rewardTokenMint,
+        trackRewardMint: governanceMint,
+        rewardTrack,
+        trackVault,
+        minterTrackAccount: minterGovernanceAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([minter])
+      .rpc();
+
+    const redeem = (trackMetas: { pubkey: PublicKey; isWritable: boolean; isSigner: boolean }[]) =>
+      program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: trackEscrowLock,
+          user: minter.publicKey,
+          token: trackToken,
+          tokenMint: trackToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([...escrowWalletMetas(trackWallets), ...trackMetas])
+        .signers([minter])
+        .rpc();
+
+    // Leaving the track out would forfeit its share, so it is rejected
+    try {
+      await redeem([]);
+      assert.fail("Should have thrown an error for missing reward track accounts");
+    } catch (error) {
+      assert.include(error.toString(), "RewardTracksRequired");
+    }
+
+    const rewardBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    const governanceBefore = (await getAccount(provider.connection, minterGovernanceAccount.address)).amount;
+
+    await redeem(escrowWalletMetas([rewardTrack, trackVault, minterGovernanceAccount.address]));
+
+    const rewardAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    const governanceAfter = (await getAccount(provider.connection, minterGovernanceAccount.address)).amount;
+
+    // 10% of the supply burned: 10% of each pool
+    assert.equal((rewardAfter - rewardBefore).toString(), "100000");
+    assert.equal((governanceAfter - governanceBefore).toString(), "50000");
+
+    const track = await program.account.rewardTrack.fetch(rewardTrack);
+    assert.equal(track.remainingRewardValue.toString(), "450000");
+    assert.equal(track.rewardToken.toString(), governanceMint.toString());
+
+    console.log("✅ Both reward tokens paid in one redemption");
+  });
+
+  it("Withdraws a reward track after the escrow is cancelled and then closes the escrow", async () => {
+    const rewardValue = new BN(1_000_000);
+    const trackValue = new BN(500_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const trackToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [trackEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), trackToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const trackWallets = deriveEscrowWallets(trackToken, minter.publicKey, 5);
+    const [rewardTrack] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_track"), trackEscrowLock.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+    const [trackVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("track_vault"), trackEscrowLock.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    const governanceMint = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const minterGovernanceAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      governanceMint,
+      minter.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      governanceMint,
+      minterGovernanceAccount.address,
+      minter,
+      trackValue.toNumber()
+    );
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(trackToken, minter, supply);
+    await initializeEscrowWallets(trackToken, minter, rewardTokenMint, trackWallets);
+
+    await program.methods
+      .lockFundsV2(trackToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: trackEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: trackToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(trackWallets))
+      .signers([minter])
+      .rpc();
+
+    await program.methods
+      .addRewardTrack(0, trackValue)
+      .accounts({
+        escrowLockAccount: trackEscrowLock,
+        minter: minter.publicKey,
+        token: trackToken,
+        primaryRewardMint: rewardTokenMint,
+        trackRewardMint: governanceMint,
+        rewardTrack,
+        trackVault,
+        minterTrackAccount: minterGovernanceAccount.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([minter])
+      .rpc();
+
+    const withdrawTrack = () =>
+      program.methods
+        .withdrawRewardTrack(0)
+        .accounts({
+          escrowLockAccount: trackEscrowLock,
+          minter: minter.publicKey,
+          token: trackToken,
+          rewardTrack,
+          trackVault,
+          minterTrackAccount: minterGovernanceAccount.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([minter])
+        .rpc();
+
+    // Still redeemable, so the track stays put
+    try {
+      await withdrawTrack();
+      assert.fail("Should have thrown an error for a live escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotExpired");
+    }
+
+    await program.methods
+      .cancelLock()
+      .accounts({
+        escrowLockAccount: trackEscrowLock,
+        minter: minter.publicKey,
+        token: trackToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(trackWallets))
+      .signers([minter])
+      .rpc();
+
+    const closeEscrow = () =>
+      program.methods
+        .closeEscrow()
+        .accounts({
+          escrowLockAccount: trackEscrowLock,
+          minter: minter.publicKey,
+          token: trackToken,
+          rentRecipient: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(trackWallets))
+        .signers([minter])
+        .rpc();
+
+    // The open track keeps the escrow from being closed
+    try {
+      await closeEscrow();
+      assert.fail("Should have thrown an error for an open reward track");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotEmpty");
+    }
+
+    const governanceBefore = (await getAccount(provider.connection, minterGovernanceAccount.address)).amount;
+    await withdrawTrack();
+    const governanceAfter = (await getAccount(provider.connection, minterGovernanceAccount.address)).amount;
+
+    assert.equal((governanceAfter - governanceBefore).toString(), trackValue.toString());
+    assert.isNull(await provider.connection.getAccountInfo(rewardTrack));
+    assert.isNull(await provider.connection.getAccountInfo(trackVault));
+
+    await closeEscrow();
+    assert.isNull(await provider.connection.getAccountInfo(trackEscrowLock));
+
+    console.log("✅ Reward track withdrawn and escrow closed");
+  });
+
+  it("Withdraws expired rewards in parts and deactivates once nothing is left", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+
+    const partialToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [partialEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), partialToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const partialWallets = deriveEscrowWallets(partialToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(partialToken, minter, supply);
+    await initializeEscrowWallets(partialToken, minter, rewardTokenMint, partialWallets);
+
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    await program.methods
+      .lockFundsV2(partialToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: partialEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: partialToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(partialWallets))
+      .signers([minter])
+      .rpc();
+
+    await waitForExpiry(expiryTime);
+
+    const partialWithdraw = (amount: BN, signer: Keypair = minter) =>
+      program.methods
+        .partialWithdrawExpired(amount)
+        .accounts({
+          escrowLockAccount: partialEscrowLock,
+          minter: signer.publicKey,
+          token: partialToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(partialWallets))
+        .signers([signer])
+        .rpc();
+
+    // Only the minter can reclaim
+    try {
+      await partialWithdraw(new BN(100_000), user);
+      assert.fail("Should have thrown an error for a non-minter");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    // More than what is left is rejected
+    try {
+      await partialWithdraw(rewardValue.addn(1));
+      assert.fail("Should have thrown an error for an amount over the remaining rewards");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+    }
+
+    const before = await getAccount(provider.connection, minterRewardAccount);
+    await partialWithdraw(new BN(300_000));
+    const after = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal((after.amount - before.amount).toString(), "300000");
+
+    let escrowData = await program.account.escrowLockAccount.fetch(partialEscrowLock);
+    assert.deepEqual(escrowData.status, { expired: {} });
+    assert.equal(escrowData.remainingRewardValue.toString(), "700000");
+
+    // Each wallet gave an even share
+    for (const wallet of partialWallets) {
+      const walletAccount = await getAccount(provider.connection, wallet);
+      assert.equal(walletAccount.amount.toString(), "140000");
+    }
+
+    // Taking the rest deactivates the escrow
+    await partialWithdraw(new BN(700_000));
+    escrowData = await program.account.escrowLockAccount.fetch(partialEscrowLock);
+    assert.deepEqual(escrowData.status, { closed: {} });
+    assert.equal(escrowData.remainingRewardValue.toString(), "0");
+
+    console.log("✅ Expired rewards reclaimed in parts");
+  });
+
+  it("Rejects a lock the minter can't fully fund before any transfer", async () => {
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const underfundedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [underfundedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), underfundedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const underfundedWallets = deriveEscrowWallets(underfundedToken, minter.publicKey, 5);
+
+    await mintMainSupply(underfundedToken, minter, supply);
+    await initializeEscrowWallets(underfundedToken, minter, rewardTokenMint, underfundedWallets);
+
+    // One token more than the minter holds
+    const balance = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    const rewardValue = new BN((balance + BigInt(1)).toString());
+
+    try {
+      await program.methods
+        .lockFundsV2(underfundedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: underfundedEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: underfundedToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(underfundedWallets))
+        .signers([minter])
+        .rpc();
+
+      assert.fail("Should have thrown an error for an underfunded minter");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+    }
+
+    const after = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    assert.equal(after.toString(), balance.toString());
+
+    console.log("✅ Underfunded lock rejected up front");
+  });
+
+  it("Registers a minter on their first lock and counts every escrow they create", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const newMinter = Keypair.generate();
+    const airdropNewMinter = await provider.connection.requestAirdrop(
+      newMinter.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropNewMinter);
+
+    const [minterAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("minter"), newMinter.publicKey.toBuffer()],
+      program.programId
+    );
+    const newMinterRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      newMinter,
+      rewardTokenMint,
+      newMinter.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      newMinterRewardAccount.address,
+      minter,
+      rewardValue.muln(2).toNumber()
+    );
+
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+
+    // Lock two campaigns and collect the registry events of each
+    const lockCampaign = async () => {
+      const campaignToken = await createMint(provider.connection, newMinter, newMinter.publicKey, null, 6);
+      const [campaignEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), campaignToken.toBuffer(), newMinter.publicKey.toBuffer()],
+        program.programId
+      );
+      const campaignWallets = deriveEscrowWallets(campaignToken, newMinter.publicKey, 5);
+      await mintMainSupply(campaignToken, newMinter, supply);
+      await initializeEscrowWallets(campaignToken, newMinter, rewardTokenMint, campaignWallets);
+
+      const signature = await program.methods
+        .lockFundsV2(campaignToken, rewardTokenMint, newMinter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: campaignEscrowLock,
+          minter: newMinter.publicKey,
+          minterAccount,
+          tokenMint: campaignToken,
+          rewardTokenMint,
+          minterRewardAccount: newMinterRewardAccount.address,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(campaignWallets))
+        .signers([newMinter])
+        .rpc({ commitment: "confirmed" });
+
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const events = [...eventParser.parseLogs(tx.meta.logMessages)];
+      return {
+        escrow: campaignEscrowLock,
+        registered: events.filter((event) => event.name === "minterRegistered"),
+        created: events.filter((event) => event.name === "escrowCreated"),
+      };
+    };
+
+    const first = await lockCampaign();
+    assert.equal(first.registered.length, 1);
+    assert.equal(first.created.length, 1);
+    assert.equal(first.created[0].data.escrow.toString(), first.escrow.toString());
+    assert.equal(first.created[0].data.escrowNumber.toString(), "1");
+
+    const second = await lockCampaign();
+    assert.equal(second.registered.length, 0);
+    assert.equal(second.created.length, 1);
+    assert.equal(second.created[0].data.escrowNumber.toString(), "2");
+
+    const registry = await program.account.minterAccount.fetch(minterAccount);
+    assert.equal(registry.minter.toString(), newMinter.publicKey.toString());
+    assert.equal(registry.totalEscrows.toString(), "2");
+
+    // Escrow ids are program-wide and sequential, whichever minter locks
+    const [escrowCounter] = PublicKey.findProgramAddressSync([Buffer.from("escrow_counter")], program.programId);
+    const counter = await program.account.escrowCounter.fetch(escrowCounter);
+    const firstEscrow = await program.account.escrowLockAccount.fetch(first.escrow);
+    const secondEscrow = await program.account.escrowLockAccount.fetch(second.escrow);
+    assert.equal(firstEscrow.escrowId.toString(), first.created[0].data.escrowId.toString());
+    assert.equal(secondEscrow.escrowId.toString(), firstEscrow.escrowId.addn(1).toString());
+    assert.equal(counter.totalEscrows.toString(), secondEscrow.escrowId.toString());
+
+    console.log("✅ Minter registry counts escrows");
+  });
+
+  it("Sends redeemed main tokens to the configured incinerator instead of burning them", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(100_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const holder = Keypair.generate();
+    const airdropHolder = await provider.connection.requestAirdrop(
+      holder.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropHolder);
+
+    const incineratedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [incineratedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), incineratedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const incineratedWallets = deriveEscrowWallets(incineratedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const holderMainAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      incineratedToken,
+      holder.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      incineratedToken,
+      holderMainAccount.address,
+      minter,
+      supply.toNumber()
+    );
+    await initializeEscrowWallets(incineratedToken, minter, rewardTokenMint, incineratedWallets);
+
+    await program.methods
+      .lockFundsV2(incineratedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: incineratedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: incineratedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(incineratedWallets))
+      .signers([minter])
+      .rpc();
+
+    // A token account owned by a key nobody holds stands in for the dead address
+    const deadOwner = Keypair.generate();
+    const incineratorAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      incineratedToken,
+      deadOwner.publicKey
+    );
+
+    await program.methods
+      .setIncinerator()
+      .accounts({
+        escrowLockAccount: incineratedEscrowLock,
+        minter: minter.publicKey,
+        token: incineratedToken,
+        incineratorAccount: incineratorAccount.address,
+      })
+      .signers([minter])
+      .rpc();
+
+    const holderRewardAccount = getAssociatedTokenAddressSync(rewardTokenMint, holder.publicKey);
+    const redeemTransfer = (incinerator: PublicKey) =>
+      program.methods
+        .redeemRewardsTransfer(burnAmount, null)
+        .accounts({
+          escrowLockAccount: incineratedEscrowLock,
+          user: holder.publicKey,
+          token: incineratedToken,
+          tokenMint: incineratedToken,
+          userTokenAccount: holderMainAccount.address,
+          incineratorAccount: incinerator,
+          rewardToken: rewardTokenMint,
+          userRewardAccount: holderRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(incineratedWallets))
+        .signers([holder])
+        .rpc();
+
+    // Any other main token account is rejected
+    try {
+      await redeemTransfer(holderMainAccount.address);
+      assert.fail("Should have thrown an error for a different incinerator account");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidIncinerator");
+    }
+
+    await redeemTransfer(incineratorAccount.address);
+
+    const incinerated = await getAccount(provider.connection, incineratorAccount.address);
+    const holderMain = await getAccount(provider.connection, holderMainAccount.address);
+    const holderReward = await getAccount(provider.connection, holderRewardAccount);
+    const mint = await getMint(provider.connection, incineratedToken);
+    assert.equal(incinerated.amount.toString(), burnAmount.toString());
+    assert.equal(holderMain.amount.toString(), supply.sub(burnAmount).toString());
+    assert.equal(holderReward.amount.toString(), "100000");
+    // Nothing was burned, so the mint supply is unchanged
+    assert.equal(mint.supply.toString(), supply.toString());
+
+    const escrow = await program.account.escrowLockAccount.fetch(incineratedEscrowLock);
+    assert.equal(escrow.totalTokenSupply.toString(), supply.sub(burnAmount).toString());
+
+    console.log("✅ Redeemed tokens sent to the incinerator");
+  });
+
+  it("Rejects a lock that has already expired and locks for a duration instead", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const duration = new BN(30 * 24 * 60 * 60);
+
+    const durationToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [durationEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), durationToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const durationWallets = deriveEscrowWallets(durationToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(durationToken, minter, supply);
+    await initializeEscrowWallets(durationToken, minter, rewardTokenMint, durationWallets);
+
+    const lockAccounts = {
+      escrowLockAccount: durationEscrowLock,
+      minter: minter.publicKey,
+      tokenMint: durationToken,
+      rewardTokenMint,
+      minterRewardAccount,
+      treasuryRewardAccount: null,
+      escrowWallet1: durationWallets[0],
+      escrowWallet2: durationWallets[1],
+      escrowWallet3: durationWallets[2],
+      escrowWallet4: durationWallets[3],
+      escrowWallet5: durationWallets[4],
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // An expiry in the past would make the escrow reclaimable straight away
+    const pastExpiry = new BN(Math.floor(Date.now() / 1000) - 1);
+    try {
+      await program.methods
+        .lockFunds(durationToken, rewardTokenMint, minter.publicKey, rewardValue, supply, pastExpiry, lockParams())
+        .accounts(lockAccounts)
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for a past expiry");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidExpiry");
+    }
+
+    // So would a duration that isn't positive
+    try {
+      await program.methods
+        .lockFundsDuration(durationToken, rewardTokenMint, minter.publicKey, rewardValue, supply, new BN(0), lockParams())
+        .accounts(lockAccounts)
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for a zero duration");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidExpiry");
+    }
+
+    await program.methods
+      .lockFundsDuration(durationToken, rewardTokenMint, minter.publicKey, rewardValue, supply, duration, lockParams())
+      .accounts(lockAccounts)
+      .signers([minter])
+      .rpc();
+
+    const escrow = await program.account.escrowLockAccount.fetch(durationEscrowLock);
+    assert.equal(
+      escrow.expiresAt.toString(),
+      escrow.createdAt.add(duration).toString()
+    );
+
+    console.log("✅ Past expiries rejected, duration lock expires relative to the lock time");
+  });
+
+  it("Rejects a redemption that would pay more than max_reward_per_redeem", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const rewardCap = new BN(50_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const cappedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [cappedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), cappedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const cappedWallets = deriveEscrowWallets(cappedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const holderMainAccount = await mintMainSupply(cappedToken, minter, supply);
+    await initializeEscrowWallets(cappedToken, minter, rewardTokenMint, cappedWallets);
+
+    await program.methods
+      .lockFundsV2(cappedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ maxRewardPerRedeem: rewardCap }))
+      .accounts({
+        escrowLockAccount: cappedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: cappedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(cappedWallets))
+      .signers([minter])
+      .rpc();
+
+    const redeem = (burnAmount: BN) =>
+      program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: cappedEscrowLock,
+          user: minter.publicKey,
+          token: cappedToken,
+          tokenMint: cappedToken,
+          userTokenAccount: holderMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cappedWallets))
+        .signers([minter])
+        .rpc();
+
+    // 10% of the supply would pay 100_000, twice the cap
+    try {
+      await redeem(new BN(100_000));
+      assert.fail("Should have thrown an error for a reward above the cap");
+    } catch (error) {
+      assert.include(error.toString(), "RewardCapExceeded");
+    }
+
+    const holderBefore = await getAccount(provider.connection, holderMainAccount);
+    assert.equal(holderBefore.amount.toString(), supply.toString());
+
+    // A burn that pays exactly the cap goes through
+    await redeem(new BN(50_000));
+
+    const escrow = await program.account.escrowLockAccount.fetch(cappedEscrowLock);
+    assert.equal(escrow.maxRewardPerRedeem.toString(), rewardCap.toString());
+    assert.equal(escrow.remainingRewardValue.toString(), "950000");
+
+    console.log("✅ Per-redemption reward cap enforced");
+  });
+
+  it("Verifies escrow solvency and flags wallets that drift from the recorded value", async () => {
+    const rewardValue = new BN(1_000_003);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const solventToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [solventEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), solventToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const solventWallets = deriveEscrowWallets(solventToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(solventToken, minter, supply);
+    await initializeEscrowWallets(solventToken, minter, rewardTokenMint, solventWallets);
+
+    await program.methods
+      .lockFundsV2(solventToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: solventEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: solventToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(solventWallets))
+      .signers([minter])
+      .rpc();
+
+    const verify = () =>
+      program.methods
+        .verifyEscrowSolvency()
+        .accounts({ escrowLockAccount: solventEscrowLock, token: solventToken })
+        .remainingAccounts(escrowWalletMetas(solventWallets))
+        .rpc();
+
+    // Freshly locked, including the remainder shares
+    await verify();
+
+    // Tokens sent straight to a wallet put it beyond the tolerance of one per wallet
+    await mintTo(provider.connection, minter, rewardTokenMint, solventWallets[0], minter, 6);
+    try {
+      await verify();
+      assert.fail("Should have thrown an error for a wallet surplus");
+    } catch (error) {
+      assert.include(error.toString(), "SolvencyMismatch");
+    }
+
+    console.log("✅ Solvency probe passes on a fresh escrow and flags drift");
+  });
+
+  it("Activates a lock over escrow wallets funded in separate transactions", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const stagedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [stagedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), stagedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const stagedWallets = deriveEscrowWallets(stagedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(stagedToken, minter, supply);
+    await initializeEscrowWallets(stagedToken, minter, rewardTokenMint, stagedWallets);
+
+    const fund = (walletIndex: number, amount: BN) =>
+      program.methods
+        .fundEscrowWallet(stagedToken, walletIndex, amount)
+        .accounts({
+          minter: minter.publicKey,
+          escrowWallet: stagedWallets[walletIndex - 1],
+          minterRewardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([minter])
+        .rpc();
+    const activate = () =>
+      program.methods
+        .activateLock(stagedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: stagedEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: stagedToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(stagedWallets))
+        .signers([minter])
+        .rpc();
+
+    // Uneven drips that stop short of the full value
+    await fund(1, new BN(400_000));
+    await fund(2, new BN(300_000));
+    await fund(3, new BN(200_000));
+
+    try {
+      await activate();
+      assert.fail("Should have thrown an error for an underfunded activation");
+    } catch (error) {
+      assert.include(error.toString(), "FundingMismatch");
+    }
+
+    await fund(4, new BN(100_000));
+    const minterBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    await activate();
+    const minterAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    assert.equal(minterAfter.toString(), minterBefore.toString());
+
+    const escrow = await program.account.escrowLockAccount.fetch(stagedEscrowLock);
+    assert.deepEqual(escrow.status, { active: {} });
+    assert.equal(escrow.remainingRewardValue.toString(), rewardValue.toString());
+
+    // Once the lock exists the wallets can't be topped up this way
+    try {
+      await fund(5, new BN(1));
+      assert.fail("Should have thrown an error for funding an active escrow");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowAlreadyExists");
+    }
+
+    console.log("✅ Staged funding activated once the wallets held the full value");
+  });
+
+  it("Describes the per-wallet split in the FundsLocked event", async () => {
+    const rewardValue = new BN(1_000_003);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const splitToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [splitEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), splitToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const splitWallets = deriveEscrowWallets(splitToken, minter.publicKey, 4);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(splitToken, minter, supply);
+    await initializeEscrowWallets(splitToken, minter, rewardTokenMint, splitWallets);
+
+    const signature = await program.methods
+      .lockFundsV2(splitToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ walletCount: 4 }))
+      .accounts({
+        escrowLockAccount: splitEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: splitToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(splitWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const locked = [...eventParser.parseLogs(tx.meta.logMessages)].filter((event) => event.name === "fundsLocked");
+
+    assert.equal(locked.length, 1);
+    assert.equal(locked[0].data.rewardPerWallet.toString(), "250000");
+    assert.equal(locked[0].data.rewardRemainder.toString(), "3");
+    assert.deepEqual(
+      locked[0].data.escrowWallets.map((wallet: PublicKey) => wallet.toString()),
+      splitWallets.map((wallet) => wallet.toString())
+    );
+
+    console.log("✅ FundsLocked carries the per-wallet split");
+  });
+
+  it("Splits the lock and redemptions across escrow wallets by weight", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const weightedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [weightedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), weightedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const weightedWallets = deriveEscrowWallets(weightedToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const holderMainAccount = await mintMainSupply(weightedToken, minter, supply);
+    await initializeEscrowWallets(weightedToken, minter, rewardTokenMint, weightedWallets);
+
+    const lock = (weights: number[]) =>
+      program.methods
+        .lockFunds(weightedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ weights }))
+        .accounts({
+          escrowLockAccount: weightedEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: weightedToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          escrowWallet1: weightedWallets[0],
+          escrowWallet2: weightedWallets[1],
+          escrowWallet3: weightedWallets[2],
+          escrowWallet4: weightedWallets[3],
+          escrowWallet5: weightedWallets[4],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    const walletBalances = async () =>
+      Promise.all(
+        weightedWallets.map(async (wallet) => (await getAccount(provider.connection, wallet)).amount.toString())
+      );
+
+    // Weights must sum to 10000
+    try {
+      await lock([5000, 1000, 1000, 1000, 1000]);
+      assert.fail("Should have thrown an error for weights that don't sum to 10000");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWeights");
+    }
+
+    await lock([5000, 1250, 1250, 1250, 1250]);
+    assert.deepEqual(await walletBalances(), ["500000", "125000", "125000", "125000", "125000"]);
+
+    // 10% of the supply pays 100_000, drawn by the same weights
+    await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: weightedEscrowLock,
+        user: minter.publicKey,
+        token: weightedToken,
+        tokenMint: weightedToken,
+        userTokenAccount: holderMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(weightedWallets))
+      .signers([minter])
+      .rpc();
+
+    assert.deepEqual(await walletBalances(), ["450000", "112500", "112500", "112500", "112500"]);
+
+    console.log("✅ Weighted wallets funded and drawn by weight");
+  });
+
+  it("Reconciles the remaining reward value with live wallet balances on redemption", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const surplus = 500_000;
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const driftToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [driftEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), driftToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const driftWallets = deriveEscrowWallets(driftToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const holderMainAccount = await mintMainSupply(driftToken, minter, supply);
+    await initializeEscrowWallets(driftToken, minter, rewardTokenMint, driftWallets);
+
+    await program.methods
+      .lockFundsV2(driftToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: driftEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: driftToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(driftWallets))
+      .signers([minter])
+      .rpc();
+
+    // Tokens sent straight to a wallet make the recorded value stale
+    await mintTo(provider.connection, minter, rewardTokenMint, driftWallets[0], minter, surplus);
+
+    const signature = await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: driftEscrowLock,
+        user: minter.publicKey,
+        token: driftToken,
+        tokenMint: driftToken,
+        userTokenAccount: holderMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(driftWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const reconciled = [...eventParser.parseLogs(tx.meta.logMessages)].filter(
+      (event) => event.name === "remainingRewardReconciled"
+    );
+    assert.equal(reconciled.length, 1);
+    assert.equal(reconciled[0].data.recordedValue.toString(), "1000000");
+    assert.equal(reconciled[0].data.walletBalanceTotal.toString(), "1500000");
+
+    // 10% of the supply is paid from the live 1_500_000, not the stale 1_000_000
+    const escrow = await program.account.escrowLockAccount.fetch(driftEscrowLock);
+    assert.equal(escrow.remainingRewardValue.toString(), "1350000");
+    const walletTotal = (
+      await Promise.all(driftWallets.map((wallet) => getAccount(provider.connection, wallet)))
+    ).reduce((total, wallet) => total + wallet.amount, BigInt(0));
+    assert.equal(walletTotal.toString(), escrow.remainingRewardValue.toString());
+
+    console.log("✅ Remaining rewards reconciled with the wallets");
+  });
+
+  it("Burns only what a short pool can pay for and leaves the rest unburned", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const shortExpiry = new BN(Math.floor(Date.now() / 1000) + 3);
+
+    const shortToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [shortEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), shortToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const shortWallets = deriveEscrowWallets(shortToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(shortToken, minter, supply);
+    await initializeEscrowWallets(shortToken, minter, rewardTokenMint, shortWallets);
+
+    // Snapshot mode pays a fixed share per token, so reclaiming part of the pool
+    // leaves it short of what the outstanding supply is owed
+    await program.methods
+      .lockFundsV2(shortToken, rewardTokenMint, minter.publicKey, rewardValue, supply, shortExpiry, lockParams({ mode: 1 }))
+      .accounts({
+        escrowLockAccount: shortEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: shortToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(shortWallets))
+      .signers([minter])
+      .rpc();
+
+    await waitForExpiry(shortExpiry);
+
+    await program.methods
+      .partialWithdrawExpired(new BN(600_000))
+      .accounts({
+        escrowLockAccount: shortEscrowLock,
+        minter: minter.publicKey,
+        token: shortToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(shortWallets))
+      .signers([minter])
+      .rpc();
+
+    await program.methods
+      .extendExpiry(new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60))
+      .accounts({
+        escrowLockAccount: shortEscrowLock,
+        minter: minter.publicKey,
+        token: shortToken,
+      })
+      .signers([minter])
+      .rpc();
+
+    // The whole supply is owed 1_000_000 but only 400_000 is left
+    const rewardBefore = await getAccount(provider.connection, minterRewardAccount);
+    await program.methods
+      .redeemRewards(supply, null)
+      .accounts({
+        escrowLockAccount: shortEscrowLock,
+        user: minter.publicKey,
+        token: shortToken,
+        tokenMint: shortToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(shortWallets))
+      .signers([minter])
+      .rpc();
+    const rewardAfter = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal((rewardAfter.amount - rewardBefore.amount).toString(), "400000");
+
+    const mainAfter = await getAccount(provider.connection, minterMainAccount);
+    assert.equal(mainAfter.amount.toString(), "600000");
+
+    const escrowData = await program.account.escrowLockAccount.fetch(shortEscrowLock);
+    assert.equal(escrowData.totalBurned.toString(), "400000");
+    assert.equal(escrowData.remainingRewardValue.toString(), "0");
+    assert.deepEqual(escrowData.status, { drained: {} });
+
+    console.log("✅ Short pool drained with a reduced burn");
+  });
+
+  it("Resyncs the escrow wallet addresses from the canonical PDAs", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const resyncToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [resyncEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), resyncToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const resyncWallets = deriveEscrowWallets(resyncToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(resyncToken, minter, supply);
+    await initializeEscrowWallets(resyncToken, minter, rewardTokenMint, resyncWallets);
+
+    await program.methods
+      .lockFundsV2(resyncToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: resyncEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: resyncToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(resyncWallets))
+      .signers([minter])
+      .rpc();
+
+    const resync = (wallets: PublicKey[], signer: Keypair = minter) =>
+      program.methods
+        .resyncEscrowWallets()
+        .accounts({
+          escrowLockAccount: resyncEscrowLock,
+          minter: signer.publicKey,
+          token: resyncToken,
+        })
+        .remainingAccounts(escrowWalletMetas(wallets))
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    // Only the minter can resync
+    try {
+      await resync(resyncWallets, user);
+      assert.fail("Should have thrown an error for a non-minter");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    // Wallets out of index order are not the canonical PDAs
+    try {
+      await resync([resyncWallets[1], resyncWallets[0], ...resyncWallets.slice(2)]);
+      assert.fail("Should have thrown an error for a wallet at the wrong index");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidEscrowWalletIndex");
+    }
+
+    const signature = await resync(resyncWallets);
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const resynced = [...eventParser.parseLogs(tx.meta.logMessages)].filter(
+      (event) => event.name === "escrowWalletsResynced"
+    );
+    assert.equal(resynced.length, 1);
+    assert.deepEqual(
+      resynced[0].data.newEscrowWallets.map((wallet: PublicKey) => wallet.toBase58()),
+      resyncWallets.map((wallet) => wallet.toBase58())
+    );
+
+    const escrowData = await program.account.escrowLockAccount.fetch(resyncEscrowLock);
+    assert.deepEqual(
+      escrowData.escrowWallets.slice(0, 5).map((wallet: PublicKey) => wallet.toBase58()),
+      resyncWallets.map((wallet) => wallet.toBase58())
+    );
+
+    console.log("✅ Escrow wallets resynced");
+  });
+
+  it("Mints a claim receipt per redemption once a receipt mint is set", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const receiptToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [receiptEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), receiptToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const receiptWallets = deriveEscrowWallets(receiptToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(receiptToken, minter, supply);
+    await initializeEscrowWallets(receiptToken, minter, rewardTokenMint, receiptWallets);
+
+    await program.methods
+      .lockFundsV2(receiptToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: receiptEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: receiptToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(receiptWallets))
+      .signers([minter])
+      .rpc();
+
+    // The escrow lock account must be the receipt mint's authority
+    const receiptMint = await createMint(provider.connection, minter, receiptEscrowLock, null, 0);
+    const minterReceiptAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      receiptMint,
+      minter.publicKey
+    );
+
+    await program.methods
+      .setReceiptMint()
+      .accounts({
+        escrowLockAccount: receiptEscrowLock,
+        minter: minter.publicKey,
+        token: receiptToken,
+        receiptMint,
+      })
+      .signers([minter])
+      .rpc();
+
+    const redeem = (receipt: PublicKey | null, receiptAccount: PublicKey | null) =>
+      program.methods
+        .redeemRewards(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: receiptEscrowLock,
+          user: minter.publicKey,
+          token: receiptToken,
+          tokenMint: receiptToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: receipt,
+          userReceiptAccount: receiptAccount,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(receiptWallets))
+        .signers([minter])
+        .rpc();
+
+    // Once configured, the receipt accounts are required
+    try {
+      await redeem(null, null);
+      assert.fail("Should have thrown an error for missing receipt accounts");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidReceiptMint");
+    }
+
+    await redeem(receiptMint, minterReceiptAccount.address);
+    await redeem(receiptMint, minterReceiptAccount.address);
+
+    const receipts = await getAccount(provider.connection, minterReceiptAccount.address);
+    assert.equal(receipts.amount.toString(), "2");
+
+    // Variants that don't mint receipts can't bypass them
+    try {
+      await program.methods
+        .redeemRewardsTo(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: receiptEscrowLock,
+          user: minter.publicKey,
+          token: receiptToken,
+          tokenMint: receiptToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(receiptWallets))
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for a redemption without a receipt");
+    } catch (error) {
+      assert.include(error.toString(), "ReceiptMintRequired");
+    }
+
+    console.log("✅ Claim receipts minted");
+  });
+
+  it("Enforces the reward value and token supply bounds at lock time", async () => {
+    const MAX_REWARD_VALUE = new BN("1000000000000000000");
+    const MAX_TOKEN_SUPPLY = new BN("1000000000000000000");
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const lockBounded = async (rewardValue: BN, supply: BN) => {
+      const boundToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+      const [boundEscrowLock] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_lock"), boundToken.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const boundWallets = deriveEscrowWallets(boundToken, minter.publicKey, 5);
+
+      await mintMainSupply(boundToken, minter, supply);
+      await initializeEscrowWallets(boundToken, minter, rewardTokenMint, boundWallets);
+
+      await program.methods
+        .lockFundsV2(boundToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: boundEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: boundToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(boundWallets))
+        .signers([minter])
+        .rpc();
+
+      return boundEscrowLock;
+    };
+
+    // One past either bound is rejected
+    try {
+      await lockBounded(MAX_REWARD_VALUE.addn(1), new BN(1_000_000));
+      assert.fail("Should have thrown an error for a reward value above the bound");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, 1_000_000);
+    try {
+      await lockBounded(new BN(1_000_000), MAX_TOKEN_SUPPLY.addn(1));
+      assert.fail("Should have thrown an error for a token supply above the bound");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    // Exactly at both bounds is accepted
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      BigInt(MAX_REWARD_VALUE.toString())
+    );
+    const boundEscrowLock = await lockBounded(MAX_REWARD_VALUE, MAX_TOKEN_SUPPLY);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(boundEscrowLock);
+    assert.equal(escrowData.totalRewardValue.toString(), MAX_REWARD_VALUE.toString());
+    assert.equal(escrowData.totalTokenSupply.toString(), MAX_TOKEN_SUPPLY.toString());
+
+    console.log("✅ Lock bounds enforced");
+  });
+
+  it("Rejects a lock whose escrow wallets weren't all initialized", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const setupToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [setupEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), setupToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const setupWallets = deriveEscrowWallets(setupToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    await mintMainSupply(setupToken, minter, supply);
+    // Wallet 5 is forgotten
+    await initializeEscrowWallets(setupToken, minter, rewardTokenMint, setupWallets.slice(0, 4));
+
+    const lock = () =>
+      program.methods
+        .lockFunds(setupToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: setupEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: setupToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          escrowWallet1: setupWallets[0],
+          escrowWallet2: setupWallets[1],
+          escrowWallet3: setupWallets[2],
+          escrowWallet4: setupWallets[3],
+          escrowWallet5: setupWallets[4],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+
+    const before = await getAccount(provider.connection, minterRewardAccount);
+    try {
+      await lock();
+      assert.fail("Should have thrown an error for an uninitialized escrow wallet");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowWalletNotInitialized");
+    }
+    const after = await getAccount(provider.connection, minterRewardAccount);
+    assert.equal(after.amount.toString(), before.amount.toString());
+
+    // Initializing the missing wallet is all it takes
+    await program.methods
+      .initializeEscrowWallet(setupToken, 5)
+      .accounts({
+        minter: minter.publicKey,
+        rewardTokenMint,
+        escrowLockAccount: setupEscrowLock,
+        escrowWallet: setupWallets[4],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([minter])
+      .rpc();
+    await lock();
+
+    const escrowData = await program.account.escrowLockAccount.fetch(setupEscrowLock);
+    assert.equal(escrowData.totalRewardValue.toString(), rewardValue.toString());
+
+    console.log("✅ Uninitialized escrow wallet caught before any transfer");
+  });
+
+  it("Rejects a Token-2022 lock whose escrow wallets weren't all initialized", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const setupToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const setupReward = await createMint(
+      provider.connection,
+      minter,
+      minter.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [setupEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), setupToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const setupWallets = deriveEscrowWallets(setupToken, minter.publicKey, 5);
+
+    const minterSetupReward = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      setupReward,
+      minter.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      minter,
+      setupReward,
+      minterSetupReward.address,
+      minter,
+      rewardValue.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintMainSupply(setupToken, minter, supply);
+
+    // Wallet 5 is forgotten
+    for (let i = 1; i <= 4; i++) {
+      await program.methods
+        .initializeEscrowWallet2022(setupToken, i)
+        .accounts({
+          minter: minter.publicKey,
+          rewardTokenMint: setupReward,
+          escrowLockAccount: setupEscrowLock,
+          escrowWallet: setupWallets[i - 1],
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    }
+
+    try {
+      await program.methods
+        .lockFunds2022(setupToken, setupReward, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: setupEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: setupToken,
+          rewardTokenMint: setupReward,
+          minterRewardAccount: minterSetupReward.address,
+          treasuryRewardAccount: null,
+          rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(setupWallets))
+        .signers([minter])
+        .rpc();
+      assert.fail("Should have thrown an error for an uninitialized escrow wallet");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowWalletNotInitialized");
+    }
+
+    const after = await getAccount(provider.connection, minterSetupReward.address, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(after.amount.toString(), rewardValue.toString());
+
+    console.log("✅ Uninitialized Token-2022 escrow wallet caught before any transfer");
+  });
+
+  it("Emits FirstRedemption only for an escrow's first redemption", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const firstToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [firstEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), firstToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const firstWallets = deriveEscrowWallets(firstToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(firstToken, minter, supply);
+    await initializeEscrowWallets(firstToken, minter, rewardTokenMint, firstWallets);
+
+    await program.methods
+      .lockFundsV2(firstToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: firstEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: firstToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(firstWallets))
+      .signers([minter])
+      .rpc();
+
+    const eventParser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const redeemAndCollect = async () => {
+      const signature = await program.methods
+        .redeemRewards(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: firstEscrowLock,
+          user: minter.publicKey,
+          token: firstToken,
+          tokenMint: firstToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(firstWallets))
+        .signers([minter])
+        .rpc({ commitment: "confirmed" });
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      return [...eventParser.parseLogs(tx.meta.logMessages)].filter((event) => event.name === "firstRedemption");
+    };
+
+    const firstEvents = await redeemAndCollect();
+    assert.equal(firstEvents.length, 1);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(firstEscrowLock);
+    assert.isAbove(escrowData.firstRedemptionAt.toNumber(), 0);
+    assert.equal(firstEvents[0].data.createdAt.toString(), escrowData.createdAt.toString());
+    assert.equal(
+      firstEvents[0].data.secondsSinceLock.toString(),
+      escrowData.firstRedemptionAt.sub(escrowData.createdAt).toString()
+    );
+
+    // Later redemptions leave the marker alone
+    const laterEvents = await redeemAndCollect();
+    assert.equal(laterEvents.length, 0);
+    const escrowAfter = await program.account.escrowLockAccount.fetch(firstEscrowLock);
+    assert.equal(escrowAfter.firstRedemptionAt.toString(), escrowData.firstRedemptionAt.toString());
+
+    console.log("✅ FirstRedemption emitted once");
+  });
+
+  it("Rejects a burn too small to earn any reward", async () => {
+    const rewardValue = new BN(1_000);
+    const supply = new BN("1000000000000");
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const dustToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [dustEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), dustToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const dustWallets = deriveEscrowWallets(dustToken, minter.publicKey, 5);
+
+    await mintTo(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      minterRewardAccount,
+      minter,
+      rewardValue.toNumber()
+    );
+    const minterMainAccount = await mintMainSupply(dustToken, minter, supply);
+    await initializeEscrowWallets(dustToken, minter, rewardTokenMint, dustWallets);
+
+    await program.methods
+      .lockFundsV2(dustToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: dustEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: dustToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(dustWallets))
+      .signers([minter])
+      .rpc();
+
+    const redeem = (burnAmount: BN) =>
+      program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: dustEscrowLock,
+          user: minter.publicKey,
+          token: dustToken,
+          tokenMint: dustToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(dustWallets))
+        .signers([minter])
+        .rpc();
+
+    // 999_999_999 / 1e12 of a 1_000 pool floors to 0
+    try {
+      await redeem(new BN(999_999_999));
+      assert.fail("Should have thrown an error for a zero reward");
+    } catch (error) {
+      assert.include(error.toString(), "RewardRoundsToZero");
+    }
+
+    const mainAfterReject = await getAccount(provider.connection, minterMainAccount);
+    assert.equal(mainAfterReject.amount.toString(), supply.toString());
+
+    // 1e9 is the smallest burn that earns a whole reward token
+    await redeem(new BN(1_000_000_000));
+    const escrowData = await program.account.escrowLockAccount.fetch(dustEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "999");
+
+    console.log("✅ Zero-reward burn rejected");
+  });
+
+  it("Locks exact per-wallet amounts and draws in proportion to them", async () => {
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const customToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [customEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), customToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const customWallets = deriveEscrowWallets(customToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, 1_000_000);
+    const holderMainAccount = await mintMainSupply(customToken, minter, supply);
+    await initializeEscrowWallets(customToken, minter, rewardTokenMint, customWallets);
+
+    const lock = (amounts: number[], params = lockParams()) =>
+      program.methods
+        .lockFundsCustom(
+          customToken,
+          rewardTokenMint,
+          minter.publicKey,
+          amounts.map((amount) => new BN(amount)),
+          supply,
+          expiryTime,
+          params
+        )
+        .accounts({
+          escrowLockAccount: customEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: customToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          escrowWallet1: customWallets[0],
+          escrowWallet2: customWallets[1],
+          escrowWallet3: customWallets[2],
+          escrowWallet4: customWallets[3],
+          escrowWallet5: customWallets[4],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    const walletBalances = async () =>
+      Promise.all(
+        customWallets.map(async (wallet) => (await getAccount(provider.connection, wallet)).amount.toString())
+      );
+
+    // Every wallet must get something
+    try {
+      await lock([500_000, 500_000, 0, 0, 0]);
+      assert.fail("Should have thrown an error for an empty wallet");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWalletAmounts");
+    }
+
+    // The amounts already set each wallet's share, so weights can't be given too
+    try {
+      await lock([400_000, 300_000, 200_000, 50_000, 50_000], lockParams({ weights: [2000, 2000, 2000, 2000, 2000] }));
+      assert.fail("Should have thrown an error for weights on a custom lock");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWeights");
+    }
+
+    // The 5 named wallets fix the wallet count
+    try {
+      await lock([400_000, 300_000, 200_000, 50_000, 50_000], lockParams({ walletCount: 4 }));
+      assert.fail("Should have thrown an error for a wallet count other than 5");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWalletCount");
+    }
+
+    await lock([400_000, 300_000, 200_000, 50_000, 50_000]);
+    assert.deepEqual(await walletBalances(), ["400000", "300000", "200000", "50000", "50000"]);
+
+    let escrowData = await program.account.escrowLockAccount.fetch(customEscrowLock);
+    assert.equal(escrowData.totalRewardValue.toString(), "1000000");
+    assert.deepEqual(
+      escrowData.walletAmounts.slice(0, 5).map((amount: BN) => amount.toString()),
+      ["400000", "300000", "200000", "50000", "50000"]
+    );
+
+    // 10% of the supply pays 100_000, drawn in proportion to the locked amounts
+    await program.methods
+      .redeemRewards(new BN(100_000), null)
+      .accounts({
+        escrowLockAccount: customEscrowLock,
+        user: minter.publicKey,
+        token: customToken,
+        tokenMint: customToken,
+        userTokenAccount: holderMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(customWallets))
+      .signers([minter])
+      .rpc();
+
+    assert.deepEqual(await walletBalances(), ["360000", "270000", "180000", "45000", "45000"]);
+    escrowData = await program.account.escrowLockAccount.fetch(customEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "900000");
+
+    console.log("✅ Custom amounts locked and drawn proportionally");
+  });
+
+  it("Enforces the program's minimum lock duration", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+
+    // Only the config admin can set the minimum
+    try {
+      await program.methods
+        .setMinLockDuration(new BN(3600))
+        .accounts({ admin: user.publicKey })
+        .signers([user])
+        .rpc();
+
+      assert.fail("Should have thrown an error for a non-admin");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedAdmin");
+    }
+
+    const shortToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [shortEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), shortToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const shortWallets = deriveEscrowWallets(shortToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(shortToken, minter, supply);
+    await initializeEscrowWallets(shortToken, minter, rewardTokenMint, shortWallets);
+
+    const lock = (expiryTime: BN) =>
+      program.methods
+        .lockFundsV2(shortToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: shortEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: shortToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(shortWallets))
+        .signers([minter])
+        .rpc();
+
+    await program.methods
+      .setMinLockDuration(new BN(3600))
+      .accounts({ admin: provider.wallet.publicKey })
+      .rpc();
+
+    try {
+      // A minute of redemption window is below the one hour minimum
+      try {
+        await lock(new BN(Math.floor(Date.now() / 1000) + 60));
+        assert.fail("Should have thrown an error for a lock shorter than the minimum");
+      } catch (error) {
+        assert.include(error.toString(), "LockDurationTooShort");
+      }
+
+      await lock(new BN(Math.floor(Date.now() / 1000) + 2 * 3600));
+      const escrowData = await program.account.escrowLockAccount.fetch(shortEscrowLock);
+      assert.equal(escrowData.totalRewardValue.toString(), rewardValue.toString());
+    } finally {
+      // Later tests lock with short expiries
+      await program.methods
+        .setMinLockDuration(new BN(0))
+        .accounts({ admin: provider.wallet.publicKey })
+        .rpc();
+    }
+
+    const [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    const configData = await program.account.programConfig.fetch(programConfig);
+    assert.equal(configData.minLockDuration.toString(), "0");
+
+    console.log("✅ Locks shorter than the minimum rejected");
+  });
+
+  it("Rejects a redemption from a frozen escrow wallet with AccountFrozen", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const burnAmount = new BN(100_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const freezableReward = await createMint(provider.connection, minter, minter.publicKey, minter.publicKey, 6);
+    const minterFreezable = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      freezableReward,
+      minter.publicKey
+    );
+    await mintTo(provider.connection, minter, freezableReward, minterFreezable.address, minter, rewardValue.toNumber());
+
+    const frozenToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [frozenEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), frozenToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const frozenWallets = deriveEscrowWallets(frozenToken, minter.publicKey, 5);
+    const holderMainAccount = await mintMainSupply(frozenToken, minter, supply);
+    await initializeEscrowWallets(frozenToken, minter, freezableReward, frozenWallets);
+
+    await program.methods
+      .lockFundsV2(frozenToken, freezableReward, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: frozenEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: frozenToken,
+        rewardTokenMint: freezableReward,
+        minterRewardAccount: minterFreezable.address,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(frozenWallets))
+      .signers([minter])
+      .rpc();
+
+    const redeem = () =>
+      program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: frozenEscrowLock,
+          user: minter.publicKey,
+          token: frozenToken,
+          tokenMint: frozenToken,
+          userTokenAccount: holderMainAccount,
+          rewardToken: freezableReward,
+          rewardTokenMint: freezableReward,
+          userRewardAccount: minterFreezable.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(frozenWallets))
+        .signers([minter])
+        .rpc();
+
+    await freezeAccount(provider.connection, minter, frozenWallets[2], freezableReward, minter);
+
+    try {
+      await redeem();
+      assert.fail("Should have thrown an error for a frozen escrow wallet");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+    }
+
+    // Once thawed, the same redemption goes through
+    await thawAccount(provider.connection, minter, frozenWallets[2], freezableReward, minter);
+    await redeem();
+
+    const escrowData = await program.account.escrowLockAccount.fetch(frozenEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "900000");
+
+    console.log("✅ Frozen escrow wallet reported as AccountFrozen");
+  });
+
+  it("Pays a fixed rate per burned token once the minter sets one", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const fixedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [fixedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), fixedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const fixedWallets = deriveEscrowWallets(fixedToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    const minterMainAccount = await mintMainSupply(fixedToken, minter, supply);
+    await initializeEscrowWallets(fixedToken, minter, rewardTokenMint, fixedWallets);
+
+    await program.methods
+      .lockFundsV2(fixedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: fixedEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: fixedToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(fixedWallets))
+      .signers([minter])
+      .rpc();
+
+    const setFixedRate = (rate: number) =>
+      program.methods
+        .setFixedRate(new BN(rate))
+        .accounts({
+          escrowLockAccount: fixedEscrowLock,
+          minter: minter.publicKey,
+          token: fixedToken,
+        })
+        .signers([minter])
+        .rpc();
+    const redeem = (burnAmount: number) =>
+      program.methods
+        .redeemRewards(new BN(burnAmount), null)
+        .accounts({
+          escrowLockAccount: fixedEscrowLock,
+          user: minter.publicKey,
+          token: fixedToken,
+          tokenMint: fixedToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(fixedWallets))
+        .signers([minter])
+        .rpc();
+
+    await setFixedRate(2);
+    let escrowData = await program.account.escrowLockAccount.fetch(fixedEscrowLock);
+    assert.equal(escrowData.mode, 2);
+    assert.equal(escrowData.rate.toString(), "2");
+
+    // 100_000 burned at 2 per token pays 200_000, where the live mode would pay 100_000
+    const rewardBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    await redeem(100_000);
+    const rewardAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    assert.equal((rewardAfter - rewardBefore).toString(), "200000");
+
+    // 500_000 more would pay 1_000_000, more than the 800_000 left, so it reverts
+    try {
+      await redeem(500_000);
+      assert.fail("Should have thrown an error for a burn the pool can't cover");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+    }
+
+    // The model is fixed once someone has redeemed
+    try {
+      await setFixedRate(0);
+      assert.fail("Should have thrown an error after a redemption");
+    } catch (error) {
+      assert.include(error.toString(), "RedemptionsInProgress");
+    }
+
+    escrowData = await program.account.escrowLockAccount.fetch(fixedEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "800000");
+
+    console.log("✅ Fixed-rate redemptions pay rate × burn");
+  });
+
+  it("Clears a fixed rate back to the snapshot mode the escrow was locked with", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const snapshotToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [snapshotEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), snapshotToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const snapshotWallets = deriveEscrowWallets(snapshotToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(snapshotToken, minter, supply);
+    await initializeEscrowWallets(snapshotToken, minter, rewardTokenMint, snapshotWallets);
+
+    await program.methods
+      .lockFundsV2(snapshotToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ mode: 1 }))
+      .accounts({
+        escrowLockAccount: snapshotEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: snapshotToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(snapshotWallets))
+      .signers([minter])
+      .rpc();
+
+    const setFixedRate = (rate: number) =>
+      program.methods
+        .setFixedRate(new BN(rate))
+        .accounts({
+          escrowLockAccount: snapshotEscrowLock,
+          minter: minter.publicKey,
+          token: snapshotToken,
+        })
+        .signers([minter])
+        .rpc();
+
+    let escrowData = await program.account.escrowLockAccount.fetch(snapshotEscrowLock);
+    assert.equal(escrowData.mode, 1);
+    assert.equal(escrowData.lockMode, 1);
+
+    await setFixedRate(3);
+    escrowData = await program.account.escrowLockAccount.fetch(snapshotEscrowLock);
+    assert.equal(escrowData.mode, 2);
+
+    // Clearing the rate goes back to snapshot, not to the live default
+    await setFixedRate(0);
+    escrowData = await program.account.escrowLockAccount.fetch(snapshotEscrowLock);
+    assert.equal(escrowData.mode, 1);
+    assert.equal(escrowData.rate.toString(), "0");
+
+    console.log("✅ Cleared fixed rate restores the lock-time snapshot mode");
+  });
+
+  it("Splits one redemption's payout across recipients by weight", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const splitToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [splitEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), splitToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const splitWallets = deriveEscrowWallets(splitToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    const minterMainAccount = await mintMainSupply(splitToken, minter, supply);
+    await initializeEscrowWallets(splitToken, minter, rewardTokenMint, splitWallets);
+
+    await program.methods
+      .lockFundsV2(splitToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: splitEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: splitToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(splitWallets))
+      .signers([minter])
+      .rpc();
+
+    const beneficiary = Keypair.generate();
+    const recipients = [
+      (await getOrCreateAssociatedTokenAccount(provider.connection, minter, rewardTokenMint, user.publicKey)).address,
+      (await getOrCreateAssociatedTokenAccount(provider.connection, minter, rewardTokenMint, beneficiary.publicKey)).address,
+    ];
+    const recipientBalances = async () =>
+      Promise.all(recipients.map(async (recipient) => (await getAccount(provider.connection, recipient)).amount));
+
+    const split = (weights: number[]) =>
+      program.methods
+        .redeemRewardsSplit(new BN(100_000), null, weights)
+        .accounts({
+          escrowLockAccount: splitEscrowLock,
+          user: minter.publicKey,
+          token: splitToken,
+          tokenMint: splitToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          ...escrowWalletMetas(splitWallets),
+          ...recipients.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })),
+        ])
+        .signers([minter])
+        .rpc();
+
+    // Weights must sum to 10000
+    try {
+      await split([7000, 2000]);
+      assert.fail("Should have thrown an error for weights that don't sum to 10000");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWeights");
+    }
+
+    // A zero-weight recipient is rejected rather than handed a remainder token
+    try {
+      await split([0, 10000]);
+      assert.fail("Should have thrown an error for a zero weight");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidWeights");
+    }
+
+    const before = await recipientBalances();
+    const minterBefore = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    await split([7000, 3000]);
+    const after = await recipientBalances();
+
+    // 10% of the supply pays 100_000, shared 70/30; nothing stays with the signer
+    assert.equal((after[0] - before[0]).toString(), "70000");
+    assert.equal((after[1] - before[1]).toString(), "30000");
+    const minterAfter = (await getAccount(provider.connection, minterRewardAccount)).amount;
+    assert.equal(minterAfter.toString(), minterBefore.toString());
+
+    const escrowData = await program.account.escrowLockAccount.fetch(splitEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "900000");
+
+    console.log("✅ Payout split 70/30 across two recipients");
+  });
+
+  it("Gives every wallet but one the extra token when the remainder is 4", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const remainderToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [remainderEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), remainderToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const remainderWallets = deriveEscrowWallets(remainderToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    const minterMainAccount = await mintMainSupply(remainderToken, minter, supply);
+    await initializeEscrowWallets(remainderToken, minter, rewardTokenMint, remainderWallets);
+
+    await program.methods
+      .lockFundsV2(remainderToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: remainderEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: remainderToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(remainderWallets))
+      .signers([minter])
+      .rpc();
+
+    // A burn of 9 pays 9 reward tokens: 1 per wallet plus the largest remainder, 4
+    await program.methods
+      .redeemRewards(new BN(9), null)
+      .accounts({
+        escrowLockAccount: remainderEscrowLock,
+        user: minter.publicKey,
+        token: remainderToken,
+        tokenMint: remainderToken,
+        userTokenAccount: minterMainAccount,
+        rewardToken: rewardTokenMint,
+        rewardTokenMint: rewardTokenMint,
+        userRewardAccount: minterRewardAccount,
+        treasuryRewardAccount: null,
+        redeemerEntry: null,
+        userRedemption: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        redemptionLog: null,
+        memoProgram: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(remainderWallets))
+      .signers([minter])
+      .rpc();
+
+    // Wallets 1-4 each gave the extra token
+    const balances = await Promise.all(
+      remainderWallets.map(async (wallet) => (await getAccount(provider.connection, wallet)).amount.toString())
+    );
+    assert.deepEqual(balances, ["199998", "199998", "199998", "199998", "199999"]);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(remainderEscrowLock);
+    assert.equal(escrowData.remainingRewardValue.toString(), "999991");
+    assert.equal(escrowData.nextRemainderWallet, 4);
+
+    console.log("✅ Remainder of 4 covered by wallets 1-4");
+  });
+
+  it("Stamps new escrows with the layout version and migrates idempotently", async () => {
+    let escrowData = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(escrowData.version, 3);
+
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const signature = await program.methods
+      .migrateEscrow()
+      .accounts({
+        escrowLockAccount,
+        payer: provider.wallet.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const migrated = [...parser.parseLogs(tx.meta.logMessages)].filter((event) => event.name === "escrowMigrated");
+    assert.equal(migrated.length, 1);
+    assert.equal(migrated[0].data.fromVersion, 2);
+    assert.equal(migrated[0].data.toVersion, 2);
+    assert.equal(migrated[0].data.eventSeq.toString(), escrowData.eventSeq.addn(1).toString());
+
+    // Nothing else changed besides the event sequence
+    const after = await program.account.escrowLockAccount.fetch(escrowLockAccount);
+    assert.equal(after.remainingRewardValue.toString(), escrowData.remainingRewardValue.toString());
+    assert.equal(after.version, 3);
+    assert.equal(after.eventSeq.toString(), escrowData.eventSeq.addn(1).toString());
+
+    // Other program accounts aren't escrows
+    const [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    try {
+      await program.methods
+        .migrateEscrow()
+        .accounts({
+          escrowLockAccount: programConfig,
+          payer: provider.wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error for a non-escrow account");
+    } catch (error) {
+      assert.include(error.toString(), "EscrowNotFound");
+    }
+
+    console.log("✅ Escrow layout version checked and migration is a no-op when current");
+  });
+
+  it("Attaches a memo to the payout when the memo program is passed", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+    const memoProgram = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+    const memoToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [memoEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), memoToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const memoWallets = deriveEscrowWallets(memoToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    const minterMainAccount = await mintMainSupply(memoToken, minter, supply);
+    await initializeEscrowWallets(memoToken, minter, rewardTokenMint, memoWallets);
+
+    await program.methods
+      .lockFundsV2(memoToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: memoEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: memoToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(memoWallets))
+      .signers([minter])
+      .rpc();
+
+    const redeem = (memo: PublicKey | null) =>
+      program.methods
+        .redeemRewards(new BN(100_000), null)
+        .accounts({
+          escrowLockAccount: memoEscrowLock,
+          user: minter.publicKey,
+          token: memoToken,
+          tokenMint: memoToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: memo,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(memoWallets))
+        .signers([minter])
+        .rpc({ commitment: "confirmed" });
+    const logsOf = async (signature: string) =>
+      (
+        await provider.connection.getTransaction(signature, {
+          commitment: "confirmed",
+          maxSupportedTransactionVersion: 0,
+        })
+      ).meta.logMessages.join("\n");
+
+    const escrowData = await program.account.escrowLockAccount.fetch(memoEscrowLock);
+    const expectedMemo = `fluter-by redemption: escrow ${escrowData.escrowId.toString()} redeemer ${minter.publicKey.toString()}`;
+
+    const withMemo = await logsOf(await redeem(memoProgram));
+    assert.include(withMemo, expectedMemo);
+    assert.include(withMemo, memoProgram.toString());
+
+    // Without the memo program there is no memo CPI
+    const withoutMemo = await logsOf(await redeem(null));
+    assert.notInclude(withoutMemo, memoProgram.toString());
+
+    console.log("✅ Memo attached only when the memo program is passed");
+  });
+
+  it("Caps the escrows one minter may create", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const [minterRegistry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("minter"), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const created = (await program.account.minterAccount.fetch(minterRegistry)).totalEscrows;
+
+    const cappedToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [cappedEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), cappedToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const cappedWallets = deriveEscrowWallets(cappedToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(cappedToken, minter, supply);
+    await initializeEscrowWallets(cappedToken, minter, rewardTokenMint, cappedWallets);
+
+    const lock = () =>
+      program.methods
+        .lockFundsV2(cappedToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: cappedEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: cappedToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(cappedWallets))
+        .signers([minter])
+        .rpc();
+    const setMax = (max: BN) =>
+      program.methods
+        .setMaxEscrowsPerMinter(max)
+        .accounts({ admin: provider.wallet.publicKey })
+        .rpc();
+
+    try {
+      // The minter is already at the cap
+      await setMax(created);
+      try {
+        await lock();
+        assert.fail("Should have thrown an error at the escrow limit");
+      } catch (error) {
+        assert.include(error.toString(), "MinterEscrowLimitReached");
+      }
+
+      // One more is allowed
+      await setMax(created.addn(1));
+      await lock();
+      const registry = await program.account.minterAccount.fetch(minterRegistry);
+      assert.equal(registry.totalEscrows.toString(), created.addn(1).toString());
+    } finally {
+      // Later tests keep locking with this minter
+      await setMax(new BN(0));
+    }
+
+    console.log("✅ Locks past the per-minter limit rejected");
+  });
+
+  it("Numbers every event about an escrow in sequence", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const seqToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [seqEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), seqToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const seqWallets = deriveEscrowWallets(seqToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(seqToken, minter, supply);
+    await initializeEscrowWallets(seqToken, minter, rewardTokenMint, seqWallets);
+
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const eventsOf = async (signature: string) => {
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      return [...parser.parseLogs(tx.meta.logMessages)];
+    };
+
+    const lockSignature = await program.methods
+      .lockFundsV2(seqToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: seqEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: seqToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(seqWallets))
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+
+    // The lock's own events count up from 1; registry events carry no sequence
+    const lockEvents = (await eventsOf(lockSignature)).filter((event) => event.data.eventSeq !== undefined);
+    assert.deepEqual(
+      lockEvents.map((event) => event.name),
+      ["escrowCreated", "fundsLocked"]
+    );
+    assert.deepEqual(
+      lockEvents.map((event) => event.data.eventSeq.toNumber()),
+      [1, 2]
+    );
+
+    const cooldownSignature = await program.methods
+      .setRedemptionCooldown(new BN(0))
+      .accounts({
+        escrowLockAccount: seqEscrowLock,
+        minter: minter.publicKey,
+        token: seqToken,
+      })
+      .signers([minter])
+      .rpc({ commitment: "confirmed" });
+    const [cooldownSet] = await eventsOf(cooldownSignature);
+    assert.equal(cooldownSet.name, "redemptionCooldownSet");
+    assert.equal(cooldownSet.data.eventSeq.toNumber(), 3);
+
+    const escrowData = await program.account.escrowLockAccount.fetch(seqEscrowLock);
+    assert.equal(escrowData.eventSeq.toNumber(), 3);
+
+    console.log("✅ Escrow events numbered 1, 2, 3");
+  });
+
+  it("Redeems to a recipient and closes the claimer's emptied reward account", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const claimAmount = new BN(100_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const claimer = Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(claimer.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+
+    const closeToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [closeEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), closeToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const closeWallets = deriveEscrowWallets(closeToken, minter.publicKey, 5);
+
+    // The claimer holds 10% of the supply and an empty reward account
+    const claimerMainAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      closeToken,
+      claimer.publicKey
+    );
+    await mintTo(provider.connection, minter, closeToken, claimerMainAccount.address, minter, claimAmount.toNumber());
+    await mintMainSupply(closeToken, minter, supply.sub(claimAmount));
+    const claimerRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      claimer.publicKey
+    );
+    const exchange = Keypair.generate();
+    const exchangeRewardAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      minter,
+      rewardTokenMint,
+      exchange.publicKey
+    );
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await initializeEscrowWallets(closeToken, minter, rewardTokenMint, closeWallets);
+    await program.methods
+      .lockFundsV2(closeToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: closeEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: closeToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(closeWallets))
+      .signers([minter])
+      .rpc();
+
+    const redeemAndClose = (recipientRewardAccount: PublicKey) =>
+      program.methods
+        .redeemRewardsAndClose(new BN(50_000), null)
+        .accounts({
+          escrowLockAccount: closeEscrowLock,
+          user: claimer.publicKey,
+          token: closeToken,
+          tokenMint: closeToken,
+          userTokenAccount: claimerMainAccount.address,
+          rewardToken: rewardTokenMint,
+          recipientRewardAccount,
+          userRewardAccount: claimerRewardAccount.address,
+          treasuryRewardAccount: null,
+          redeemerEntry: null,
+          userRedemption: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(closeWallets))
+        .signers([claimer])
+        .rpc();
+
+    // Paying the claimer's own account would leave nothing to close
+    try {
+      await redeemAndClose(claimerRewardAccount.address);
+      assert.fail("Should have thrown an error for the claimer's own account as recipient");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDistributionAmount");
+    }
+
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(165);
+    const lamportsBefore = await provider.connection.getBalance(claimer.publicKey);
+    await redeemAndClose(exchangeRewardAccount.address);
+
+    // 5% of the supply pays 50_000 to the exchange, and the rent comes back to the claimer
+    const exchangeAfter = await getAccount(provider.connection, exchangeRewardAccount.address);
+    assert.equal(exchangeAfter.amount.toString(), "50000");
+    assert.isNull(await provider.connection.getAccountInfo(claimerRewardAccount.address));
+    const lamportsAfter = await provider.connection.getBalance(claimer.publicKey);
+    assert.equal(lamportsAfter - lamportsBefore, rent);
+
+    console.log("✅ Rewards paid to the exchange and the empty reward account closed");
+  });
+
+  it("Stores each escrow wallet at the array slot of its seed index", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const orderToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [orderEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), orderToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const orderWallets = deriveEscrowWallets(orderToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(orderToken, minter, supply);
+    await initializeEscrowWallets(orderToken, minter, rewardTokenMint, orderWallets);
+
+    const lock = (wallets: PublicKey[]) =>
+      program.methods
+        .lockFunds(orderToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+        .accounts({
+          escrowLockAccount: orderEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: orderToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          escrowWallet1: wallets[0],
+          escrowWallet2: wallets[1],
+          escrowWallet3: wallets[2],
+          escrowWallet4: wallets[3],
+          escrowWallet5: wallets[4],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+
+    // Wallets 2 and 3 wired into each other's slots
+    const swapped = [orderWallets[0], orderWallets[2], orderWallets[1], orderWallets[3], orderWallets[4]];
+    try {
+      await lock(swapped);
+      assert.fail("Should have thrown an error for swapped escrow wallets");
+    } catch (error) {
+      assert.include(error.toString(), "ConstraintSeeds");
+    }
+
+    await lock(orderWallets);
+
+    // Slot i holds the PDA for seed index i + 1, with that PDA's bump
+    const escrowData = await program.account.escrowLockAccount.fetch(orderEscrowLock);
+    for (let i = 0; i < 5; i++) {
+      const [wallet, bump] = PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow_wallet"), orderToken.toBuffer(), minter.publicKey.toBuffer(), Buffer.from([i + 1])],
+        program.programId
+      );
+      assert.equal(escrowData.escrowWallets[i].toString(), wallet.toString());
+      assert.equal(escrowData.walletBumps[i], bump);
+    }
+
+    console.log("✅ Escrow wallets stored in seed-index order");
+  });
+
+  it("Sends the rent of a closed escrow to the minter's chosen rent recipient", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const rentToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [rentEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), rentToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const rentWallets = deriveEscrowWallets(rentToken, minter.publicKey, 5);
+
+    await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+    await mintMainSupply(rentToken, minter, supply);
+    await initializeEscrowWallets(rentToken, minter, rewardTokenMint, rentWallets);
+    await program.methods
+      .lockFundsV2(rentToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams())
+      .accounts({
+        escrowLockAccount: rentEscrowLock,
+        minter: minter.publicKey,
+        tokenMint: rentToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        treasuryRewardAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(rentWallets))
+      .signers([minter])
+      .rpc();
+    await program.methods
+      .cancelLock()
+      .accounts({
+        escrowLockAccount: rentEscrowLock,
+        minter: minter.publicKey,
+        token: rentToken,
+        rewardTokenMint,
+        minterRewardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(rentWallets))
+      .signers([minter])
+      .rpc();
+
+    const platformTreasury = Keypair.generate().publicKey;
+    const close = (signer: Keypair, rentRecipient: PublicKey) =>
+      program.methods
+        .closeEscrow()
+        .accounts({
+          escrowLockAccount: rentEscrowLock,
+          minter: signer.publicKey,
+          token: rentToken,
+          rentRecipient,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(rentWallets))
+        .signers([signer])
+        .rpc();
+
+    // Only the minter's signature can pick the recipient
+    try {
+      await close(user, user.publicKey);
+      assert.fail("Should have thrown an error for a non-minter signer");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedMinter");
+    }
+
+    try {
+      await close(minter, rentEscrowLock);
+      assert.fail("Should have thrown an error for the escrow as its own rent recipient");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidRentRecipient");
+    }
+
+    let rent = 0;
+    for (const account of [rentEscrowLock, ...rentWallets]) {
+      rent += await provider.connection.getBalance(account);
+    }
+    const minterBefore = await provider.connection.getBalance(minter.publicKey);
+    await close(minter, platformTreasury);
+
+    // Every closed account's rent lands with the treasury; the minter gets none
+    assert.equal(await provider.connection.getBalance(platformTreasury), rent);
+    assert.equal(await provider.connection.getBalance(minter.publicKey), minterBefore);
+    assert.isNull(await provider.connection.getAccountInfo(rentEscrowLock));
+
+    console.log("✅ Escrow rent returned to the platform treasury");
+  });
+
+  it("Starts a re-lock after close with no redemptions and an empty whitelist", async () => {
+    const rewardValue = new BN(1_000_000);
+    const supply = new BN(1_000_000);
+    const expiryTime = new BN(Math.floor(Date.now() / 1000) + 30 * 24 * 60 * 60);
+
+    const freshToken = await createMint(provider.connection, minter, minter.publicKey, null, 6);
+    const [freshEscrowLock] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_lock"), freshToken.toBuffer(), minter.publicKey.toBuffer()],
+      program.programId
+    );
+    const freshWallets = deriveEscrowWallets(freshToken, minter.publicKey, 5);
+
+    // Whitelisted, with every user capped at the whole supply
+    const lock = async () => {
+      await mintTo(provider.connection, minter, rewardTokenMint, minterRewardAccount, minter, rewardValue.toNumber());
+      await initializeEscrowWallets(freshToken, minter, rewardTokenMint, freshWallets);
+      await program.methods
+        .lockFundsV2(freshToken, rewardTokenMint, minter.publicKey, rewardValue, supply, expiryTime, lockParams({ requiresWhitelist: true, maxPerUser: supply }))
+        .accounts({
+          escrowLockAccount: freshEscrowLock,
+          minter: minter.publicKey,
+          tokenMint: freshToken,
+          rewardTokenMint,
+          minterRewardAccount,
+          treasuryRewardAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(freshWallets))
+        .signers([minter])
+        .rpc();
+
+      const idSeed = await escrowIdSeed(freshEscrowLock);
+      const [redeemerEntry] = PublicKey.findProgramAddressSync(
+        [Buffer.from("redeemer"), freshEscrowLock.toBuffer(), idSeed, minter.publicKey.toBuffer()],
+        program.programId
+      );
+      const [userRedemption] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_redeem"), freshEscrowLock.toBuffer(), idSeed, minter.publicKey.toBuffer()],
+        program.programId
+      );
+      return { redeemerEntry, userRedemption };
+    };
+    const authorize = (redeemerEntry: PublicKey) =>
+      program.methods
+        .authorizeRedeemer(minter.publicKey)
+        .accounts({
+          escrowLockAccount: freshEscrowLock,
+          minter: minter.publicKey,
+          token: freshToken,
+          redeemerEntry,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([minter])
+        .rpc();
+    const redeem = (burnAmount: BN, redeemerEntry: PublicKey | null, userRedemption: PublicKey) =>
+      program.methods
+        .redeemRewards(burnAmount, null)
+        .accounts({
+          escrowLockAccount: freshEscrowLock,
+          user: minter.publicKey,
+          token: freshToken,
+          tokenMint: freshToken,
+          userTokenAccount: minterMainAccount,
+          rewardToken: rewardTokenMint,
+          rewardTokenMint: rewardTokenMint,
+          userRewardAccount: minterRewardAccount,
+          treasuryRewardAccount: null,
+          redeemerEntry,
+          userRedemption,
+          receiptMint: null,
+          userReceiptAccount: null,
+          redemptionLog: null,
+          memoProgram: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(escrowWalletMetas(freshWallets))
+        .signers([minter])
+        .rpc();
+
+    // The first escrow: the minter is whitelisted and burns up to the cap, draining it
+    let minterMainAccount = await mintMainSupply(freshToken, minter, supply);
+    const first = await lock();
+    await authorize(first.redeemerEntry);
+    await redeem(supply, first.redeemerEntry, first.userRedemption);
+    const firstRecord = await program.account.userRedemption.fetch(first.userRedemption);
+    assert.equal(firstRecord.totalBurned.toString(), supply.toString());
+
+    await program.methods
+      .closeEscrow()
+      .accounts({
+        escrowLockAccount: freshEscrowLock,
+        minter: minter.publicKey,
+        token: freshToken,
+        rentRecipient: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(escrowWalletMetas(freshWallets))
+      .signers([minter])
+      .rpc();
+
+    // The re-lock reuses the escrow address, but not its per-lock accounts
+    minterMainAccount = await mintMainSupply(freshToken, minter, supply);
+    const second = await lock();
+    assert.notEqual(second.redeemerEntry.toString(), first.redeemerEntry.toString());
+    assert.notEqual(second.userRedemption.toString(), first.userRedemption.toString());
+    assert.isNull(await provider.connection.getAccountInfo(second.redeemerEntry));
+    assert.isNull(await provider.connection.getAccountInfo(second.userRedemption));
+
+    // The old whitelist entry doesn't admit the minter to the new campaign
+    try {
+      await redeem(new BN(1_000), null, second.userRedemption);
+      assert.fail("Should have thrown an error for a redeemer whitelisted only on the closed escrow");
+    } catch (error) {
+      assert.include(error.toString(), "RedeemerNotAuthorized");
+    }
+
+    // Once authorized again, the minter's cap starts over
+    await authorize(second.redeemerEntry);
+    await redeem(supply.divn(2), second.redeemerEntry, second.userRedemption);
+    const secondRecord = await program.account.userRedemption.fetch(second.userRedemption);
+    assert.equal(secondRecord.totalBurned.toString(), supply.divn(2).toString());
+
+    console.log("✅ Re-locked escrow started with fresh redemption records and whitelist");
+  });
 });